@@ -0,0 +1,36 @@
+//! Lexes query files of 1, 2, 4 and 8 MiB. The throughput stays the same across sizes
+//! when lexing takes linear time.
+//!
+//! Run with `cargo bench --bench lexer`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use daoql::Lexer;
+
+const MEBIBYTE: usize = 1024 * 1024;
+
+/// `example.sql`, with non-ASCII strings and comments, repeated to at least `size` bytes.
+fn source(size: usize) -> String {
+    let chunk = format!(
+        "{}\n-- Étoiles près du pôle céleste\nSELECT 'α Centauri', \"déclinaison\" FROM gaia.source WHERE name = '天狼星';\n",
+        include_str!("../example.sql")
+    );
+    chunk.repeat(size.div_ceil(chunk.len()))
+}
+
+fn lex(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lex");
+    group.sample_size(10);
+    for mebibytes in [1, 2, 4, 8] {
+        let source = source(mebibytes * MEBIBYTE);
+        group.throughput(Throughput::Bytes(source.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("lossless", format!("{mebibytes} MiB")),
+            &source,
+            |b, source| b.iter(|| Lexer::lossless(black_box(source)).count()),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, lex);
+criterion_main!(benches);
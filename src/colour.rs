@@ -0,0 +1,11 @@
+//! ANSI escape sequences shared by syntax highlighting and diagnostics.
+
+pub const RESET: &str = "\x1b[0m";
+pub const BOLD: &str = "\x1b[1m";
+pub const YELLOW: &str = "\x1b[33m";
+pub const BRIGHT_RED: &str = "\x1b[1;31m";
+pub const BRIGHT_BLUE: &str = "\x1b[1;34m";
+pub const MAGENTA: &str = "\x1b[1;35m";
+pub const CYAN: &str = "\x1b[1;36m";
+pub const GRAY: &str = "\x1b[90m";
+pub const UNDERLINED_RED: &str = "\x1b[4;31m";
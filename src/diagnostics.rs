@@ -0,0 +1,325 @@
+//! Rendering of errors tied to a `Span` in the style of rustc:
+//!
+//! ```text
+//! error: expected `FROM`, found identifier
+//!  --> query.sql:1:12
+//!   |
+//! 1 | SELECT a b c
+//!   |            ^ expected `FROM`
+//! ```
+
+use std::fmt::Write;
+
+use crate::colour;
+use crate::engine::{ExecutionError, ExecutionErrorKind};
+use crate::lexer::{LexError, SourceMap, Span};
+use crate::parser::{ParseError, ParseErrorKind};
+
+/// How serious a diagnostic is, which decides its heading and colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// A problem that stops the source from being used.
+    Error,
+    /// A likely mistake that does not stop the source from being used.
+    Warning,
+}
+
+/// A message about a span of the source, built up with the `with_*` methods.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// How serious the problem is.
+    pub severity: Severity,
+    /// The heading, such as ``expected `FROM`, found identifier``.
+    pub message: String,
+    /// The source text the diagnostic is about, which is underlined.
+    pub span: Span,
+    /// Short text printed next to the underline.
+    pub label: Option<String>,
+    /// Suggestions for fixing the problem, printed after the source as `help:` lines.
+    pub help: Vec<String>,
+    /// Background on the problem, printed after the source as `note:` lines.
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    /// An error with no label, help or notes.
+    pub fn error(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            severity: Severity::Error,
+            message: message.into(),
+            span,
+            label: None,
+            help: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    /// Sets the label printed next to the underline.
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Adds a `help:` line.
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help.push(help.into());
+        self
+    }
+
+    /// Adds a `note:` line.
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// The diagnostic for a lex error at `span`, with advice on fixing it.
+    pub fn from_lex_error(error: LexError, span: Span) -> Self {
+        let diagnostic = Diagnostic::error(error.to_string(), span);
+        match error {
+            LexError::InvalidCharacter('!') => diagnostic
+                .with_label("not valid here")
+                .with_help("use `<>` or `!=` for inequality and `NOT` for negation"),
+            LexError::InvalidCharacter(_) => diagnostic.with_label("not valid here"),
+            LexError::UnterminatedString => diagnostic
+                .with_label("string starts here")
+                .with_help("add a closing `'`")
+                .with_note("a quote inside a string literal is written as `''`"),
+            LexError::UnterminatedIdentifier => diagnostic
+                .with_label("identifier starts here")
+                .with_help("add a closing `\"`")
+                .with_note("a quote inside a delimited identifier is written as `\"\"`"),
+            LexError::EmptyIdentifier => diagnostic
+                .with_label("identifier has no name")
+                .with_help("write the name between the quotes"),
+            LexError::UnterminatedComment => diagnostic
+                .with_label("comment starts here")
+                .with_help("add a closing `*/`")
+                .with_note("block comments nest, so every `/*` needs its own `*/`"),
+            LexError::MalformedNumber => diagnostic
+                .with_label("not a valid number")
+                .with_note("numbers look like `12`, `1.5`, `.5`, `6.02E23` or `0x1F`"),
+        }
+    }
+
+    /// The diagnostic for a parse error, with advice on fixing it.
+    pub fn from_parse_error(error: &ParseError) -> Self {
+        match &error.kind {
+            ParseErrorKind::Lex(lex_error) => Diagnostic::from_lex_error(*lex_error, error.span),
+            ParseErrorKind::UnexpectedToken { expected, .. }
+            | ParseErrorKind::UnexpectedEndOfInput { expected } => {
+                Diagnostic::error(error.to_string(), error.span)
+                    .with_label(format!("expected {expected}"))
+            }
+            ParseErrorKind::IntegerOutOfRange => Diagnostic::error(error.to_string(), error.span)
+                .with_label("too large")
+                .with_note(format!("the largest allowed value is {}", i64::MAX)),
+            ParseErrorKind::ChainedComparison => Diagnostic::error(error.to_string(), error.span)
+                .with_label("second comparison")
+                .with_help("combine comparisons with `AND`, e.g. `a < b AND b < c`"),
+            ParseErrorKind::NaturalJoinWithCondition => {
+                Diagnostic::error(error.to_string(), error.span)
+                    .with_label("join condition")
+                    .with_help("remove either `NATURAL` or the join condition")
+                    .with_note("a natural join already joins on every column name both sides share")
+            }
+            ParseErrorKind::UnsupportedStatement(_) => {
+                Diagnostic::error(error.to_string(), error.span).with_label("not supported yet")
+            }
+        }
+    }
+
+    /// The diagnostic for an error raised while executing a statement.
+    pub fn from_execution_error(error: &ExecutionError) -> Self {
+        let diagnostic = Diagnostic::error(error.to_string(), error.span);
+        match &error.kind {
+            ExecutionErrorKind::UnknownTable(_) => diagnostic
+                .with_label("no such table")
+                .with_note("tables are created with `CREATE TABLE` or read from `<name>.csv`"),
+            ExecutionErrorKind::TableExists(_) => diagnostic.with_label("already exists"),
+            ExecutionErrorKind::UnknownColumn(_) => diagnostic.with_label("no such column"),
+            ExecutionErrorKind::AmbiguousColumn(name) => diagnostic
+                .with_label("matches more than one column")
+                .with_help(format!("qualify it with a table name, e.g. `t.{name}`")),
+            ExecutionErrorKind::DuplicateColumn(_) => diagnostic.with_label("named again here"),
+            ExecutionErrorKind::UnknownFunction(_) => diagnostic.with_label("not supported"),
+            ExecutionErrorKind::WrongArgumentCount { .. } => {
+                diagnostic.with_label("wrong number of arguments")
+            }
+            ExecutionErrorKind::MisplacedAggregate => diagnostic
+                .with_label("aggregate")
+                .with_note("aggregates are allowed in the select list, `HAVING` and `ORDER BY`"),
+            ExecutionErrorKind::InvalidValue { .. } => diagnostic.with_label("this value"),
+            ExecutionErrorKind::ColumnCountMismatch { expected, .. } => {
+                diagnostic.with_label(format!("expected {expected} values"))
+            }
+            ExecutionErrorKind::SubqueryNotScalar => diagnostic
+                .with_label("used as a value")
+                .with_help("use `IN` or `EXISTS` to test against many rows"),
+            ExecutionErrorKind::InvalidStatement => diagnostic.with_label("has errors"),
+            ExecutionErrorKind::TypeMismatch(_)
+            | ExecutionErrorKind::Overflow
+            | ExecutionErrorKind::DivisionByZero
+            | ExecutionErrorKind::Csv(_)
+            | ExecutionErrorKind::TransactionAlreadyOpen
+            | ExecutionErrorKind::NoTransaction => diagnostic,
+        }
+    }
+}
+
+/// Renders diagnostics against one source file.
+pub struct Renderer<'a> {
+    file_name: &'a str,
+    source_map: SourceMap<'a>,
+    colour: bool,
+}
+
+impl<'a> Renderer<'a> {
+    /// A renderer for `source`, which is named `file_name` in the output and is coloured
+    /// with ANSI escape sequences if `colour` is set.
+    pub fn new(file_name: &'a str, source: &'a str, colour: bool) -> Self {
+        Self {
+            file_name,
+            source_map: SourceMap::new(source),
+            colour,
+        }
+    }
+
+    /// The diagnostic as text, ending in a newline.
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let (severity, severity_colour) = match diagnostic.severity {
+            Severity::Error => ("error", colour::BRIGHT_RED),
+            Severity::Warning => ("warning", colour::YELLOW),
+        };
+        let (start, end) = self.source_map.span_locations(diagnostic.span);
+        let gutter_width = start.line.to_string().len();
+        let gutter = " ".repeat(gutter_width);
+        let blue = self.paint(colour::BRIGHT_BLUE);
+        let reset = self.paint(colour::RESET);
+
+        let mut output = String::new();
+        let _ = writeln!(
+            output,
+            "{}{severity}{reset}{}: {}{reset}",
+            self.paint(severity_colour),
+            self.paint(colour::BOLD),
+            diagnostic.message,
+        );
+        let _ = writeln!(
+            output,
+            "{gutter}{blue}-->{reset} {}:{}:{}",
+            self.file_name, start.line, start.character_column
+        );
+        let _ = writeln!(output, "{gutter} {blue}|{reset}");
+
+        let line = self.source_map.line_text(start.line).unwrap_or_default();
+        let _ = writeln!(output, "{blue}{}{reset} {blue}|{reset} {line}", start.line);
+
+        // Underline up to the end of the span or the end of its first line
+        let underline_start = start.utf8_column - 1;
+        let underline_end = if end.line == start.line {
+            end.utf8_column - 1
+        } else {
+            line.len()
+        };
+        let underline_end = underline_end.clamp(underline_start, line.len());
+        let padding: String = line[..underline_start.min(line.len())]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let carets = "^".repeat(
+            line[underline_start.min(line.len())..underline_end]
+                .chars()
+                .count()
+                .max(1),
+        );
+        let label = match &diagnostic.label {
+            Some(label) => format!(" {label}"),
+            None => String::new(),
+        };
+        let _ = writeln!(
+            output,
+            "{gutter} {blue}|{reset} {padding}{}{carets}{label}{reset}",
+            self.paint(severity_colour)
+        );
+
+        if !diagnostic.help.is_empty() || !diagnostic.notes.is_empty() {
+            let _ = writeln!(output, "{gutter} {blue}|{reset}");
+        }
+        for help in &diagnostic.help {
+            let _ = writeln!(
+                output,
+                "{gutter} {blue}={reset} {}help{reset}: {help}",
+                self.paint(colour::BOLD)
+            );
+        }
+        for note in &diagnostic.notes {
+            let _ = writeln!(
+                output,
+                "{gutter} {blue}={reset} {}note{reset}: {note}",
+                self.paint(colour::BOLD)
+            );
+        }
+        output
+    }
+
+    fn paint(&self, code: &'static str) -> &'static str {
+        if self.colour {
+            code
+        } else {
+            ""
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Diagnostic, Renderer};
+    use crate::lexer::{LexError, Span};
+    use crate::Script;
+
+    fn render_first_error(source: &str) -> String {
+        let script = Script::parse(source);
+        let diagnostic = Diagnostic::from_parse_error(&script.errors[0]);
+        Renderer::new("query.sql", source, false).render(&diagnostic)
+    }
+
+    #[test]
+    fn columns_count_characters_after_a_multi_byte_string() {
+        assert_eq!(
+            render_first_error("SELECT 'héllo' b c"),
+            "\
+error: expected `FROM`, found identifier
+ --> query.sql:1:18
+  |
+1 | SELECT 'héllo' b c
+  |                  ^ expected `FROM`
+"
+        );
+    }
+
+    #[test]
+    fn columns_count_characters_after_a_multi_byte_identifier() {
+        assert_eq!(
+            render_first_error("SELECT x\nFROM \"天体\" AS t WHERE"),
+            "\
+error: expected an expression, found end of input
+ --> query.sql:2:21
+  |
+2 | FROM \"天体\" AS t WHERE
+  |                     ^ expected an expression
+"
+        );
+    }
+
+    #[test]
+    fn underlines_multi_byte_text_one_caret_per_character() {
+        let source = "SELECT 'ünterminated";
+        let diagnostic = Diagnostic::from_lex_error(LexError::UnterminatedString, Span::new(7, 14));
+        let rendered = Renderer::new("query.sql", source, false).render(&diagnostic);
+        assert!(rendered.contains(" --> query.sql:1:8\n"), "{rendered}");
+        assert!(
+            rendered.contains("  |        ^^^^^^^^^^^^^ string starts here\n"),
+            "{rendered}"
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use std::fmt;
+
+use crate::lexer::Span;
+
+/// An error raised while executing a statement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutionError {
+    /// What went wrong.
+    pub kind: ExecutionErrorKind,
+    /// The part of the statement that caused it.
+    pub span: Span,
+}
+
+/// The kinds of [`ExecutionError`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecutionErrorKind {
+    /// A table that is neither in memory nor a CSV file in the data directory.
+    UnknownTable(String),
+    /// `CREATE TABLE` with the name of an existing table.
+    TableExists(String),
+    /// A column that no table in scope has.
+    UnknownColumn(String),
+    /// A column that more than one table in scope has, and that needs qualifying.
+    AmbiguousColumn(String),
+    /// The same column named twice in `CREATE TABLE` or an `INSERT` column list.
+    DuplicateColumn(String),
+    /// A function that the engine does not implement.
+    UnknownFunction(String),
+    /// A function called with the wrong number of arguments.
+    WrongArgumentCount {
+        /// The name of the function.
+        function: String,
+        /// The accepted number of arguments, such as `"1 or 2 arguments"`.
+        expected: &'static str,
+    },
+    /// An aggregate function outside `SELECT`, `HAVING` and `ORDER BY`, or inside another.
+    MisplacedAggregate,
+    /// An operator or function applied to values of the wrong type.
+    TypeMismatch(String),
+    /// A value that cannot be stored in a column of the declared type.
+    InvalidValue {
+        /// The name of the column.
+        column: String,
+        /// The declared type of the column.
+        expected: &'static str,
+    },
+    /// Integer arithmetic whose result does not fit in an `i64`.
+    Overflow,
+    /// Division or `MOD` by zero.
+    DivisionByZero,
+    /// Rows or column lists whose lengths do not match.
+    ColumnCountMismatch {
+        /// The number of columns needed.
+        expected: usize,
+        /// The number of values given.
+        found: usize,
+    },
+    /// A scalar subquery that returned more than one row or column.
+    SubqueryNotScalar,
+    /// A CSV file that could not be read.
+    Csv(String),
+    /// `BEGIN` inside a transaction.
+    TransactionAlreadyOpen,
+    /// `COMMIT` or `ROLLBACK` outside a transaction.
+    NoTransaction,
+    /// A statement that failed to parse, which cannot be executed.
+    InvalidStatement,
+}
+
+impl fmt::Display for ExecutionErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecutionErrorKind::UnknownTable(name) => write!(f, "table `{name}` does not exist"),
+            ExecutionErrorKind::TableExists(name) => write!(f, "table `{name}` already exists"),
+            ExecutionErrorKind::UnknownColumn(name) => write!(f, "column `{name}` does not exist"),
+            ExecutionErrorKind::AmbiguousColumn(name) => {
+                write!(f, "column reference `{name}` is ambiguous")
+            }
+            ExecutionErrorKind::DuplicateColumn(name) => {
+                write!(f, "column `{name}` is named more than once")
+            }
+            ExecutionErrorKind::UnknownFunction(name) => {
+                write!(f, "function `{name}` is not supported")
+            }
+            ExecutionErrorKind::WrongArgumentCount { function, expected } => {
+                write!(f, "`{function}` takes {expected}")
+            }
+            ExecutionErrorKind::MisplacedAggregate => {
+                write!(f, "aggregate functions are not allowed here")
+            }
+            ExecutionErrorKind::TypeMismatch(message) => write!(f, "{message}"),
+            ExecutionErrorKind::InvalidValue { column, expected } => {
+                write!(f, "column `{column}` only holds {expected} values")
+            }
+            ExecutionErrorKind::Overflow => write!(f, "integer arithmetic overflowed"),
+            ExecutionErrorKind::DivisionByZero => write!(f, "division by zero"),
+            ExecutionErrorKind::ColumnCountMismatch { expected, found } => {
+                write!(f, "expected {expected} values, found {found}")
+            }
+            ExecutionErrorKind::SubqueryNotScalar => {
+                write!(
+                    f,
+                    "subquery used as a value must return one column and at most one row"
+                )
+            }
+            ExecutionErrorKind::Csv(message) => write!(f, "{message}"),
+            ExecutionErrorKind::TransactionAlreadyOpen => {
+                write!(f, "a transaction is already in progress")
+            }
+            ExecutionErrorKind::NoTransaction => write!(f, "no transaction is in progress"),
+            ExecutionErrorKind::InvalidStatement => {
+                write!(f, "statement cannot be run because it failed to parse")
+            }
+        }
+    }
+}
+
+impl fmt::Display for ExecutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)
+    }
+}
+
+impl std::error::Error for ExecutionError {}
@@ -0,0 +1,510 @@
+//! Evaluation of expressions with SQL's three-valued logic, in which comparisons with
+//! `NULL` are unknown rather than false.
+
+use std::cmp::Ordering;
+
+use super::error::{ExecutionError, ExecutionErrorKind};
+use super::functions;
+use super::query::{Executor, Scope};
+use super::value::Value;
+use crate::parser::{
+    BinaryOperator, Expr, FunctionArguments, FunctionCall, Literal, LiteralValue, Query,
+    UnaryOperator,
+};
+use crate::Decimal;
+
+impl Executor<'_> {
+    pub(crate) fn eval(&mut self, expr: &Expr, scope: &Scope) -> Result<Value, ExecutionError> {
+        let error = |kind| ExecutionError {
+            kind,
+            span: expr.span(),
+        };
+        match expr {
+            Expr::Column(name) => scope.lookup(name),
+            Expr::Literal(literal) => Ok(literal_value(literal)),
+            Expr::Unary { op, operand, .. } => {
+                let operand = self.eval(operand, scope)?;
+                unary(*op, operand).map_err(error)
+            }
+            Expr::Binary {
+                left,
+                op: op @ (BinaryOperator::And | BinaryOperator::Or),
+                right,
+                ..
+            } => {
+                let left = truth(&self.eval(left, scope)?).map_err(error)?;
+                // `FALSE AND x` and `TRUE OR x` are decided without `x`
+                let decided = if *op == BinaryOperator::And {
+                    Some(false)
+                } else {
+                    Some(true)
+                };
+                if left == decided {
+                    return Ok(Value::Boolean(left == Some(true)));
+                }
+                let right = truth(&self.eval(right, scope)?).map_err(error)?;
+                Ok(match (left, right) {
+                    (_, right) if right == decided => Value::Boolean(right == Some(true)),
+                    (Some(_), Some(right)) => Value::Boolean(right),
+                    _ => Value::Null,
+                })
+            }
+            Expr::Binary {
+                left, op, right, ..
+            } => {
+                let left = self.eval(left, scope)?;
+                let right = self.eval(right, scope)?;
+                binary(*op, left, right).map_err(error)
+            }
+            Expr::Between {
+                expr: value,
+                negated,
+                low,
+                high,
+                ..
+            } => {
+                let value = self.eval(value, scope)?;
+                let low = self.eval(low, scope)?;
+                let high = self.eval(high, scope)?;
+                let above = binary(BinaryOperator::GreaterThanOrEquals, value.clone(), low);
+                let below = binary(BinaryOperator::LessThanOrEquals, value, high);
+                let between = binary(
+                    BinaryOperator::And,
+                    above.map_err(error)?,
+                    below.map_err(error)?,
+                );
+                Ok(negate(between.map_err(error)?, *negated))
+            }
+            Expr::Like {
+                expr: value,
+                negated,
+                case_insensitive,
+                pattern,
+                escape,
+                ..
+            } => {
+                let value = self.eval(value, scope)?;
+                let pattern = self.eval(pattern, scope)?;
+                let escape = match escape {
+                    Some(escape) => self.eval(escape, scope)?,
+                    None => Value::Text(String::new()),
+                };
+                let (value, pattern, escape) = match (value, pattern, escape) {
+                    (Value::Text(value), Value::Text(pattern), Value::Text(escape)) => {
+                        (value, pattern, escape)
+                    }
+                    (Value::Null, _, _) | (_, Value::Null, _) | (_, _, Value::Null) => {
+                        return Ok(Value::Null)
+                    }
+                    (value, pattern, _) => {
+                        return Err(error(ExecutionErrorKind::TypeMismatch(format!(
+                            "`LIKE` expects text, found {} and {}",
+                            value.type_name(),
+                            pattern.type_name()
+                        ))))
+                    }
+                };
+                let mut escape_chars = escape.chars();
+                let escape = match (escape_chars.next(), escape_chars.next()) {
+                    (escape, None) => escape,
+                    _ => {
+                        return Err(error(ExecutionErrorKind::TypeMismatch(
+                            "the `ESCAPE` of `LIKE` must be a single character".to_string(),
+                        )))
+                    }
+                };
+                let matched = if *case_insensitive {
+                    like(&value.to_lowercase(), &pattern.to_lowercase(), escape)
+                } else {
+                    like(&value, &pattern, escape)
+                };
+                Ok(Value::Boolean(matched != *negated))
+            }
+            Expr::InList {
+                expr: value,
+                negated,
+                list,
+                ..
+            } => {
+                let value = self.eval(value, scope)?;
+                let mut candidates = Vec::with_capacity(list.len());
+                for candidate in list {
+                    candidates.push(self.eval(candidate, scope)?);
+                }
+                Ok(negate(
+                    contains(&value, candidates).map_err(error)?,
+                    *negated,
+                ))
+            }
+            Expr::InSubquery {
+                expr: value,
+                negated,
+                subquery,
+                ..
+            } => {
+                let value = self.eval(value, scope)?;
+                let candidates = self.column(subquery, scope)?;
+                Ok(negate(
+                    contains(&value, candidates).map_err(error)?,
+                    *negated,
+                ))
+            }
+            Expr::IsNull {
+                expr: value,
+                negated,
+                ..
+            } => Ok(Value::Boolean(
+                self.eval(value, scope)?.is_null() != *negated,
+            )),
+            Expr::Exists { subquery, .. } => {
+                let relation = self.query(subquery, Some(scope))?;
+                Ok(Value::Boolean(!relation.rows.is_empty()))
+            }
+            Expr::Case {
+                operand,
+                branches,
+                else_result,
+                ..
+            } => {
+                let operand = match operand {
+                    Some(operand) => Some(self.eval(operand, scope)?),
+                    None => None,
+                };
+                for branch in branches {
+                    let condition = self.eval(&branch.condition, scope)?;
+                    let matched = match &operand {
+                        Some(operand) => {
+                            let ordering = operand.compare(&condition).map_err(|message| {
+                                error(ExecutionErrorKind::TypeMismatch(message))
+                            })?;
+                            ordering == Some(Ordering::Equal)
+                        }
+                        None => truth(&condition).map_err(error)? == Some(true),
+                    };
+                    if matched {
+                        return self.eval(&branch.result, scope);
+                    }
+                }
+                match else_result {
+                    Some(result) => self.eval(result, scope),
+                    None => Ok(Value::Null),
+                }
+            }
+            Expr::Function(call) => self.call(call, scope),
+            Expr::Subquery { query, .. } => {
+                let mut values = self.column(query, scope)?.into_iter();
+                match (values.next(), values.next()) {
+                    (value, None) => Ok(value.unwrap_or(Value::Null)),
+                    _ => Err(error(ExecutionErrorKind::SubqueryNotScalar)),
+                }
+            }
+            Expr::Error(_) => Err(error(ExecutionErrorKind::InvalidStatement)),
+        }
+    }
+
+    /// Whether `expr` is true, treating unknown as false as `WHERE` does.
+    pub(crate) fn condition(&mut self, expr: &Expr, scope: &Scope) -> Result<bool, ExecutionError> {
+        let value = self.eval(expr, scope)?;
+        let truth = truth(&value).map_err(|kind| ExecutionError {
+            kind,
+            span: expr.span(),
+        })?;
+        Ok(truth == Some(true))
+    }
+
+    /// The values of a subquery that returns a single column.
+    fn column(&mut self, query: &Query, scope: &Scope) -> Result<Vec<Value>, ExecutionError> {
+        let relation = self.query(query, Some(scope))?;
+        if relation.columns.len() != 1 {
+            return Err(ExecutionError {
+                kind: ExecutionErrorKind::SubqueryNotScalar,
+                span: query.span,
+            });
+        }
+        Ok(relation.rows.into_iter().flatten().collect())
+    }
+
+    fn call(&mut self, call: &FunctionCall, scope: &Scope) -> Result<Value, ExecutionError> {
+        let error = |kind| ExecutionError {
+            kind,
+            span: call.span,
+        };
+        let name = &call.name.value;
+        let args = match &call.args {
+            FunctionArguments::List(args) => args,
+            FunctionArguments::Wildcard(_) if name.eq_ignore_ascii_case("COUNT") => {
+                return match scope.group {
+                    Some(group) => Ok(Value::Integer(group.len() as i64)),
+                    None => Err(error(ExecutionErrorKind::MisplacedAggregate)),
+                };
+            }
+            FunctionArguments::Wildcard(_) => {
+                return Err(error(ExecutionErrorKind::TypeMismatch(format!(
+                    "only `COUNT` accepts `*`, not `{}`",
+                    name.to_ascii_uppercase()
+                ))))
+            }
+        };
+
+        if !functions::is_aggregate(name) {
+            let mut values = Vec::with_capacity(args.len());
+            for arg in args {
+                values.push(self.eval(arg, scope)?);
+            }
+            return functions::call(name, values).map_err(error);
+        }
+
+        let Some(group) = scope.group else {
+            return Err(error(ExecutionErrorKind::MisplacedAggregate));
+        };
+        let [arg] = args.as_slice() else {
+            return Err(error(ExecutionErrorKind::WrongArgumentCount {
+                function: name.to_ascii_uppercase(),
+                expected: "1 argument",
+            }));
+        };
+        let mut values = Vec::with_capacity(group.len());
+        for row in group {
+            // Without a group here, an aggregate inside the argument is an error
+            let row_scope = Scope::new(scope.columns, row, scope.outer);
+            values.push(self.eval(arg, &row_scope)?);
+        }
+        if call.distinct {
+            values.sort_by(Value::total_cmp);
+            values.dedup_by(|left, right| left.total_cmp(right).is_eq());
+        }
+        functions::aggregate(name, values).map_err(error)
+    }
+}
+
+fn literal_value(literal: &Literal) -> Value {
+    match &literal.value {
+        LiteralValue::Number(text) => {
+            let hexadecimal = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X"));
+            let decimal = match hexadecimal {
+                Some(digits) => Decimal::from_hexadecimal(digits),
+                None => Decimal::parse(text),
+            };
+            let Some(decimal) = decimal else {
+                return Value::Null;
+            };
+            // `1.0` and `1E0` are floats even though their values are whole
+            let exact = hexadecimal.is_some() || !text.contains(['.', 'e', 'E']);
+            match decimal.to_i64() {
+                Some(integer) if exact => Value::Integer(integer),
+                _ => Value::Float(decimal.to_f64()),
+            }
+        }
+        LiteralValue::String(text) => Value::Text(text.clone()),
+        LiteralValue::Boolean(boolean) => Value::Boolean(*boolean),
+        LiteralValue::Null => Value::Null,
+    }
+}
+
+/// The truth value of a condition, `None` being unknown.
+fn truth(value: &Value) -> Result<Option<bool>, ExecutionErrorKind> {
+    match value {
+        Value::Boolean(boolean) => Ok(Some(*boolean)),
+        Value::Null => Ok(None),
+        other => Err(ExecutionErrorKind::TypeMismatch(format!(
+            "expected a boolean condition, found {}",
+            other.type_name()
+        ))),
+    }
+}
+
+fn negate(value: Value, negated: bool) -> Value {
+    match value {
+        Value::Boolean(boolean) => Value::Boolean(boolean != negated),
+        other => other,
+    }
+}
+
+/// `value IN (candidates)`, which is unknown rather than false if there is no match but a
+/// `NULL` is involved.
+fn contains(value: &Value, candidates: Vec<Value>) -> Result<Value, ExecutionErrorKind> {
+    let mut unknown = value.is_null();
+    for candidate in candidates {
+        match value
+            .compare(&candidate)
+            .map_err(ExecutionErrorKind::TypeMismatch)?
+        {
+            Some(Ordering::Equal) => return Ok(Value::Boolean(true)),
+            Some(_) => {}
+            None => unknown = true,
+        }
+    }
+    Ok(if unknown {
+        Value::Null
+    } else {
+        Value::Boolean(false)
+    })
+}
+
+fn unary(op: UnaryOperator, operand: Value) -> Result<Value, ExecutionErrorKind> {
+    match (op, operand) {
+        (_, Value::Null) => Ok(Value::Null),
+        (UnaryOperator::Not, Value::Boolean(boolean)) => Ok(Value::Boolean(!boolean)),
+        (UnaryOperator::Plus, value @ (Value::Integer(_) | Value::Float(_))) => Ok(value),
+        (UnaryOperator::Minus, Value::Integer(integer)) => integer
+            .checked_neg()
+            .map(Value::Integer)
+            .ok_or(ExecutionErrorKind::Overflow),
+        (UnaryOperator::Minus, Value::Float(float)) => Ok(Value::Float(-float)),
+        (UnaryOperator::BitwiseNot, Value::Integer(integer)) => Ok(Value::Integer(!integer)),
+        (op, operand) => Err(ExecutionErrorKind::TypeMismatch(format!(
+            "cannot apply `{op}` to {}",
+            operand.type_name()
+        ))),
+    }
+}
+
+fn binary(op: BinaryOperator, left: Value, right: Value) -> Result<Value, ExecutionErrorKind> {
+    let mismatch = |left: &Value, right: &Value| {
+        ExecutionErrorKind::TypeMismatch(format!(
+            "cannot apply `{op}` to {} and {}",
+            left.type_name(),
+            right.type_name()
+        ))
+    };
+    let ordering = |left: &Value, right: &Value| {
+        left.compare(right)
+            .map_err(ExecutionErrorKind::TypeMismatch)
+    };
+
+    let comparison = match op {
+        BinaryOperator::Equals => Some(ordering(&left, &right)?.map(Ordering::is_eq)),
+        BinaryOperator::NotEquals => Some(ordering(&left, &right)?.map(Ordering::is_ne)),
+        BinaryOperator::LessThan => Some(ordering(&left, &right)?.map(Ordering::is_lt)),
+        BinaryOperator::LessThanOrEquals => Some(ordering(&left, &right)?.map(Ordering::is_le)),
+        BinaryOperator::GreaterThan => Some(ordering(&left, &right)?.map(Ordering::is_gt)),
+        BinaryOperator::GreaterThanOrEquals => Some(ordering(&left, &right)?.map(Ordering::is_ge)),
+        _ => None,
+    };
+    if let Some(comparison) = comparison {
+        return Ok(comparison.map_or(Value::Null, Value::Boolean));
+    }
+
+    match (op, left, right) {
+        (BinaryOperator::And | BinaryOperator::Or, left, right) => {
+            let (left, right) = (truth(&left)?, truth(&right)?);
+            let result = if op == BinaryOperator::And {
+                match (left, right) {
+                    (Some(false), _) | (_, Some(false)) => Some(false),
+                    (Some(true), Some(true)) => Some(true),
+                    _ => None,
+                }
+            } else {
+                match (left, right) {
+                    (Some(true), _) | (_, Some(true)) => Some(true),
+                    (Some(false), Some(false)) => Some(false),
+                    _ => None,
+                }
+            };
+            Ok(result.map_or(Value::Null, Value::Boolean))
+        }
+        (_, Value::Null, _) | (_, _, Value::Null) => Ok(Value::Null),
+        (BinaryOperator::Concatenate, left, right) => Ok(Value::Text(format!("{left}{right}"))),
+        (
+            BinaryOperator::BitwiseAnd | BinaryOperator::BitwiseOr | BinaryOperator::BitwiseXor,
+            Value::Integer(left),
+            Value::Integer(right),
+        ) => Ok(Value::Integer(match op {
+            BinaryOperator::BitwiseAnd => left & right,
+            BinaryOperator::BitwiseOr => left | right,
+            _ => left ^ right,
+        })),
+        (_, Value::Integer(_), Value::Integer(0)) if op == BinaryOperator::Divide => {
+            Err(ExecutionErrorKind::DivisionByZero)
+        }
+        (
+            BinaryOperator::Plus
+            | BinaryOperator::Minus
+            | BinaryOperator::Multiply
+            | BinaryOperator::Divide,
+            Value::Integer(left),
+            Value::Integer(right),
+        ) => {
+            let result = match op {
+                BinaryOperator::Plus => left.checked_add(right),
+                BinaryOperator::Minus => left.checked_sub(right),
+                BinaryOperator::Multiply => left.checked_mul(right),
+                _ => left.checked_div(right),
+            };
+            result
+                .map(Value::Integer)
+                .ok_or(ExecutionErrorKind::Overflow)
+        }
+        (
+            BinaryOperator::Plus
+            | BinaryOperator::Minus
+            | BinaryOperator::Multiply
+            | BinaryOperator::Divide,
+            left,
+            right,
+        ) => {
+            let (Some(x), Some(y)) = (left.as_f64(), right.as_f64()) else {
+                return Err(mismatch(&left, &right));
+            };
+            Ok(Value::Float(match op {
+                BinaryOperator::Plus => x + y,
+                BinaryOperator::Minus => x - y,
+                BinaryOperator::Multiply => x * y,
+                _ if y == 0.0 => return Err(ExecutionErrorKind::DivisionByZero),
+                _ => x / y,
+            }))
+        }
+        (_, left, right) => Err(mismatch(&left, &right)),
+    }
+}
+
+/// Whether `text` matches a `LIKE` pattern, in which `%` matches any run of characters and
+/// `_` any single character unless preceded by `escape`.
+fn like(text: &str, pattern: &str, escape: Option<char>) -> bool {
+    enum Part {
+        Any,
+        One,
+        Char(char),
+    }
+
+    let mut parts = Vec::new();
+    let mut pattern_chars = pattern.chars();
+    while let Some(c) = pattern_chars.next() {
+        parts.push(match c {
+            c if Some(c) == escape => Part::Char(pattern_chars.next().unwrap_or(c)),
+            '%' => Part::Any,
+            '_' => Part::One,
+            c => Part::Char(c),
+        });
+    }
+
+    // Greedy matching that backtracks to the most recent `%`
+    let text: Vec<char> = text.chars().collect();
+    let (mut t, mut p) = (0, 0);
+    let mut backtrack = None;
+    while t < text.len() {
+        match parts.get(p) {
+            Some(Part::Any) => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(Part::One) => {
+                t += 1;
+                p += 1;
+            }
+            Some(Part::Char(c)) if *c == text[t] => {
+                t += 1;
+                p += 1;
+            }
+            _ => match backtrack {
+                Some((any, start)) => {
+                    p = any + 1;
+                    t = start + 1;
+                    backtrack = Some((any, start + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    parts[p..].iter().all(|part| matches!(part, Part::Any))
+}
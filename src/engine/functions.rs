@@ -0,0 +1,234 @@
+//! The scalar and aggregate functions.
+//!
+//! Scalar functions return `NULL` when any argument is `NULL`, apart from `COALESCE` and
+//! `NULLIF`. Aggregates skip `NULL`s and return `NULL` for no values, apart from `COUNT`.
+
+use std::cmp::Ordering;
+
+use super::error::ExecutionErrorKind;
+use super::value::Value;
+
+/// Whether `name` is one of `COUNT`, `SUM`, `AVG`, `MIN` and `MAX`.
+pub(crate) fn is_aggregate(name: &str) -> bool {
+    ["COUNT", "SUM", "AVG", "MIN", "MAX"]
+        .iter()
+        .any(|aggregate| name.eq_ignore_ascii_case(aggregate))
+}
+
+/// Applies the aggregate `name` to the values of a group.
+pub(crate) fn aggregate(name: &str, mut values: Vec<Value>) -> Result<Value, ExecutionErrorKind> {
+    values.retain(|value| !value.is_null());
+    let name = name.to_ascii_uppercase();
+    if name == "COUNT" {
+        return Ok(Value::Integer(values.len() as i64));
+    }
+    let mut values = values.into_iter();
+    let Some(first) = values.next() else {
+        return Ok(Value::Null);
+    };
+    match name.as_str() {
+        "SUM" | "AVG" => {
+            let mut count = 1;
+            let mut sum = number(&name, first)?;
+            for value in values {
+                sum = add(sum, number(&name, value)?)?;
+                count += 1;
+            }
+            if name == "SUM" {
+                return Ok(sum);
+            }
+            Ok(Value::Float(
+                sum.as_f64().unwrap_or_default() / count as f64,
+            ))
+        }
+        _ => {
+            let wanted = if name == "MIN" {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            };
+            let mut best = first;
+            for value in values {
+                let ordering = value
+                    .compare(&best)
+                    .map_err(ExecutionErrorKind::TypeMismatch)?;
+                if ordering == Some(wanted) {
+                    best = value;
+                }
+            }
+            Ok(best)
+        }
+    }
+}
+
+/// Calls the scalar function `name`.
+pub(crate) fn call(name: &str, args: Vec<Value>) -> Result<Value, ExecutionErrorKind> {
+    let name = name.to_ascii_uppercase();
+    let (min, max, count) = match name.as_str() {
+        "PI" => (0, 0, "no arguments"),
+        "ROUND" | "TRUNCATE" => (1, 2, "1 or 2 arguments"),
+        "MOD" | "POWER" | "ATAN2" | "NULLIF" => (2, 2, "2 arguments"),
+        "COALESCE" => (1, usize::MAX, "at least 1 argument"),
+        "ABS" | "CEILING" | "FLOOR" | "SQRT" | "EXP" | "LOG" | "LOG10" | "SIN" | "COS" | "TAN"
+        | "COT" | "ASIN" | "ACOS" | "ATAN" | "DEGREES" | "RADIANS" | "LOWER" | "UPPER"
+        | "CHAR_LENGTH" | "CHARACTER_LENGTH" | "OCTET_LENGTH" => (1, 1, "1 argument"),
+        _ => return Err(ExecutionErrorKind::UnknownFunction(name)),
+    };
+    if args.len() < min || args.len() > max {
+        return Err(arguments(&name, count));
+    }
+
+    match name.as_str() {
+        "COALESCE" => {
+            let found = args.into_iter().find(|value| !value.is_null());
+            return Ok(found.unwrap_or(Value::Null));
+        }
+        "NULLIF" => {
+            let [left, right] = exactly(args);
+            let equal = left
+                .compare(&right)
+                .map_err(ExecutionErrorKind::TypeMismatch)?
+                == Some(Ordering::Equal);
+            return Ok(if equal { Value::Null } else { left });
+        }
+        _ if args.iter().any(Value::is_null) => return Ok(Value::Null),
+        _ => {}
+    }
+
+    match name.as_str() {
+        "ABS" => match exactly(args) {
+            [Value::Integer(x)] => x
+                .checked_abs()
+                .map(Value::Integer)
+                .ok_or(ExecutionErrorKind::Overflow),
+            [x] => Ok(Value::Float(float(&name, &x)?.abs())),
+        },
+        "CEILING" | "FLOOR" => match exactly(args) {
+            [Value::Integer(x)] => Ok(Value::Integer(x)),
+            [x] if name == "CEILING" => Ok(Value::Float(float(&name, &x)?.ceil())),
+            [x] => Ok(Value::Float(float(&name, &x)?.floor())),
+        },
+        "ROUND" | "TRUNCATE" => {
+            let mut args = args.into_iter();
+            let x = args.next().unwrap_or(Value::Null);
+            let places = match args.next() {
+                Some(Value::Integer(places)) => places,
+                Some(other) => return Err(expected(&name, "an integer", &other)),
+                None => 0,
+            };
+            if let Value::Integer(x) = x {
+                if places >= 0 {
+                    return Ok(Value::Integer(x));
+                }
+            }
+            let scale = 10f64.powi(places.clamp(-300, 300) as i32);
+            let x = float(&name, &x)? * scale;
+            let x = if name == "ROUND" {
+                x.round()
+            } else {
+                x.trunc()
+            };
+            Ok(Value::Float(x / scale))
+        }
+        "MOD" => match exactly(args) {
+            [Value::Integer(_), Value::Integer(0)] => Err(ExecutionErrorKind::DivisionByZero),
+            [Value::Integer(x), Value::Integer(y)] => Ok(Value::Integer(x.wrapping_rem(y))),
+            [x, y] => {
+                let (x, y) = (float(&name, &x)?, float(&name, &y)?);
+                if y == 0.0 {
+                    return Err(ExecutionErrorKind::DivisionByZero);
+                }
+                Ok(Value::Float(x % y))
+            }
+        },
+        "POWER" | "ATAN2" => {
+            let [x, y] = exactly(args);
+            let (x, y) = (float(&name, &x)?, float(&name, &y)?);
+            Ok(Value::Float(if name == "POWER" {
+                x.powf(y)
+            } else {
+                x.atan2(y)
+            }))
+        }
+        "PI" => {
+            let [] = exactly(args);
+            Ok(Value::Float(std::f64::consts::PI))
+        }
+        "LOWER" | "UPPER" => match exactly(args) {
+            [Value::Text(text)] if name == "LOWER" => Ok(Value::Text(text.to_lowercase())),
+            [Value::Text(text)] => Ok(Value::Text(text.to_uppercase())),
+            [other] => Err(expected(&name, "text", &other)),
+        },
+        "CHAR_LENGTH" | "CHARACTER_LENGTH" | "OCTET_LENGTH" => match exactly(args) {
+            [Value::Text(text)] if name == "OCTET_LENGTH" => Ok(Value::Integer(text.len() as i64)),
+            [Value::Text(text)] => Ok(Value::Integer(text.chars().count() as i64)),
+            [other] => Err(expected(&name, "text", &other)),
+        },
+        _ => {
+            let function: fn(f64) -> f64 = match name.as_str() {
+                "SQRT" => f64::sqrt,
+                "EXP" => f64::exp,
+                "LOG" => f64::ln,
+                "LOG10" => f64::log10,
+                "SIN" => f64::sin,
+                "COS" => f64::cos,
+                "TAN" => f64::tan,
+                "COT" => |x: f64| x.tan().recip(),
+                "ASIN" => f64::asin,
+                "ACOS" => f64::acos,
+                "ATAN" => f64::atan,
+                "DEGREES" => f64::to_degrees,
+                "RADIANS" => f64::to_radians,
+                _ => unreachable!("every function with one argument is handled"),
+            };
+            let [x] = exactly(args);
+            Ok(Value::Float(function(float(&name, &x)?)))
+        }
+    }
+}
+
+/// Adds two numbers, staying an integer while both are.
+pub(crate) fn add(left: Value, right: Value) -> Result<Value, ExecutionErrorKind> {
+    match (left, right) {
+        (Value::Integer(left), Value::Integer(right)) => left
+            .checked_add(right)
+            .map(Value::Integer)
+            .ok_or(ExecutionErrorKind::Overflow),
+        (left, right) => Ok(Value::Float(
+            left.as_f64().unwrap_or_default() + right.as_f64().unwrap_or_default(),
+        )),
+    }
+}
+
+/// The arguments of a function whose argument count has been checked.
+fn exactly<const N: usize>(args: Vec<Value>) -> [Value; N] {
+    args.try_into()
+        .unwrap_or_else(|_| unreachable!("the argument count is checked first"))
+}
+
+fn number(name: &str, value: Value) -> Result<Value, ExecutionErrorKind> {
+    match value {
+        Value::Integer(_) | Value::Float(_) => Ok(value),
+        other => Err(expected(name, "a number", &other)),
+    }
+}
+
+fn float(name: &str, value: &Value) -> Result<f64, ExecutionErrorKind> {
+    value
+        .as_f64()
+        .ok_or_else(|| expected(name, "a number", value))
+}
+
+fn expected(name: &str, expected: &str, found: &Value) -> ExecutionErrorKind {
+    ExecutionErrorKind::TypeMismatch(format!(
+        "`{name}` expects {expected}, found {}",
+        found.type_name()
+    ))
+}
+
+fn arguments(name: &str, expected: &'static str) -> ExecutionErrorKind {
+    ExecutionErrorKind::WrongArgumentCount {
+        function: name.to_string(),
+        expected,
+    }
+}
@@ -0,0 +1,1009 @@
+//! Execution of statements against tables held in memory.
+//!
+//! Tables come from `CREATE TABLE` or from CSV files in a data directory. A table that is
+//! not in memory is looked for as a file named after it, so `gaia.source` reads
+//! `gaia/source.csv` and `"stars.csv"` reads `stars.csv`. The first record of the file names
+//! the columns, empty fields are `NULL` and numeric fields are numbers. Changes to such a
+//! table stay in memory and are never written back.
+//!
+//! ```
+//! use daoql::engine::{Database, Outcome, Value};
+//! use daoql::Script;
+//!
+//! let script = Script::parse(
+//!     "CREATE TABLE stars (name VARCHAR(20), magnitude REAL);
+//!      INSERT INTO stars VALUES ('Sirius', -1.46), ('Vega', 0.03);
+//!      SELECT name FROM stars WHERE magnitude < 0;",
+//! );
+//! let mut database = Database::new();
+//! let mut outcomes = Vec::new();
+//! for statement in &script.statements {
+//!     outcomes.push(database.execute(&statement.statement).unwrap());
+//! }
+//! let Outcome::Rows(result) = &outcomes[2] else {
+//!     panic!("a query returns rows");
+//! };
+//! assert_eq!(result.rows, [[Value::Text("Sirius".to_string())]]);
+//! ```
+//!
+//! Constraints other than `DEFAULT` are accepted but not enforced, and `DECIMAL` and
+//! `NUMERIC` columns hold floats. A non-aggregated column in a grouped query takes its value
+//! from the first row of the group.
+
+mod csv;
+mod error;
+mod eval;
+mod functions;
+mod query;
+mod result;
+mod table;
+mod value;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub use error::{ExecutionError, ExecutionErrorKind};
+pub use result::ResultSet;
+pub use table::{Column, Table};
+pub use value::Value;
+
+use crate::lexer::Span;
+use crate::parser::visit::{walk_table_reference, Visitor};
+use crate::parser::{
+    ColumnConstraintKind, CreateTable, DataType, Delete, DropTable, Expr, Ident, Insert,
+    InsertSource, ObjectName, Statement, TableReference, TransactionKind, Update,
+};
+use query::{ColumnRef, Executor, Scope};
+use table::names_match;
+
+/// What executing a statement produced.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    /// The rows returned by a query.
+    Rows(ResultSet),
+    /// The number of rows changed by `INSERT`, `UPDATE` or `DELETE`.
+    RowsAffected(usize),
+    /// A statement that returns nothing, such as `CREATE TABLE` or `COMMIT`.
+    Done,
+}
+
+/// A set of tables that statements are executed against.
+#[derive(Debug, Clone, Default)]
+pub struct Database {
+    tables: Vec<Table>,
+    /// Where tables that are not in memory are read from as CSV files.
+    data_directory: Option<PathBuf>,
+    /// The tables as they were at `BEGIN`, restored by `ROLLBACK`.
+    snapshot: Option<Vec<Table>>,
+}
+
+impl Database {
+    /// An empty database with only the tables created in it.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// An empty database that also reads tables from the CSV files in `directory`.
+    pub fn with_data_directory(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            data_directory: Some(directory.into()),
+            ..Self::default()
+        }
+    }
+
+    /// The tables in memory, in the order they were created or read.
+    pub fn tables(&self) -> &[Table] {
+        &self.tables
+    }
+
+    /// Whether a transaction started by `BEGIN` is in progress.
+    pub fn in_transaction(&self) -> bool {
+        self.snapshot.is_some()
+    }
+
+    /// Executes one statement. A statement that fails leaves the tables unchanged.
+    pub fn execute(&mut self, statement: &Statement) -> Result<Outcome, ExecutionError> {
+        self.read_tables(statement)?;
+        match statement {
+            Statement::Query(query) => {
+                let relation = Executor::new(self).query(query, None)?;
+                Ok(Outcome::Rows(ResultSet {
+                    columns: relation
+                        .columns
+                        .into_iter()
+                        .map(|column| column.name)
+                        .collect(),
+                    rows: relation.rows,
+                }))
+            }
+            Statement::CreateTable(create) => self.create_table(create),
+            Statement::DropTable(drop) => self.drop_table(drop),
+            Statement::Insert(insert) => self.insert(insert),
+            Statement::Update(update) => self.update(update),
+            Statement::Delete(delete) => self.delete(delete),
+            Statement::Transaction { kind, span } => self.transaction(*kind, *span),
+            Statement::Error(span) => Err(ExecutionError {
+                kind: ExecutionErrorKind::InvalidStatement,
+                span: *span,
+            }),
+        }
+    }
+
+    /// The table called `name`, which is read from the data directory if it is not in memory.
+    pub fn table(&mut self, name: &ObjectName) -> Result<Option<&Table>, ExecutionError> {
+        self.read_table(name)?;
+        Ok(self.find_table(&name.parts))
+    }
+
+    /// The names of the tables in memory and of the CSV files in the data directory that
+    /// have not been read yet, sorted. A file `a/b.csv` is the table `a.b`, and files more
+    /// than two directories deep are left out, as a name has at most three parts.
+    pub fn catalogue(&self) -> Vec<String> {
+        fn walk(directory: &Path, prefix: &str, depth: usize, names: &mut Vec<String>) {
+            let Ok(entries) = fs::read_dir(directory) else {
+                return;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                    continue;
+                };
+                if path.is_dir() {
+                    if depth < 2 {
+                        walk(&path, &format!("{prefix}{stem}."), depth + 1, names);
+                    }
+                } else if path.extension().is_some_and(|extension| extension == "csv") {
+                    names.push(format!("{prefix}{stem}"));
+                }
+            }
+        }
+
+        let mut names: Vec<String> = self.tables.iter().map(Table::qualified_name).collect();
+        if let Some(directory) = &self.data_directory {
+            let mut files = Vec::new();
+            walk(directory, "", 0, &mut files);
+            files.retain(|file| !names.iter().any(|name| name.eq_ignore_ascii_case(file)));
+            names.extend(files);
+        }
+        names.sort();
+        names
+    }
+
+    pub(crate) fn find_table(&self, name: &[Ident]) -> Option<&Table> {
+        self.tables.iter().find(|table| table.is_named(name))
+    }
+
+    fn table_index(&self, name: &ObjectName) -> Result<usize, ExecutionError> {
+        self.tables
+            .iter()
+            .position(|table| table.is_named(&name.parts))
+            .ok_or_else(|| ExecutionError {
+                kind: ExecutionErrorKind::UnknownTable(name.to_string()),
+                span: name.span,
+            })
+    }
+
+    /// Reads the CSV files of the tables named by `statement` that are not in memory yet.
+    fn read_tables(&mut self, statement: &Statement) -> Result<(), ExecutionError> {
+        #[derive(Default)]
+        struct TableNames<'ast>(Vec<&'ast ObjectName>);
+
+        impl<'ast> Visitor<'ast> for TableNames<'ast> {
+            fn visit_table_reference(&mut self, table: &'ast TableReference) {
+                if let TableReference::Table { name, .. } = table {
+                    self.0.push(name);
+                }
+                walk_table_reference(self, table);
+            }
+        }
+
+        let mut names = TableNames::default();
+        names.visit_statement(statement);
+        match statement {
+            Statement::DropTable(drop) => names.0.extend(&drop.names),
+            Statement::Insert(insert) => names.0.push(&insert.table),
+            Statement::Update(update) => names.0.push(&update.table),
+            Statement::Delete(delete) => names.0.push(&delete.table),
+            _ => {}
+        }
+        names
+            .0
+            .into_iter()
+            .try_for_each(|name| self.read_table(name))
+    }
+
+    /// Reads the CSV file of the table `name` if it exists and the table is not in memory.
+    fn read_table(&mut self, name: &ObjectName) -> Result<(), ExecutionError> {
+        let Some(directory) = &self.data_directory else {
+            return Ok(());
+        };
+        let path = csv_path(directory, name);
+        if self.find_table(&name.parts).is_some() || !path.is_file() {
+            return Ok(());
+        }
+        let error = |message: String| ExecutionError {
+            kind: ExecutionErrorKind::Csv(format!("can't read {}: {message}", path.display())),
+            span: name.span,
+        };
+        let text = fs::read_to_string(&path).map_err(|io| error(io.to_string()))?;
+        let parts = name.parts.iter().map(|part| part.value.clone()).collect();
+        self.tables
+            .push(Table::from_csv(parts, &text).map_err(error)?);
+        Ok(())
+    }
+
+    fn create_table(&mut self, create: &CreateTable) -> Result<Outcome, ExecutionError> {
+        if self.find_table(&create.name.parts).is_some() {
+            return Err(ExecutionError {
+                kind: ExecutionErrorKind::TableExists(create.name.to_string()),
+                span: create.name.span,
+            });
+        }
+        let mut columns: Vec<Column> = Vec::with_capacity(create.columns.len());
+        for definition in &create.columns {
+            if columns
+                .iter()
+                .any(|column| names_match(&definition.name, &column.name))
+            {
+                return Err(ExecutionError {
+                    kind: ExecutionErrorKind::DuplicateColumn(definition.name.value.clone()),
+                    span: definition.name.span,
+                });
+            }
+            let default =
+                definition
+                    .constraints
+                    .iter()
+                    .find_map(|constraint| match &constraint.kind {
+                        ColumnConstraintKind::Default(expr) => Some(expr.clone()),
+                        _ => None,
+                    });
+            columns.push(Column {
+                name: definition.name.value.clone(),
+                data_type: Some(definition.data_type.clone()),
+                default,
+            });
+        }
+        self.tables.push(Table {
+            name: create
+                .name
+                .parts
+                .iter()
+                .map(|part| part.value.clone())
+                .collect(),
+            columns,
+            rows: Vec::new(),
+        });
+        Ok(Outcome::Done)
+    }
+
+    fn drop_table(&mut self, drop: &DropTable) -> Result<Outcome, ExecutionError> {
+        let indexes = drop
+            .names
+            .iter()
+            .map(|name| self.table_index(name))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut index = 0;
+        self.tables.retain(|_| {
+            index += 1;
+            !indexes.contains(&(index - 1))
+        });
+        Ok(Outcome::Done)
+    }
+
+    fn insert(&mut self, insert: &Insert) -> Result<Outcome, ExecutionError> {
+        let index = self.table_index(&insert.table)?;
+        let table = &self.tables[index];
+        let targets: Vec<usize> = if insert.columns.is_empty() {
+            (0..table.columns.len()).collect()
+        } else {
+            let mut targets = Vec::with_capacity(insert.columns.len());
+            for name in &insert.columns {
+                let target = column_index(table, name)?;
+                if targets.contains(&target) {
+                    return Err(ExecutionError {
+                        kind: ExecutionErrorKind::DuplicateColumn(name.value.clone()),
+                        span: name.span,
+                    });
+                }
+                targets.push(target);
+            }
+            targets
+        };
+
+        let mut executor = Executor::new(self);
+        let empty = Scope::new(&[], &[], None);
+        // The value of each target column with the span it came from
+        let mut sources: Vec<Vec<(Value, Span)>> = Vec::new();
+        match &insert.source {
+            InsertSource::Values(rows) => {
+                for row in rows {
+                    if row.len() != targets.len() {
+                        return Err(ExecutionError {
+                            kind: ExecutionErrorKind::ColumnCountMismatch {
+                                expected: targets.len(),
+                                found: row.len(),
+                            },
+                            span: row
+                                .iter()
+                                .map(Expr::span)
+                                .reduce(Span::merge)
+                                .unwrap_or(insert.span),
+                        });
+                    }
+                    let mut values = Vec::with_capacity(row.len());
+                    for expr in row {
+                        values.push((executor.eval(expr, &empty)?, expr.span()));
+                    }
+                    sources.push(values);
+                }
+            }
+            InsertSource::Query(query) => {
+                let relation = executor.query(query, None)?;
+                if relation.columns.len() != targets.len() {
+                    return Err(ExecutionError {
+                        kind: ExecutionErrorKind::ColumnCountMismatch {
+                            expected: targets.len(),
+                            found: relation.columns.len(),
+                        },
+                        span: query.span,
+                    });
+                }
+                for row in relation.rows {
+                    sources.push(row.into_iter().map(|value| (value, query.span)).collect());
+                }
+            }
+        }
+
+        let mut rows = Vec::with_capacity(sources.len());
+        for values in sources {
+            let mut row = Vec::with_capacity(table.columns.len());
+            for (position, column) in table.columns.iter().enumerate() {
+                let (value, span) = match targets.iter().position(|&target| target == position) {
+                    Some(source) => values[source].clone(),
+                    None => match &column.default {
+                        Some(default) => (executor.eval(default, &empty)?, default.span()),
+                        None => (Value::Null, insert.span),
+                    },
+                };
+                row.push(coerce(value, column).map_err(|kind| ExecutionError { kind, span })?);
+            }
+            rows.push(row);
+        }
+        let count = rows.len();
+        self.tables[index].rows.extend(rows);
+        Ok(Outcome::RowsAffected(count))
+    }
+
+    fn update(&mut self, update: &Update) -> Result<Outcome, ExecutionError> {
+        let index = self.table_index(&update.table)?;
+        let table = &self.tables[index];
+        let columns = table_columns(table, &update.table);
+        let assignments = update
+            .assignments
+            .iter()
+            .map(|assignment| Ok((column_index(table, &assignment.column)?, &assignment.value)))
+            .collect::<Result<Vec<_>, ExecutionError>>()?;
+
+        let mut executor = Executor::new(self);
+        let mut changes = Vec::new();
+        for (position, row) in table.rows.iter().enumerate() {
+            let scope = Scope::new(&columns, row, None);
+            if let Some(condition) = &update.where_clause {
+                if !executor.condition(condition, &scope)? {
+                    continue;
+                }
+            }
+            let mut changed = row.clone();
+            for &(column, value) in &assignments {
+                changed[column] = coerce(executor.eval(value, &scope)?, &table.columns[column])
+                    .map_err(|kind| ExecutionError {
+                        kind,
+                        span: value.span(),
+                    })?;
+            }
+            changes.push((position, changed));
+        }
+        let count = changes.len();
+        for (position, row) in changes {
+            self.tables[index].rows[position] = row;
+        }
+        Ok(Outcome::RowsAffected(count))
+    }
+
+    fn delete(&mut self, delete: &Delete) -> Result<Outcome, ExecutionError> {
+        let index = self.table_index(&delete.table)?;
+        let table = &self.tables[index];
+        let columns = table_columns(table, &delete.table);
+        let mut executor = Executor::new(self);
+        let mut keep = Vec::with_capacity(table.rows.len());
+        for row in &table.rows {
+            let deleted = match &delete.where_clause {
+                Some(condition) => {
+                    executor.condition(condition, &Scope::new(&columns, row, None))?
+                }
+                None => true,
+            };
+            keep.push(!deleted);
+        }
+        let count = keep.iter().filter(|keep| !**keep).count();
+        let mut keep = keep.into_iter();
+        self.tables[index]
+            .rows
+            .retain(|_| keep.next().unwrap_or(true));
+        Ok(Outcome::RowsAffected(count))
+    }
+
+    fn transaction(
+        &mut self,
+        kind: TransactionKind,
+        span: Span,
+    ) -> Result<Outcome, ExecutionError> {
+        let error = |kind| ExecutionError { kind, span };
+        match kind {
+            TransactionKind::Begin if self.snapshot.is_some() => {
+                return Err(error(ExecutionErrorKind::TransactionAlreadyOpen))
+            }
+            TransactionKind::Begin => self.snapshot = Some(self.tables.clone()),
+            TransactionKind::Commit => {
+                self.snapshot
+                    .take()
+                    .ok_or(error(ExecutionErrorKind::NoTransaction))?;
+            }
+            TransactionKind::Rollback => {
+                self.tables = self
+                    .snapshot
+                    .take()
+                    .ok_or(error(ExecutionErrorKind::NoTransaction))?;
+            }
+        }
+        Ok(Outcome::Done)
+    }
+}
+
+/// The CSV file that holds the table `name`.
+fn csv_path(directory: &Path, name: &ObjectName) -> PathBuf {
+    let mut path = directory.to_path_buf();
+    let (last, qualifiers) = name
+        .parts
+        .split_last()
+        .expect("an object name has at least one part");
+    path.extend(qualifiers.iter().map(|part| &part.value));
+    if last.value.to_ascii_lowercase().ends_with(".csv") {
+        path.push(&last.value);
+    } else {
+        path.push(format!("{}.csv", last.value));
+    }
+    path
+}
+
+/// The columns of `table` for evaluating `WHERE` and `SET`, qualified by its name.
+fn table_columns(table: &Table, name: &ObjectName) -> Vec<ColumnRef> {
+    let qualifier = &name.parts[name.parts.len() - 1].value;
+    table
+        .columns
+        .iter()
+        .map(|column| ColumnRef {
+            qualifier: Some(qualifier.clone()),
+            name: column.name.clone(),
+        })
+        .collect()
+}
+
+fn column_index(table: &Table, name: &Ident) -> Result<usize, ExecutionError> {
+    table
+        .columns
+        .iter()
+        .position(|column| names_match(name, &column.name))
+        .ok_or_else(|| ExecutionError {
+            kind: ExecutionErrorKind::UnknownColumn(name.value.clone()),
+            span: name.span,
+        })
+}
+
+/// Converts `value` to the type of `column`, or fails if it cannot be stored there.
+fn coerce(value: Value, column: &Column) -> Result<Value, ExecutionErrorKind> {
+    let invalid = |expected| ExecutionErrorKind::InvalidValue {
+        column: column.name.clone(),
+        expected,
+    };
+    let Some(data_type) = &column.data_type else {
+        return Ok(value);
+    };
+    if value.is_null() {
+        return Ok(value);
+    }
+    match data_type {
+        DataType::Smallint | DataType::Integer => match value {
+            Value::Integer(_) => Ok(value),
+            Value::Float(float) if float.fract() == 0.0 && float.abs() < 9.2e18 => {
+                Ok(Value::Integer(float as i64))
+            }
+            _ => Err(invalid("integer")),
+        },
+        DataType::Real
+        | DataType::DoublePrecision
+        | DataType::Float(_)
+        | DataType::Decimal { .. }
+        | DataType::Numeric { .. } => value
+            .as_f64()
+            .map(Value::Float)
+            .ok_or_else(|| invalid("numeric")),
+        DataType::Char(_)
+        | DataType::Varchar(_)
+        | DataType::Date
+        | DataType::Time
+        | DataType::Timestamp => match value {
+            Value::Text(_) => Ok(value),
+            _ => Err(invalid("text")),
+        },
+        DataType::Custom(name) if name.value.eq_ignore_ascii_case("BOOLEAN") => match value {
+            Value::Boolean(_) => Ok(value),
+            _ => Err(invalid("boolean")),
+        },
+        DataType::Custom(_) => Ok(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{Database, ExecutionErrorKind, Outcome};
+    use crate::Script;
+
+    const STARS: &str = "
+        CREATE TABLE stars (id INTEGER, name VARCHAR(20), magnitude REAL, constellation CHAR(3));
+        INSERT INTO stars VALUES
+            (1, 'Sirius', -1.46, 'CMa'), (2, 'Canopus', -0.74, 'Car'), (3, 'Vega', 0.03, 'Lyr'),
+            (4, 'Adhara', 1.5, 'CMa'), (5, 'Unnamed', NULL, NULL);
+        CREATE TABLE constellations (abbreviation CHAR(3), name VARCHAR(20));
+        INSERT INTO constellations VALUES ('CMa', 'Canis Major'), ('Lyr', 'Lyra'), ('Ori', 'Orion');";
+
+    fn stars() -> Database {
+        let mut database = Database::new();
+        for outcome in execute(&mut database, STARS) {
+            outcome.unwrap();
+        }
+        database
+    }
+
+    /// The outcome of each statement of `source`, stopping after the first error.
+    fn execute(
+        database: &mut Database,
+        source: &str,
+    ) -> Vec<Result<Outcome, (ExecutionErrorKind, String)>> {
+        let script = Script::parse(source);
+        assert!(script.errors.is_empty(), "{source}: {:?}", script.errors);
+        let mut outcomes = Vec::new();
+        for statement in &script.statements {
+            let outcome = database
+                .execute(&statement.statement)
+                .map_err(|error| (error.kind, source[error.span.range()].to_string()));
+            let failed = outcome.is_err();
+            outcomes.push(outcome);
+            if failed {
+                break;
+            }
+        }
+        outcomes
+    }
+
+    /// The rows of the last statement of `source` run against [`STARS`], as CSV.
+    fn query(source: &str) -> String {
+        match execute(&mut stars(), source).pop() {
+            Some(Ok(Outcome::Rows(result))) => result.to_csv(),
+            outcome => panic!("{source}: {outcome:?}"),
+        }
+    }
+
+    /// The error that running `source` against [`STARS`] stops at, with the text it points
+    /// at.
+    fn error(source: &str) -> (ExecutionErrorKind, String) {
+        match execute(&mut stars(), source).pop() {
+            Some(Err(error)) => error,
+            outcome => panic!("{source}: {outcome:?}"),
+        }
+    }
+
+    #[test]
+    fn where_order_by_offset_and_top() {
+        assert_eq!(
+            query("SELECT name FROM stars WHERE magnitude < 1 ORDER BY magnitude DESC"),
+            "name\nVega\nCanopus\nSirius\n"
+        );
+        assert_eq!(
+            query("SELECT TOP 2 id FROM stars ORDER BY constellation, id DESC OFFSET 1"),
+            "id\n1\n2\n"
+        );
+        assert_eq!(
+            query("SELECT DISTINCT constellation FROM stars WHERE constellation IS NOT NULL ORDER BY 1"),
+            "constellation\nCMa\nCar\nLyr\n"
+        );
+    }
+
+    #[test]
+    fn predicates_and_null_logic() {
+        assert_eq!(
+            query("SELECT id FROM stars WHERE magnitude BETWEEN -1 AND 1 OR name LIKE 'S%' ORDER BY id"),
+            "id\n1\n2\n3\n"
+        );
+        assert_eq!(
+            query("SELECT id FROM stars WHERE NOT magnitude > 0 OR magnitude IS NULL ORDER BY id"),
+            "id\n1\n2\n5\n"
+        );
+        assert_eq!(
+            query("SELECT id FROM stars WHERE constellation IN ('CMa', 'Lyr') ORDER BY id"),
+            "id\n1\n3\n4\n"
+        );
+        assert_eq!(
+            query("SELECT NULL = NULL, 1 = 1 AND NULL, 1 = 1 OR NULL FROM stars WHERE id = 1"),
+            "NULL = NULL,1 = 1 AND NULL,1 = 1 OR NULL\n,,TRUE\n"
+        );
+    }
+
+    #[test]
+    fn expressions_and_functions() {
+        assert_eq!(
+            query(
+                "SELECT 7 / 2, 7.0 / 2, -id * 2 + 1, name || '!', UPPER(name), \
+                 ABS(magnitude), ROUND(magnitude, 1), COALESCE(NULL, 'x'), MOD(7, 3) \
+                 FROM stars WHERE id = 1"
+            ),
+            "7 / 2,7.0 / 2,-id * 2 + 1,name || '!',UPPER(name),ABS(magnitude),\
+             \"ROUND(magnitude, 1)\",\"COALESCE(NULL, 'x')\",\"MOD(7, 3)\"\n\
+             3,3.5,-1,Sirius!,SIRIUS,1.46,-1.5,x,1\n"
+        );
+        assert_eq!(
+            query(
+                "SELECT CASE WHEN magnitude < 0 THEN 'bright' WHEN magnitude < 1 THEN 'visible' \
+                 ELSE 'faint' END, CASE constellation WHEN 'CMa' THEN 1 END FROM stars ORDER BY id"
+            ),
+            "CASE WHEN magnitude < 0 THEN 'bright' WHEN magnitude < 1 THEN 'visible' \
+             ELSE 'faint' END,CASE constellation WHEN 'CMa' THEN 1 END\n\
+             bright,1\nbright,\nvisible,\nfaint,1\nfaint,\n"
+        );
+    }
+
+    #[test]
+    fn aggregates_and_grouping() {
+        assert_eq!(
+            query(
+                "SELECT constellation, COUNT(*), COUNT(magnitude), MIN(magnitude), MAX(name) \
+                 FROM stars GROUP BY constellation HAVING COUNT(*) > 1 ORDER BY constellation"
+            ),
+            "constellation,COUNT(*),COUNT(magnitude),MIN(magnitude),MAX(name)\nCMa,2,2,-1.46,Sirius\n"
+        );
+        assert_eq!(
+            query("SELECT COUNT(*), COUNT(DISTINCT constellation), SUM(id), AVG(id) FROM stars"),
+            "COUNT(*),COUNT(DISTINCT constellation),SUM(id),AVG(id)\n5,3,15,3.0\n"
+        );
+        assert_eq!(
+            query("SELECT COUNT(*), SUM(id) FROM stars WHERE id > 10"),
+            "COUNT(*),SUM(id)\n0,\n"
+        );
+    }
+
+    #[test]
+    fn joins() {
+        let join = |kind: &str| {
+            query(&format!(
+                "SELECT s.name, c.name FROM stars AS s {kind} JOIN constellations c \
+                 ON s.constellation = c.abbreviation ORDER BY s.id, c.name"
+            ))
+        };
+        assert_eq!(
+            join("INNER"),
+            "name,name\nSirius,Canis Major\nVega,Lyra\nAdhara,Canis Major\n"
+        );
+        assert_eq!(
+            join("LEFT OUTER"),
+            "name,name\nSirius,Canis Major\nCanopus,\nVega,Lyra\nAdhara,Canis Major\nUnnamed,\n"
+        );
+        assert_eq!(
+            join("RIGHT"),
+            "name,name\nSirius,Canis Major\nVega,Lyra\nAdhara,Canis Major\n,Orion\n"
+        );
+        assert_eq!(join("FULL"), "name,name\nSirius,Canis Major\nCanopus,\nVega,Lyra\nAdhara,Canis Major\nUnnamed,\n,Orion\n");
+        assert_eq!(
+            query("SELECT COUNT(*) FROM stars CROSS JOIN constellations"),
+            "COUNT(*)\n15\n"
+        );
+        assert_eq!(
+            query(
+                "SELECT s.id, c.abbreviation FROM stars s, constellations c \
+                 WHERE s.constellation = c.abbreviation AND c.name = 'Lyra'"
+            ),
+            "id,abbreviation\n3,Lyr\n"
+        );
+    }
+
+    #[test]
+    fn natural_joins_and_using() {
+        let mut database = stars();
+        let source = "
+            CREATE TABLE aliases (id INTEGER, common VARCHAR(20));
+            INSERT INTO aliases VALUES (1, 'Dog Star'), (3, 'Alpha Lyrae');
+            SELECT id, name, common FROM stars NATURAL JOIN aliases ORDER BY id;
+            SELECT id, common FROM stars JOIN aliases USING (id) WHERE magnitude > 0;";
+        let outcomes = execute(&mut database, source);
+        let csv: Vec<String> = outcomes
+            .into_iter()
+            .filter_map(|outcome| match outcome.unwrap() {
+                Outcome::Rows(result) => Some(result.to_csv()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            csv,
+            [
+                "id,name,common\n1,Sirius,Dog Star\n3,Vega,Alpha Lyrae\n",
+                "id,common\n3,Alpha Lyrae\n"
+            ]
+        );
+    }
+
+    #[test]
+    fn subqueries() {
+        assert_eq!(
+            query(
+                "SELECT name FROM stars WHERE constellation IN \
+                 (SELECT abbreviation FROM constellations WHERE name LIKE 'L%')"
+            ),
+            "name\nVega\n"
+        );
+        assert_eq!(
+            query(
+                "SELECT abbreviation FROM constellations c WHERE NOT EXISTS \
+                 (SELECT 1 FROM stars s WHERE s.constellation = c.abbreviation)"
+            ),
+            "abbreviation\nOri\n"
+        );
+        assert_eq!(
+            query("SELECT name FROM stars WHERE magnitude = (SELECT MIN(magnitude) FROM stars)"),
+            "name\nSirius\n"
+        );
+        assert_eq!(
+            query(
+                "SELECT b.name FROM (SELECT name, magnitude FROM stars WHERE magnitude < 0) AS b \
+                 ORDER BY b.magnitude DESC"
+            ),
+            "name\nCanopus\nSirius\n"
+        );
+    }
+
+    #[test]
+    fn set_operations_and_common_table_expressions() {
+        let set = |op: &str| {
+            query(&format!(
+                "SELECT constellation FROM stars {op} SELECT abbreviation FROM constellations"
+            ))
+        };
+        assert_eq!(set("UNION"), "constellation\nCMa\nCar\nLyr\n\nOri\n");
+        assert_eq!(
+            set("UNION ALL"),
+            "constellation\nCMa\nCar\nLyr\nCMa\n\nCMa\nLyr\nOri\n"
+        );
+        assert_eq!(set("INTERSECT"), "constellation\nCMa\nLyr\n");
+        assert_eq!(set("EXCEPT"), "constellation\nCar\n\n");
+        assert_eq!(
+            query(
+                "WITH bright (star, mag) AS (SELECT name, magnitude FROM stars WHERE magnitude < 0) \
+                 SELECT star FROM bright ORDER BY mag"
+            ),
+            "star\nSirius\nCanopus\n"
+        );
+    }
+
+    #[test]
+    fn statements_report_what_they_changed() {
+        let mut database = stars();
+        let source = "
+            INSERT INTO stars (id, name) VALUES (6, 'Rigel'), (7, 'Deneb');
+            INSERT INTO stars SELECT id + 10, name, magnitude, constellation FROM stars WHERE id < 3;
+            UPDATE stars SET magnitude = magnitude + 1 WHERE constellation = 'CMa';
+            DELETE FROM stars WHERE id > 10;
+            CREATE TABLE t (a INTEGER DEFAULT 7, b VARCHAR(5));
+            INSERT INTO t (b) VALUES ('x');
+            SELECT * FROM t;
+            DROP TABLE t;";
+        let outcomes: Vec<Outcome> = execute(&mut database, source)
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(outcomes.len(), 8);
+        assert_eq!(outcomes[..4], [2, 2, 3, 2].map(Outcome::RowsAffected));
+        assert_eq!(outcomes[4], Outcome::Done);
+        let Outcome::Rows(result) = &outcomes[6] else {
+            panic!("{:?}", outcomes[6]);
+        };
+        assert_eq!(result.to_csv(), "a,b\n7,x\n");
+        assert_eq!(outcomes[7], Outcome::Done);
+        assert_eq!(database.tables().len(), 2);
+    }
+
+    #[test]
+    fn rollback_restores_the_tables_and_commit_keeps_them() {
+        let mut database = stars();
+        let source = "BEGIN; DELETE FROM stars; DROP TABLE constellations; ROLLBACK;";
+        assert!(execute(&mut database, source)
+            .into_iter()
+            .all(|outcome| outcome.is_ok()));
+        assert!(!database.in_transaction());
+        assert_eq!(database.tables().len(), 2);
+        assert_eq!(database.tables()[0].rows.len(), 5);
+
+        let source = "BEGIN; DELETE FROM stars WHERE id > 1; COMMIT;";
+        assert!(execute(&mut database, source)
+            .into_iter()
+            .all(|outcome| outcome.is_ok()));
+        assert_eq!(database.tables()[0].rows.len(), 1);
+    }
+
+    #[test]
+    fn tables_are_read_from_csv_files() {
+        let directory = std::env::temp_dir().join(format!("daoql-engine-{}", std::process::id()));
+        fs::create_dir_all(directory.join("gaia")).unwrap();
+        fs::write(
+            directory.join("gaia/source.csv"),
+            "source_id,ra,name\n1,266.4,\"Sgr A*, the centre\"\n2,10.5,\n",
+        )
+        .unwrap();
+        fs::write(directory.join("bad.csv"), "a,b\n1\n").unwrap();
+
+        let mut database = Database::with_data_directory(&directory);
+        let results = execute(
+            &mut database,
+            "SELECT source_id, ra * 2, name FROM gaia.source WHERE name IS NOT NULL;
+             DELETE FROM gaia.source; SELECT COUNT(*) FROM gaia.source;
+             SELECT * FROM \"bad.csv\"",
+        );
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(results.len(), 4);
+        let Ok(Outcome::Rows(first)) = &results[0] else {
+            panic!("{:?}", results[0]);
+        };
+        assert_eq!(
+            first.to_csv(),
+            "source_id,ra * 2,name\n1,532.8,\"Sgr A*, the centre\"\n"
+        );
+        let Ok(Outcome::Rows(count)) = &results[2] else {
+            panic!("{:?}", results[2]);
+        };
+        assert_eq!(count.to_csv(), "COUNT(*)\n0\n");
+        assert!(
+            matches!(&results[3], Err((ExecutionErrorKind::Csv(_), text)) if text == "\"bad.csv\""),
+            "{:?}",
+            results[3]
+        );
+    }
+
+    #[test]
+    fn each_error_kind_points_at_its_cause() {
+        use ExecutionErrorKind::*;
+        let name = |name: &str| name.to_string();
+        let cases = [
+            (
+                "SELECT * FROM planets",
+                UnknownTable(name("planets")),
+                "planets",
+            ),
+            (
+                "CREATE TABLE stars (a INTEGER)",
+                TableExists(name("stars")),
+                "stars",
+            ),
+            (
+                "SELECT colour FROM stars",
+                UnknownColumn(name("colour")),
+                "colour",
+            ),
+            (
+                "SELECT name FROM stars, constellations",
+                AmbiguousColumn(name("name")),
+                "name",
+            ),
+            (
+                "CREATE TABLE t (a INTEGER, a REAL)",
+                DuplicateColumn(name("a")),
+                "a",
+            ),
+            (
+                "INSERT INTO stars (id, id) VALUES (1, 2)",
+                DuplicateColumn(name("id")),
+                "id",
+            ),
+            (
+                "SELECT SINH(id) FROM stars",
+                UnknownFunction(name("SINH")),
+                "SINH(id)",
+            ),
+            (
+                "SELECT ROUND() FROM stars",
+                WrongArgumentCount {
+                    function: name("ROUND"),
+                    expected: "1 or 2 arguments",
+                },
+                "ROUND()",
+            ),
+            (
+                "SELECT id FROM stars WHERE COUNT(*) > 1",
+                MisplacedAggregate,
+                "COUNT(*)",
+            ),
+            (
+                "SELECT MAX(MIN(id)) FROM stars",
+                MisplacedAggregate,
+                "MIN(id)",
+            ),
+            (
+                "SELECT name + 1 FROM stars",
+                TypeMismatch(name("cannot apply `+` to text and integer")),
+                "name + 1",
+            ),
+            (
+                "INSERT INTO stars (id) VALUES ('one')",
+                InvalidValue {
+                    column: name("id"),
+                    expected: "integer",
+                },
+                "'one'",
+            ),
+            (
+                "SELECT 9223372036854775807 + id FROM stars",
+                Overflow,
+                "9223372036854775807 + id",
+            ),
+            ("SELECT id / 0 FROM stars", DivisionByZero, "id / 0"),
+            ("SELECT MOD(id, 0) FROM stars", DivisionByZero, "MOD(id, 0)"),
+            (
+                "INSERT INTO stars VALUES (1, 'x')",
+                ColumnCountMismatch {
+                    expected: 4,
+                    found: 2,
+                },
+                "1, 'x'",
+            ),
+            (
+                "SELECT id FROM stars UNION SELECT id, name FROM stars",
+                ColumnCountMismatch {
+                    expected: 1,
+                    found: 2,
+                },
+                "SELECT id FROM stars UNION SELECT id, name FROM stars",
+            ),
+            (
+                "SELECT (SELECT id FROM stars) FROM stars",
+                SubqueryNotScalar,
+                "(SELECT id FROM stars)",
+            ),
+            ("BEGIN; BEGIN", TransactionAlreadyOpen, "BEGIN"),
+            ("COMMIT", NoTransaction, "COMMIT"),
+            ("ROLLBACK", NoTransaction, "ROLLBACK"),
+        ];
+        for (source, kind, text) in cases {
+            assert_eq!(error(source), (kind, text.to_string()), "{source}");
+        }
+    }
+
+    #[test]
+    fn statements_that_failed_to_parse_are_not_run() {
+        let script = Script::parse("SELECT FROM stars WHERE");
+        let error = stars()
+            .execute(&script.statements[0].statement)
+            .unwrap_err();
+        assert_eq!(error.kind, ExecutionErrorKind::InvalidStatement);
+    }
+
+    #[test]
+    fn a_failed_statement_changes_nothing() {
+        let mut database = stars();
+        let outcomes = execute(&mut database, "UPDATE stars SET id = id / (id - 3)");
+        assert!(matches!(
+            outcomes[0],
+            Err((ExecutionErrorKind::DivisionByZero, _))
+        ));
+        assert_eq!(database.tables()[0].rows, stars().tables()[0].rows);
+    }
+}
@@ -0,0 +1,674 @@
+//! Evaluation of queries into relations.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::error::{ExecutionError, ExecutionErrorKind};
+use super::functions;
+use super::table::names_match;
+use super::value::{RowKey, Value};
+use super::Database;
+use crate::parser::visit::{walk_function_call, Visitor};
+use crate::parser::{
+    Expr, FunctionCall, Ident, Join, JoinConstraint, JoinKind, LiteralValue, ObjectName,
+    OrderByItem, Query, Select, SelectItem, SetExpr, SetOperator, SortDirection, TableReference,
+};
+
+/// A column of an intermediate result.
+#[derive(Debug, Clone)]
+pub(crate) struct ColumnRef {
+    /// The table name or alias that qualified references match, if any.
+    pub(crate) qualifier: Option<String>,
+    pub(crate) name: String,
+}
+
+/// The rows of a table, join or query while a statement runs.
+#[derive(Debug, Clone)]
+pub(crate) struct Relation {
+    pub(crate) columns: Vec<ColumnRef>,
+    pub(crate) rows: Vec<Vec<Value>>,
+}
+
+/// The columns an expression can refer to: the row being evaluated, the rows of its group
+/// when aggregating, and the rows of the enclosing queries for correlated subqueries.
+pub(crate) struct Scope<'s> {
+    pub(crate) columns: &'s [ColumnRef],
+    pub(crate) row: &'s [Value],
+    pub(crate) group: Option<&'s [Vec<Value>]>,
+    pub(crate) outer: Option<&'s Scope<'s>>,
+}
+
+impl<'s> Scope<'s> {
+    pub(crate) fn new(
+        columns: &'s [ColumnRef],
+        row: &'s [Value],
+        outer: Option<&'s Scope<'s>>,
+    ) -> Self {
+        Scope {
+            columns,
+            row,
+            group: None,
+            outer,
+        }
+    }
+
+    /// The value of the column `name`, looked up from the innermost query outwards.
+    pub(crate) fn lookup(&self, name: &ObjectName) -> Result<Value, ExecutionError> {
+        let error = |kind| ExecutionError {
+            kind,
+            span: name.span,
+        };
+        let (column, qualifiers) = name
+            .parts
+            .split_last()
+            .expect("an object name has at least one part");
+        let qualifier = qualifiers.last();
+
+        let mut scope = Some(self);
+        while let Some(current) = scope {
+            let mut found = current.columns.iter().enumerate().filter(|(_, candidate)| {
+                names_match(column, &candidate.name)
+                    && qualifier.is_none_or(|qualifier| {
+                        candidate
+                            .qualifier
+                            .as_ref()
+                            .is_some_and(|candidate| names_match(qualifier, candidate))
+                    })
+            });
+            if let Some((index, _)) = found.next() {
+                if found.next().is_some() {
+                    return Err(error(ExecutionErrorKind::AmbiguousColumn(name.to_string())));
+                }
+                return Ok(current.row[index].clone());
+            }
+            scope = current.outer;
+        }
+        Err(error(ExecutionErrorKind::UnknownColumn(name.to_string())))
+    }
+}
+
+/// Runs queries against the tables of a database.
+pub(crate) struct Executor<'a> {
+    database: &'a Database,
+    /// The common table expressions in scope, innermost last.
+    ctes: Vec<(String, Relation)>,
+}
+
+/// What a select list item evaluates to.
+enum Projection<'a> {
+    /// Copies of the source columns at these positions, from `*` or `t.*`.
+    Columns(Vec<usize>),
+    Expr(&'a Expr),
+}
+
+impl<'a> Executor<'a> {
+    pub(crate) fn new(database: &'a Database) -> Self {
+        Executor {
+            database,
+            ctes: Vec::new(),
+        }
+    }
+
+    pub(crate) fn query(
+        &mut self,
+        query: &Query,
+        outer: Option<&Scope>,
+    ) -> Result<Relation, ExecutionError> {
+        let ctes_in_scope = self.ctes.len();
+        let result = self.query_with_ctes(query, outer);
+        self.ctes.truncate(ctes_in_scope);
+        result
+    }
+
+    fn query_with_ctes(
+        &mut self,
+        query: &Query,
+        outer: Option<&Scope>,
+    ) -> Result<Relation, ExecutionError> {
+        for cte in &query.with {
+            let mut relation = self.query(&cte.query, outer)?;
+            if !cte.columns.is_empty() {
+                if cte.columns.len() != relation.columns.len() {
+                    return Err(ExecutionError {
+                        kind: ExecutionErrorKind::ColumnCountMismatch {
+                            expected: cte.columns.len(),
+                            found: relation.columns.len(),
+                        },
+                        span: cte.span,
+                    });
+                }
+                for (column, name) in relation.columns.iter_mut().zip(&cte.columns) {
+                    column.name.clone_from(&name.value);
+                }
+            }
+            self.ctes.push((cte.name.value.clone(), relation));
+        }
+
+        let (relation, keys, top) = match &query.body {
+            SetExpr::Select(select) => {
+                let (relation, keys) = self.select(select, &query.order_by, outer)?;
+                (relation, keys, select.top)
+            }
+            body => {
+                let relation = self.set_expr(body, outer)?;
+                let keys = relation
+                    .rows
+                    .iter()
+                    .map(|row| self.order_keys(&query.order_by, &relation.columns, row, None))
+                    .collect::<Result<_, _>>()?;
+                (relation, keys, None)
+            }
+        };
+
+        let mut rows: Vec<(Vec<Value>, Vec<Value>)> = relation.rows.into_iter().zip(keys).collect();
+        if !query.order_by.is_empty() {
+            rows.sort_by(|(_, left), (_, right)| compare_keys(&query.order_by, left, right));
+        }
+        let offset = query.offset.map_or(0, |offset| offset.value);
+        let top = top.map_or(u64::MAX, |top| top.value);
+        let rows = rows
+            .into_iter()
+            .skip(usize::try_from(offset).unwrap_or(usize::MAX))
+            .take(usize::try_from(top).unwrap_or(usize::MAX))
+            .map(|(row, _)| row)
+            .collect();
+        Ok(Relation {
+            columns: relation.columns,
+            rows,
+        })
+    }
+
+    fn set_expr(
+        &mut self,
+        set_expr: &SetExpr,
+        outer: Option<&Scope>,
+    ) -> Result<Relation, ExecutionError> {
+        let (op, all, left, right, span) = match set_expr {
+            SetExpr::Select(select) => {
+                let (mut relation, _) = self.select(select, &[], outer)?;
+                if let Some(top) = select.top {
+                    relation
+                        .rows
+                        .truncate(usize::try_from(top.value).unwrap_or(usize::MAX));
+                }
+                return Ok(relation);
+            }
+            SetExpr::Query(query) => return self.query(query, outer),
+            SetExpr::SetOperation {
+                op,
+                all,
+                left,
+                right,
+                span,
+            } => (*op, *all, left, right, *span),
+        };
+
+        let left = self.set_expr(left, outer)?;
+        let right = self.set_expr(right, outer)?;
+        if left.columns.len() != right.columns.len() {
+            return Err(ExecutionError {
+                kind: ExecutionErrorKind::ColumnCountMismatch {
+                    expected: left.columns.len(),
+                    found: right.columns.len(),
+                },
+                span,
+            });
+        }
+
+        let mut right_counts: BTreeMap<RowKey, usize> = BTreeMap::new();
+        if op != SetOperator::Union {
+            for row in &right.rows {
+                *right_counts.entry(RowKey(row.clone())).or_default() += 1;
+            }
+        }
+        let mut rows = Vec::new();
+        for row in left.rows {
+            let keep = match right_counts.get_mut(&RowKey(row.clone())) {
+                Some(count) if *count > 0 => {
+                    if all {
+                        *count -= 1;
+                    }
+                    op == SetOperator::Intersect
+                }
+                _ => op != SetOperator::Intersect,
+            };
+            if keep {
+                rows.push(row);
+            }
+        }
+        if op == SetOperator::Union {
+            rows.extend(right.rows);
+        }
+        if !all {
+            rows = distinct(rows, |row| row);
+        }
+        Ok(Relation {
+            columns: left.columns,
+            rows,
+        })
+    }
+
+    /// Runs a `SELECT` without its `TOP`, returning its rows with the values of `order_by`
+    /// for each.
+    fn select(
+        &mut self,
+        select: &Select,
+        order_by: &[OrderByItem],
+        outer: Option<&Scope>,
+    ) -> Result<(Relation, Vec<Vec<Value>>), ExecutionError> {
+        let source = self.from(&select.from, outer)?;
+        let mut rows = Vec::new();
+        for row in source.rows {
+            if let Some(condition) = &select.where_clause {
+                if !self.condition(condition, &Scope::new(&source.columns, &row, outer))? {
+                    continue;
+                }
+            }
+            rows.push(row);
+        }
+
+        let mut columns = Vec::new();
+        let mut projections = Vec::new();
+        for item in &select.items {
+            match item {
+                SelectItem::Wildcard(_) => {
+                    columns.extend(source.columns.iter().map(|column| column.name.clone()));
+                    projections.push(Projection::Columns((0..source.columns.len()).collect()));
+                }
+                SelectItem::QualifiedWildcard(name, span) => {
+                    let qualifier = name.parts.last().expect("an object name has a part");
+                    let positions: Vec<usize> = (0..source.columns.len())
+                        .filter(|&index| {
+                            source.columns[index]
+                                .qualifier
+                                .as_ref()
+                                .is_some_and(|candidate| names_match(qualifier, candidate))
+                        })
+                        .collect();
+                    if positions.is_empty() {
+                        return Err(ExecutionError {
+                            kind: ExecutionErrorKind::UnknownTable(name.to_string()),
+                            span: *span,
+                        });
+                    }
+                    columns.extend(
+                        positions
+                            .iter()
+                            .map(|&index| source.columns[index].name.clone()),
+                    );
+                    projections.push(Projection::Columns(positions));
+                }
+                SelectItem::Expression { expr, alias, .. } => {
+                    columns.push(match (alias, expr) {
+                        (Some(alias), _) => alias.value.clone(),
+                        (None, Expr::Column(name)) => {
+                            name.parts[name.parts.len() - 1].value.clone()
+                        }
+                        (None, expr) => expr.to_string(),
+                    });
+                    projections.push(Projection::Expr(expr));
+                }
+                SelectItem::Error(span) => {
+                    return Err(ExecutionError {
+                        kind: ExecutionErrorKind::InvalidStatement,
+                        span: *span,
+                    })
+                }
+            }
+        }
+        let columns: Vec<ColumnRef> = columns
+            .into_iter()
+            .map(|name| ColumnRef {
+                qualifier: None,
+                name,
+            })
+            .collect();
+
+        let aggregating = !select.group_by.is_empty()
+            || select.having.is_some()
+            || projections
+                .iter()
+                .any(|projection| matches!(projection, Projection::Expr(expr) if contains_aggregate(expr)))
+            || order_by.iter().any(|item| contains_aggregate(&item.expr));
+
+        let mut output = Vec::new();
+        if aggregating {
+            let groups: Vec<Vec<Vec<Value>>> = if select.group_by.is_empty() {
+                vec![rows]
+            } else {
+                let mut groups: BTreeMap<RowKey, Vec<Vec<Value>>> = BTreeMap::new();
+                for row in rows {
+                    let scope = Scope::new(&source.columns, &row, outer);
+                    let key = select
+                        .group_by
+                        .iter()
+                        .map(|expr| self.eval(expr, &scope))
+                        .collect::<Result<_, _>>()?;
+                    groups.entry(RowKey(key)).or_default().push(row);
+                }
+                groups.into_values().collect()
+            };
+            // A query with aggregates but no rows still has one group, for `COUNT(*) = 0`
+            let null_row = vec![Value::Null; source.columns.len()];
+            for group in &groups {
+                let scope = Scope {
+                    columns: &source.columns,
+                    row: group.first().unwrap_or(&null_row),
+                    group: Some(group),
+                    outer,
+                };
+                if let Some(having) = &select.having {
+                    if !self.condition(having, &scope)? {
+                        continue;
+                    }
+                }
+                output.push(self.project(&projections, &columns, order_by, &scope)?);
+            }
+        } else {
+            for row in &rows {
+                let scope = Scope::new(&source.columns, row, outer);
+                output.push(self.project(&projections, &columns, order_by, &scope)?);
+            }
+        }
+
+        if select.distinct {
+            output = distinct(output, |(row, _)| row);
+        }
+        let (rows, keys) = output.into_iter().unzip();
+        Ok((Relation { columns, rows }, keys))
+    }
+
+    /// Evaluates the select list and the `ORDER BY` keys for one row or group.
+    fn project(
+        &mut self,
+        projections: &[Projection],
+        columns: &[ColumnRef],
+        order_by: &[OrderByItem],
+        scope: &Scope,
+    ) -> Result<(Vec<Value>, Vec<Value>), ExecutionError> {
+        let mut row = Vec::with_capacity(columns.len());
+        for projection in projections {
+            match projection {
+                Projection::Columns(positions) => {
+                    row.extend(positions.iter().map(|&index| scope.row[index].clone()));
+                }
+                Projection::Expr(expr) => row.push(self.eval(expr, scope)?),
+            }
+        }
+        let keys = self.order_keys(order_by, columns, &row, Some(scope))?;
+        Ok((row, keys))
+    }
+
+    /// The sort keys of an output row. An item names an output column by position or name,
+    /// or is otherwise evaluated against the source row in `scope`.
+    fn order_keys(
+        &mut self,
+        order_by: &[OrderByItem],
+        columns: &[ColumnRef],
+        row: &[Value],
+        scope: Option<&Scope>,
+    ) -> Result<Vec<Value>, ExecutionError> {
+        let mut keys = Vec::with_capacity(order_by.len());
+        for item in order_by {
+            let position = match &item.expr {
+                Expr::Literal(literal) => match &literal.value {
+                    LiteralValue::Number(text) => {
+                        let position = text
+                            .parse::<usize>()
+                            .ok()
+                            .filter(|position| (1..=columns.len()).contains(position));
+                        let Some(position) = position else {
+                            return Err(ExecutionError {
+                                kind: ExecutionErrorKind::UnknownColumn(text.clone()),
+                                span: literal.span,
+                            });
+                        };
+                        Some(position - 1)
+                    }
+                    _ => None,
+                },
+                Expr::Column(name) if name.parts.len() == 1 => columns
+                    .iter()
+                    .position(|column| names_match(&name.parts[0], &column.name)),
+                _ => None,
+            };
+            keys.push(match (position, scope) {
+                (Some(position), _) => row[position].clone(),
+                (None, Some(scope)) => self.eval(&item.expr, scope)?,
+                (None, None) => {
+                    return Err(ExecutionError {
+                        kind: ExecutionErrorKind::UnknownColumn(item.expr.to_string()),
+                        span: item.expr.span(),
+                    })
+                }
+            });
+        }
+        Ok(keys)
+    }
+
+    /// The cross product of the tables of a `FROM` clause.
+    fn from(
+        &mut self,
+        tables: &[TableReference],
+        outer: Option<&Scope>,
+    ) -> Result<Relation, ExecutionError> {
+        let mut result = Relation {
+            columns: Vec::new(),
+            rows: vec![Vec::new()],
+        };
+        for table in tables {
+            let relation = self.table_reference(table, outer)?;
+            let mut rows = Vec::with_capacity(result.rows.len() * relation.rows.len());
+            for left in &result.rows {
+                for right in &relation.rows {
+                    rows.push(left.iter().chain(right).cloned().collect());
+                }
+            }
+            result.columns.extend(relation.columns);
+            result.rows = rows;
+        }
+        Ok(result)
+    }
+
+    fn table_reference(
+        &mut self,
+        table: &TableReference,
+        outer: Option<&Scope>,
+    ) -> Result<Relation, ExecutionError> {
+        let (mut relation, qualifier) = match table {
+            TableReference::Table { name, alias, .. } => {
+                let qualifier = alias.as_ref().unwrap_or(&name.parts[name.parts.len() - 1]);
+                (self.named_table(name)?, qualifier)
+            }
+            TableReference::Derived { query, alias, .. } => (self.query(query, outer)?, alias),
+            TableReference::Join(join) => return self.join(join, outer),
+            TableReference::Error(span) => {
+                return Err(ExecutionError {
+                    kind: ExecutionErrorKind::InvalidStatement,
+                    span: *span,
+                })
+            }
+        };
+        for column in &mut relation.columns {
+            column.qualifier = Some(qualifier.value.clone());
+        }
+        Ok(relation)
+    }
+
+    /// A common table expression in scope or a table of the database.
+    fn named_table(&self, name: &ObjectName) -> Result<Relation, ExecutionError> {
+        if let [part] = name.parts.as_slice() {
+            let cte = self
+                .ctes
+                .iter()
+                .rev()
+                .find(|(cte, _)| names_match(part, cte));
+            if let Some((_, relation)) = cte {
+                return Ok(relation.clone());
+            }
+        }
+        let Some(table) = self.database.find_table(&name.parts) else {
+            return Err(ExecutionError {
+                kind: ExecutionErrorKind::UnknownTable(name.to_string()),
+                span: name.span,
+            });
+        };
+        Ok(Relation {
+            columns: table
+                .columns
+                .iter()
+                .map(|column| ColumnRef {
+                    qualifier: None,
+                    name: column.name.clone(),
+                })
+                .collect(),
+            rows: table.rows.clone(),
+        })
+    }
+
+    /// A nested loop join. Columns joined by `USING` or `NATURAL` appear once, taking the
+    /// value from whichever side has one.
+    fn join(&mut self, join: &Join, outer: Option<&Scope>) -> Result<Relation, ExecutionError> {
+        let left = self.table_reference(&join.left, outer)?;
+        let right = self.table_reference(&join.right, outer)?;
+
+        let shared: Vec<(usize, usize)> = match &join.constraint {
+            JoinConstraint::Using(names) => names
+                .iter()
+                .map(|name| Ok((position(&left, name)?, position(&right, name)?)))
+                .collect::<Result<_, ExecutionError>>()?,
+            JoinConstraint::Natural => {
+                left.columns
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(left_index, column)| {
+                        let right_index = right.columns.iter().position(|candidate| {
+                            candidate.name.eq_ignore_ascii_case(&column.name)
+                        })?;
+                        Some((left_index, right_index))
+                    })
+                    .collect()
+            }
+            JoinConstraint::On(_) | JoinConstraint::None => Vec::new(),
+        };
+        let is_shared = |index: usize| shared.iter().any(|&(_, right_index)| right_index == index);
+        let combine = |left_row: &[Value], right_row: &[Value]| -> Vec<Value> {
+            let mut row = left_row.to_vec();
+            for &(left_index, right_index) in &shared {
+                if row[left_index].is_null() {
+                    row[left_index] = right_row[right_index].clone();
+                }
+            }
+            row.extend(
+                right_row
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| !is_shared(*index))
+                    .map(|(_, value)| value.clone()),
+            );
+            row
+        };
+        let all_columns: Vec<ColumnRef> =
+            left.columns.iter().chain(&right.columns).cloned().collect();
+
+        let mut rows = Vec::new();
+        let mut right_matched = vec![false; right.rows.len()];
+        for left_row in &left.rows {
+            let mut matched = false;
+            for (right_index, right_row) in right.rows.iter().enumerate() {
+                let is_match = match &join.constraint {
+                    JoinConstraint::On(condition) => {
+                        let row: Vec<Value> = left_row.iter().chain(right_row).cloned().collect();
+                        self.condition(condition, &Scope::new(&all_columns, &row, outer))?
+                    }
+                    _ => shared.iter().all(|&(left_index, right_index)| {
+                        left_row[left_index].compare(&right_row[right_index])
+                            == Ok(Some(Ordering::Equal))
+                    }),
+                };
+                if is_match {
+                    matched = true;
+                    right_matched[right_index] = true;
+                    rows.push(combine(left_row, right_row));
+                }
+            }
+            if !matched && matches!(join.kind, JoinKind::Left | JoinKind::Full) {
+                rows.push(combine(left_row, &vec![Value::Null; right.columns.len()]));
+            }
+        }
+        if matches!(join.kind, JoinKind::Right | JoinKind::Full) {
+            let null_row = vec![Value::Null; left.columns.len()];
+            for (right_row, matched) in right.rows.iter().zip(right_matched) {
+                if !matched {
+                    rows.push(combine(&null_row, right_row));
+                }
+            }
+        }
+
+        let mut columns = left.columns;
+        columns.extend(
+            right
+                .columns
+                .into_iter()
+                .enumerate()
+                .filter(|(index, _)| !is_shared(*index))
+                .map(|(_, column)| column),
+        );
+        Ok(Relation { columns, rows })
+    }
+}
+
+/// The position of the column `name` in `relation`, for `USING`.
+fn position(relation: &Relation, name: &Ident) -> Result<usize, ExecutionError> {
+    relation
+        .columns
+        .iter()
+        .position(|column| names_match(name, &column.name))
+        .ok_or_else(|| ExecutionError {
+            kind: ExecutionErrorKind::UnknownColumn(name.value.clone()),
+            span: name.span,
+        })
+}
+
+/// Orders rows by their `ORDER BY` keys, with `NULL`s last when ascending.
+fn compare_keys(order_by: &[OrderByItem], left: &[Value], right: &[Value]) -> Ordering {
+    order_by
+        .iter()
+        .zip(left.iter().zip(right))
+        .map(|(item, (left, right))| match item.direction {
+            Some(SortDirection::Descending) => right.total_cmp(left),
+            _ => left.total_cmp(right),
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Removes the items whose rows repeat an earlier one, keeping the first.
+fn distinct<T>(items: Vec<T>, row: fn(&T) -> &Vec<Value>) -> Vec<T> {
+    let mut seen = BTreeSet::new();
+    items
+        .into_iter()
+        .filter(|item| seen.insert(RowKey(row(item).clone())))
+        .collect()
+}
+
+/// Whether `expr` calls an aggregate function outside of a subquery.
+fn contains_aggregate(expr: &Expr) -> bool {
+    struct Finder(bool);
+
+    impl<'ast> Visitor<'ast> for Finder {
+        fn visit_function_call(&mut self, call: &'ast FunctionCall) {
+            if functions::is_aggregate(&call.name.value) {
+                self.0 = true;
+            } else {
+                walk_function_call(self, call);
+            }
+        }
+
+        fn visit_query(&mut self, _query: &'ast Query) {}
+    }
+
+    let mut finder = Finder(false);
+    finder.visit_expr(expr);
+    finder.0
+}
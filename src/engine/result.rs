@@ -0,0 +1,89 @@
+use std::fmt;
+
+use super::csv;
+use super::value::Value;
+
+/// The rows returned by a query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultSet {
+    /// The names of the columns, in order.
+    pub columns: Vec<String>,
+    /// The rows, each with one value per column.
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl ResultSet {
+    /// The result as CSV with a header record. `NULL`s are empty fields.
+    pub fn to_csv(&self) -> String {
+        let mut output = String::new();
+        csv::write_record(&mut output, self.columns.iter().map(String::as_str));
+        for row in &self.rows {
+            let fields: Vec<String> = row
+                .iter()
+                .map(|value| match value {
+                    Value::Null => String::new(),
+                    value => value.to_string(),
+                })
+                .collect();
+            csv::write_record(&mut output, fields.iter().map(String::as_str));
+        }
+        output
+    }
+}
+
+/// Writes an aligned table with numbers on the right, followed by the number of rows:
+///
+/// ```text
+///  id | name
+/// ----+------
+///   1 | Jane
+/// (1 row)
+/// ```
+impl fmt::Display for ResultSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| row.iter().map(Value::to_string).collect())
+            .collect();
+        let widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                cells
+                    .iter()
+                    .map(|row| row[index].chars().count())
+                    .chain([name.chars().count()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let header: Vec<String> = self
+            .columns
+            .iter()
+            .zip(&widths)
+            .map(|(name, width)| format!(" {name:<width$} "))
+            .collect();
+        writeln!(f, "{}", header.join("|").trim_end())?;
+        let rule: Vec<String> = widths.iter().map(|width| "-".repeat(width + 2)).collect();
+        writeln!(f, "{}", rule.join("+"))?;
+        for (row, values) in cells.iter().zip(&self.rows) {
+            let line: Vec<String> = row
+                .iter()
+                .zip(values)
+                .zip(&widths)
+                .map(|((cell, value), width)| match value {
+                    Value::Integer(_) | Value::Float(_) => format!(" {cell:>width$} "),
+                    _ => format!(" {cell:<width$} "),
+                })
+                .collect();
+            writeln!(f, "{}", line.join("|").trim_end())?;
+        }
+        match self.rows.len() {
+            1 => write!(f, "(1 row)"),
+            count => write!(f, "({count} rows)"),
+        }
+    }
+}
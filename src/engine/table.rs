@@ -0,0 +1,89 @@
+use super::csv;
+use super::value::Value;
+use crate::parser::{DataType, Expr, Ident};
+
+/// A table held in memory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    /// The parts of the qualified name, such as `["gaia", "source"]`.
+    pub name: Vec<String>,
+    /// The columns, in order.
+    pub columns: Vec<Column>,
+    /// The rows, each with one value per column.
+    pub rows: Vec<Vec<Value>>,
+}
+
+/// A column of a [`Table`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    /// The name of the column.
+    pub name: String,
+    /// The declared type, or `None` for the untyped columns of a CSV file.
+    pub data_type: Option<DataType>,
+    /// The value given to the column when an `INSERT` leaves it out, if not `NULL`.
+    pub default: Option<Expr>,
+}
+
+impl Table {
+    /// A table read from CSV text, whose first record names the columns.
+    pub(crate) fn from_csv(name: Vec<String>, text: &str) -> Result<Table, String> {
+        let mut records = csv::parse(text)?.into_iter();
+        let header = records
+            .next()
+            .ok_or("the file is empty, so it has no header")?;
+        let rows = records
+            .enumerate()
+            .map(|(index, record)| {
+                if record.len() != header.len() {
+                    return Err(format!(
+                        "record {} has {} fields, but the header has {}",
+                        index + 2,
+                        record.len(),
+                        header.len()
+                    ));
+                }
+                Ok(record
+                    .iter()
+                    .map(|field| Value::from_csv_field(field))
+                    .collect())
+            })
+            .collect::<Result<_, _>>()?;
+        let columns = header
+            .into_iter()
+            .map(|name| Column {
+                name,
+                data_type: None,
+                default: None,
+            })
+            .collect();
+        Ok(Table {
+            name,
+            columns,
+            rows,
+        })
+    }
+
+    /// The name as written in a query, with its parts joined by `.`.
+    pub fn qualified_name(&self) -> String {
+        self.name.join(".")
+    }
+
+    /// Whether `name` refers to this table.
+    pub(crate) fn is_named(&self, name: &[Ident]) -> bool {
+        name.len() == self.name.len()
+            && name
+                .iter()
+                .zip(&self.name)
+                .all(|(part, table_part)| names_match(part, table_part))
+    }
+}
+
+/// Whether the identifier refers to `name`. Delimited identifiers are case-sensitive and
+/// regular identifiers are not.
+pub(crate) fn names_match(ident: &Ident, name: &str) -> bool {
+    if ident.quoted {
+        ident.value == name
+    } else {
+        ident.value.eq_ignore_ascii_case(name)
+    }
+}
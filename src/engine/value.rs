@@ -0,0 +1,145 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+/// A value held in a table or produced by an expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// `NULL`, the unknown value.
+    Null,
+    /// A whole number.
+    Integer(i64),
+    /// A floating-point number.
+    Float(f64),
+    /// A character string.
+    Text(String),
+    /// `TRUE` or `FALSE`.
+    Boolean(bool),
+}
+
+impl Value {
+    /// The name of the type of the value, as used in error messages.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::Integer(_) => "integer",
+            Value::Float(_) => "float",
+            Value::Text(_) => "text",
+            Value::Boolean(_) => "boolean",
+        }
+    }
+
+    /// Whether the value is `NULL`.
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    /// The value of a CSV field: empty is `NULL`, and numbers become integers or floats.
+    pub(crate) fn from_csv_field(field: &str) -> Value {
+        if field.is_empty() {
+            return Value::Null;
+        }
+        if let Ok(integer) = field.parse() {
+            return Value::Integer(integer);
+        }
+        // Guard against Rust's `inf` and `NaN`, which are text in a CSV file
+        let numeric = field.starts_with(|c: char| c.is_ascii_digit() || "+-.".contains(c));
+        match field.parse() {
+            Ok(float) if numeric => Value::Float(float),
+            _ => Value::Text(field.to_string()),
+        }
+    }
+
+    /// The value as an `f64`, if it is a number.
+    pub(crate) fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Integer(integer) => Some(*integer as f64),
+            Value::Float(float) => Some(*float),
+            _ => None,
+        }
+    }
+
+    /// Compares two values with SQL semantics: `None` if either is `NULL`, and an error
+    /// message if the types cannot be compared.
+    pub(crate) fn compare(&self, other: &Value) -> Result<Option<Ordering>, String> {
+        match (self, other) {
+            (Value::Null, _) | (_, Value::Null) => Ok(None),
+            (Value::Integer(left), Value::Integer(right)) => Ok(Some(left.cmp(right))),
+            (Value::Text(left), Value::Text(right)) => Ok(Some(left.cmp(right))),
+            (Value::Boolean(left), Value::Boolean(right)) => Ok(Some(left.cmp(right))),
+            _ => match (self.as_f64(), other.as_f64()) {
+                (Some(left), Some(right)) => Ok(left.partial_cmp(&right)),
+                _ => Err(format!(
+                    "cannot compare {} with {}",
+                    self.type_name(),
+                    other.type_name()
+                )),
+            },
+        }
+    }
+
+    /// A total order for sorting, grouping and removing duplicates, in which `NULL`s are
+    /// equal to each other and sort after everything else.
+    pub(crate) fn total_cmp(&self, other: &Value) -> Ordering {
+        fn rank(value: &Value) -> u8 {
+            match value {
+                Value::Boolean(_) => 0,
+                Value::Integer(_) | Value::Float(_) => 1,
+                Value::Text(_) => 2,
+                Value::Null => 3,
+            }
+        }
+
+        match (self, other) {
+            (Value::Integer(left), Value::Integer(right)) => left.cmp(right),
+            (Value::Text(left), Value::Text(right)) => left.cmp(right),
+            (Value::Boolean(left), Value::Boolean(right)) => left.cmp(right),
+            _ => match (self.as_f64(), other.as_f64()) {
+                (Some(left), Some(right)) => left.total_cmp(&right),
+                _ => rank(self).cmp(&rank(other)),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "NULL"),
+            Value::Integer(integer) => write!(f, "{integer}"),
+            // `Debug` keeps the decimal point of whole numbers, so `3.0` stays a float
+            Value::Float(float) => write!(f, "{float:?}"),
+            Value::Text(text) => write!(f, "{text}"),
+            Value::Boolean(true) => write!(f, "TRUE"),
+            Value::Boolean(false) => write!(f, "FALSE"),
+        }
+    }
+}
+
+/// A row compared with [`Value::total_cmp`], for use as a map key.
+#[derive(Debug, Clone)]
+pub(crate) struct RowKey(pub(crate) Vec<Value>);
+
+impl PartialEq for RowKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for RowKey {}
+
+impl PartialOrd for RowKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RowKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .iter()
+            .zip(&other.0)
+            .map(|(left, right)| left.total_cmp(right))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| self.0.len().cmp(&other.0.len()))
+    }
+}
@@ -0,0 +1,852 @@
+//! An opinionated ADQL formatter working on the token stream, so comments survive.
+//!
+//! Keywords are uppercased and every clause starts a line of its own. Lists such as the
+//! select list are kept on one line while they fit in [`MAX_WIDTH`] columns, and are
+//! otherwise broken into one item per line. Joins each get a line under `FROM`, with their
+//! `ON` and `USING` conditions aligned. Subqueries are indented one level past the line they
+//! open on. The layout depends only on the tokens and comments, so formatting is idempotent.
+
+use std::mem;
+
+use crate::lexer::{Keyword, LexError, Lexer, Span, Token, TokenKind};
+
+/// Lines are broken once they would grow past this many columns.
+pub const MAX_WIDTH: usize = 80;
+const INDENT: usize = 4;
+
+/// Formats a whole source, or returns its lex errors if it does not lex cleanly.
+pub fn format(source: &str) -> Result<String, Vec<(LexError, Span)>> {
+    let Items { items, dangling } = collect_items(source)?;
+    let nodes = build_tree(&mut items.into_iter(), false).0;
+    let statements = split_statements(nodes);
+
+    let mut writer = Writer::new(source, false);
+    for (index, statement) in statements.iter().enumerate() {
+        if index > 0 {
+            writer.newline(0);
+            if statement
+                .first_item()
+                .is_some_and(|item| item.blank_line_before)
+            {
+                writer.newline(0);
+            }
+        }
+        writer.write_query(&statement.nodes);
+        if let Some(semicolon) = &statement.semicolon {
+            writer.write_item(semicolon);
+        }
+    }
+    for comment in &dangling {
+        if !writer.output.is_empty() {
+            writer.newline(0);
+        }
+        writer.output.push_str(comment.text(source));
+    }
+
+    let mut output = writer.output;
+    let trimmed = output.trim_end().len();
+    output.truncate(trimmed);
+    if !output.is_empty() {
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// A significant token together with the comments around it.
+struct Item {
+    token: Token,
+    /// Comments on the lines before the token.
+    leading: Vec<Token>,
+    /// Comments after the token on the same line.
+    trailing: Vec<Token>,
+    /// Whether a blank line separates the item, including its leading comments, from the
+    /// token before it.
+    blank_line_before: bool,
+}
+
+enum Node {
+    Token(Item),
+    Group {
+        open: Item,
+        children: Vec<Node>,
+        close: Option<Item>,
+    },
+}
+
+impl Node {
+    fn first_item(&self) -> &Item {
+        match self {
+            Node::Token(item) => item,
+            Node::Group { open, .. } => open,
+        }
+    }
+
+    fn keyword(&self) -> Option<Keyword> {
+        match self {
+            Node::Token(item) => match item.token.kind {
+                TokenKind::Keyword(keyword) => Some(keyword),
+                _ => None,
+            },
+            Node::Group { .. } => None,
+        }
+    }
+}
+
+struct Statement {
+    nodes: Vec<Node>,
+    semicolon: Option<Item>,
+}
+
+impl Statement {
+    fn first_item(&self) -> Option<&Item> {
+        self.nodes
+            .first()
+            .map(Node::first_item)
+            .or(self.semicolon.as_ref())
+    }
+}
+
+/// The significant tokens of a source with their comments attached.
+struct Items {
+    items: Vec<Item>,
+    /// Comments after the last token.
+    dangling: Vec<Token>,
+}
+
+fn collect_items(source: &str) -> Result<Items, Vec<(LexError, Span)>> {
+    let mut items: Vec<Item> = Vec::new();
+    let mut errors = Vec::new();
+    let mut comments = Vec::new();
+    let mut blank_line_before = false;
+    let mut newlines = 0;
+
+    for token in Lexer::lossless(source) {
+        match token.kind {
+            TokenKind::Error(error) => errors.push((error, token.span)),
+            TokenKind::Whitespace => {
+                newlines += token.text(source).matches('\n').count();
+            }
+            TokenKind::InlineComment | TokenKind::BlockComment => {
+                match items.last_mut() {
+                    Some(last) if newlines == 0 && comments.is_empty() => last.trailing.push(token),
+                    _ => {
+                        if comments.is_empty() {
+                            blank_line_before = newlines >= 2;
+                        }
+                        comments.push(token);
+                    }
+                }
+                newlines = 0;
+            }
+            _ => {
+                if comments.is_empty() {
+                    blank_line_before = newlines >= 2;
+                }
+                items.push(Item {
+                    token,
+                    leading: mem::take(&mut comments),
+                    trailing: Vec::new(),
+                    blank_line_before,
+                });
+                newlines = 0;
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(Items {
+            items,
+            dangling: comments,
+        })
+    } else {
+        Err(errors)
+    }
+}
+
+/// Groups parenthesised tokens. Returns the closing parenthesis when `nested`.
+fn build_tree(items: &mut impl Iterator<Item = Item>, nested: bool) -> (Vec<Node>, Option<Item>) {
+    let mut nodes = Vec::new();
+    while let Some(item) = items.next() {
+        match item.token.kind {
+            TokenKind::LeftParenthesis => {
+                let (children, close) = build_tree(items, true);
+                nodes.push(Node::Group {
+                    open: item,
+                    children,
+                    close,
+                });
+            }
+            TokenKind::RightParenthesis if nested => return (nodes, Some(item)),
+            _ => nodes.push(Node::Token(item)),
+        }
+    }
+    (nodes, None)
+}
+
+fn split_statements(nodes: Vec<Node>) -> Vec<Statement> {
+    let mut statements = Vec::new();
+    let mut current = Vec::new();
+    for node in nodes {
+        match node {
+            Node::Token(item) if item.token.kind == TokenKind::Semicolon => {
+                statements.push(Statement {
+                    nodes: mem::take(&mut current),
+                    semicolon: Some(item),
+                });
+            }
+            node => current.push(node),
+        }
+    }
+    if !current.is_empty() {
+        statements.push(Statement {
+            nodes: current,
+            semicolon: None,
+        });
+    }
+    statements
+}
+
+/// A clause such as `GROUP BY a, b`, split into its keywords and the rest.
+struct Clause<'n> {
+    head: &'n [Node],
+    body: &'n [Node],
+}
+
+impl Clause<'_> {
+    fn keyword(&self) -> Option<Keyword> {
+        self.head.first().and_then(Node::keyword)
+    }
+}
+
+/// The number of nodes making up the keywords of a clause starting at `index`, if one does.
+fn clause_head_length(nodes: &[Node], index: usize) -> Option<usize> {
+    let keyword_at = |offset: usize| nodes.get(index + offset).and_then(Node::keyword);
+    let length = match keyword_at(0)? {
+        Keyword::Select => {
+            let mut length = 1;
+            if matches!(keyword_at(length), Some(Keyword::All | Keyword::Distinct)) {
+                length += 1;
+            }
+            if keyword_at(length) == Some(Keyword::Top) && nodes.len() > index + length + 1 {
+                length += 2;
+            }
+            length
+        }
+        Keyword::With
+        | Keyword::From
+        | Keyword::Where
+        | Keyword::Having
+        | Keyword::Offset
+        | Keyword::Values
+        | Keyword::Set
+        | Keyword::Update => 1,
+        Keyword::Group | Keyword::Order if keyword_at(1) == Some(Keyword::By) => 2,
+        Keyword::Union | Keyword::Intersect | Keyword::Except => {
+            if matches!(keyword_at(1), Some(Keyword::All | Keyword::Distinct)) {
+                2
+            } else {
+                1
+            }
+        }
+        Keyword::Insert if keyword_at(1) == Some(Keyword::Into) => 2,
+        Keyword::Delete if keyword_at(1) == Some(Keyword::From) => 2,
+        Keyword::Create | Keyword::Drop if keyword_at(1) == Some(Keyword::Table) => 2,
+        _ => return None,
+    };
+    Some(length)
+}
+
+fn split_clauses(nodes: &[Node]) -> Vec<Clause<'_>> {
+    let mut starts = Vec::new();
+    let mut index = 0;
+    while index < nodes.len() {
+        match clause_head_length(nodes, index) {
+            Some(length) => {
+                starts.push((index, length));
+                index += length;
+            }
+            None => index += 1,
+        }
+    }
+    if starts.first().is_none_or(|&(start, _)| start > 0) {
+        starts.insert(0, (0, 0));
+    }
+
+    starts
+        .iter()
+        .enumerate()
+        .map(|(position, &(start, length))| {
+            let end = starts.get(position + 1).map_or(nodes.len(), |next| next.0);
+            Clause {
+                head: &nodes[start..start + length],
+                body: &nodes[start + length..end],
+            }
+        })
+        .collect()
+}
+
+/// Splits on top-level commas, keeping each comma with the item before it.
+fn split_commas(nodes: &[Node]) -> Vec<(&[Node], Option<&Item>)> {
+    let mut items = Vec::new();
+    let mut start = 0;
+    for (index, node) in nodes.iter().enumerate() {
+        if let Node::Token(item) = node {
+            if item.token.kind == TokenKind::Comma {
+                items.push((&nodes[start..index], Some(item)));
+                start = index + 1;
+            }
+        }
+    }
+    if start < nodes.len() {
+        items.push((&nodes[start..], None));
+    }
+    items
+}
+
+fn is_join_keyword(keyword: Keyword) -> bool {
+    matches!(
+        keyword,
+        Keyword::Natural
+            | Keyword::Cross
+            | Keyword::Inner
+            | Keyword::Left
+            | Keyword::Right
+            | Keyword::Full
+            | Keyword::Outer
+            | Keyword::Join
+    )
+}
+
+/// A join in a `FROM` clause: the join keywords and table, then its condition if any.
+struct JoinLine<'n> {
+    table: &'n [Node],
+    condition: &'n [Node],
+}
+
+/// Splits a table reference into the first table and the joins onto it.
+fn split_joins(nodes: &[Node]) -> (&[Node], Vec<JoinLine<'_>>) {
+    let mut starts = Vec::new();
+    let mut previous_was_join = false;
+    for (index, node) in nodes.iter().enumerate() {
+        let is_join = node.keyword().is_some_and(is_join_keyword);
+        if is_join && !previous_was_join {
+            starts.push(index);
+        }
+        previous_was_join = is_join;
+    }
+
+    let first = &nodes[..starts.first().copied().unwrap_or(nodes.len())];
+    let joins = starts
+        .iter()
+        .enumerate()
+        .map(|(position, &start)| {
+            let end = starts.get(position + 1).copied().unwrap_or(nodes.len());
+            let join = &nodes[start..end];
+            let condition = join
+                .iter()
+                .position(|node| matches!(node.keyword(), Some(Keyword::On | Keyword::Using)))
+                .unwrap_or(join.len());
+            JoinLine {
+                table: &join[..condition],
+                condition: &join[condition..],
+            }
+        })
+        .collect();
+    (first, joins)
+}
+
+/// Whether parenthesised nodes hold a query rather than an expression or a list.
+fn is_query(nodes: &[Node]) -> bool {
+    match nodes.first() {
+        Some(Node::Token(_)) => matches!(nodes[0].keyword(), Some(Keyword::Select | Keyword::With)),
+        Some(Node::Group { children, .. }) => is_query(children),
+        None => false,
+    }
+}
+
+/// Keywords that take a parenthesised argument written without a space before it.
+fn is_type_keyword(keyword: Keyword) -> bool {
+    matches!(
+        keyword,
+        Keyword::Char
+            | Keyword::Character
+            | Keyword::VarChar
+            | Keyword::Varying
+            | Keyword::Decimal
+            | Keyword::Numeric
+            | Keyword::Float
+    )
+}
+
+/// Whether a token ends an operand, making a following `+` or `-` binary.
+fn ends_operand(kind: TokenKind) -> bool {
+    match kind {
+        TokenKind::Identifier
+        | TokenKind::DelimitedIdentifier
+        | TokenKind::SingleQuotedStringLiteral
+        | TokenKind::IntegerLiteral
+        | TokenKind::DecimalLiteral
+        | TokenKind::HexadecimalLiteral
+        | TokenKind::ExponentLiteral
+        | TokenKind::RightParenthesis => true,
+        TokenKind::Keyword(keyword) => matches!(
+            keyword,
+            Keyword::End | Keyword::Null | Keyword::True | Keyword::False
+        ),
+        _ => false,
+    }
+}
+
+struct Writer<'a> {
+    source: &'a str,
+    output: String,
+    /// Byte offset of the start of the current line.
+    line_start: usize,
+    /// Indentation of the clause or item the current line belongs to.
+    indent: usize,
+    previous: Option<TokenKind>,
+    /// Whether the previous token was a unary operator.
+    previous_unary: bool,
+    /// Whether the previous identifier names a table or common table expression, so that a
+    /// following column list is spaced.
+    previous_names_table: bool,
+    /// Whether the current clause is a `WITH` list.
+    in_with: bool,
+    /// Set after an inline comment, which has to end its line.
+    pending_newline: bool,
+    /// Lays everything out on one line, to measure it.
+    flat: bool,
+    /// Set when a flat layout is impossible because of comments.
+    broken: bool,
+}
+
+impl<'a> Writer<'a> {
+    fn new(source: &'a str, flat: bool) -> Self {
+        Self {
+            source,
+            output: String::new(),
+            line_start: 0,
+            indent: 0,
+            previous: None,
+            previous_unary: false,
+            previous_names_table: false,
+            in_with: false,
+            pending_newline: false,
+            flat,
+            broken: false,
+        }
+    }
+
+    fn column(&self) -> usize {
+        self.output[self.line_start..].chars().count()
+    }
+
+    fn at_line_start(&self) -> bool {
+        self.output[self.line_start..]
+            .bytes()
+            .all(|byte| byte == b' ')
+    }
+
+    /// Whether `write` would fit on the rest of the current line.
+    fn fits(&self, write: impl FnOnce(&mut Writer<'a>)) -> bool {
+        self.measure(write).is_some_and(|width| {
+            let column = self.column() + usize::from(!self.at_line_start());
+            column + width <= MAX_WIDTH
+        })
+    }
+
+    /// The width of `write` laid out on one line, or `None` if it cannot be.
+    fn measure(&self, write: impl FnOnce(&mut Writer<'a>)) -> Option<usize> {
+        let mut writer = Writer::new(self.source, true);
+        writer.in_with = self.in_with;
+        write(&mut writer);
+        (!writer.broken).then(|| writer.output.chars().count())
+    }
+
+    /// Starts a new line for the layout, at the given indentation.
+    fn newline(&mut self, indent: usize) {
+        if self.flat {
+            return;
+        }
+        self.indent = indent;
+        self.break_line(indent);
+    }
+
+    /// Starts a new line without changing the layout's indentation.
+    fn break_line(&mut self, column: usize) {
+        if self.flat {
+            self.broken = true;
+        }
+        let trimmed = self.output.trim_end_matches(' ').len();
+        self.output.truncate(trimmed);
+        self.output.push('\n');
+        self.line_start = self.output.len();
+        self.output.extend(std::iter::repeat_n(' ', column));
+        self.pending_newline = false;
+    }
+
+    fn write_item(&mut self, item: &Item) {
+        // Comments before what is being measured go on their own lines anyway
+        let measuring_start = self.flat && self.output.is_empty();
+        if !item.leading.is_empty() && !measuring_start {
+            if self.flat {
+                self.broken = true;
+            }
+            if !self.at_line_start() {
+                self.break_line(self.indent + INDENT);
+            }
+            let column = self.column();
+            for comment in &item.leading {
+                self.output.push_str(comment.text(self.source));
+                self.break_line(column);
+            }
+        }
+
+        self.write_token(item.token);
+
+        for comment in &item.trailing {
+            self.output.push(' ');
+            self.output.push_str(comment.text(self.source));
+            // Only a token after the comment breaks a flat layout
+            if comment.kind == TokenKind::InlineComment {
+                self.pending_newline = true;
+            }
+        }
+    }
+
+    fn write_token(&mut self, token: Token) {
+        if self.pending_newline {
+            self.break_line(self.indent + INDENT);
+        }
+        let text = match token.kind {
+            TokenKind::Keyword(keyword) => keyword.as_str(),
+            _ => token.text(self.source),
+        };
+        if !self.at_line_start() && self.needs_space(token.kind, text) {
+            self.output.push(' ');
+        }
+        self.output.push_str(text);
+
+        self.previous_unary = match token.kind {
+            TokenKind::Tilde => true,
+            TokenKind::Plus | TokenKind::Minus => !self.previous.is_some_and(ends_operand),
+            _ => false,
+        };
+        if matches!(
+            token.kind,
+            TokenKind::Identifier | TokenKind::DelimitedIdentifier
+        ) {
+            self.previous_names_table = match self.previous {
+                Some(TokenKind::Keyword(keyword)) => matches!(
+                    keyword,
+                    Keyword::Table | Keyword::Into | Keyword::References | Keyword::With
+                ),
+                Some(TokenKind::Comma) => self.in_with,
+                _ => false,
+            };
+        }
+        self.previous = Some(token.kind);
+    }
+
+    fn needs_space(&self, next: TokenKind, text: &str) -> bool {
+        // Never let two tokens run together into a comment
+        if (self.output.ends_with('-') && text.starts_with('-'))
+            || (self.output.ends_with('/') && text.starts_with('*'))
+        {
+            return true;
+        }
+        let Some(previous) = self.previous else {
+            return false;
+        };
+        match (previous, next) {
+            (
+                _,
+                TokenKind::Comma
+                | TokenKind::Semicolon
+                | TokenKind::RightParenthesis
+                | TokenKind::Period,
+            ) => false,
+            (TokenKind::LeftParenthesis | TokenKind::Period, _) => false,
+            _ if self.previous_unary => false,
+            (
+                TokenKind::Identifier | TokenKind::DelimitedIdentifier,
+                TokenKind::LeftParenthesis,
+            ) => self.previous_names_table,
+            (TokenKind::Keyword(keyword), TokenKind::LeftParenthesis) => {
+                !(keyword.is_function() || is_type_keyword(keyword))
+            }
+            _ => true,
+        }
+    }
+
+    fn write_nodes(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            self.write_node(node);
+        }
+    }
+
+    fn write_node(&mut self, node: &Node) {
+        match node {
+            Node::Token(item) => self.write_item(item),
+            Node::Group {
+                open,
+                children,
+                close,
+            } => self.write_group(open, children, close.as_ref()),
+        }
+    }
+
+    fn write_group(&mut self, open: &Item, children: &[Node], close: Option<&Item>) {
+        if !is_query(children) {
+            self.write_item(open);
+            self.write_nodes(children);
+            if let Some(close) = close {
+                self.write_item(close);
+            }
+            return;
+        }
+
+        let in_with = self.in_with;
+        if self.flat || self.fits(|writer| writer.write_group(open, children, close)) {
+            let flat = mem::replace(&mut self.flat, true);
+            self.write_item(open);
+            self.write_query(children);
+            if let Some(close) = close {
+                self.write_item(close);
+            }
+            self.flat = flat;
+        } else {
+            let indent = self.indent;
+            self.write_item(open);
+            self.newline(indent + INDENT);
+            self.write_query(children);
+            self.newline(indent);
+            if let Some(close) = close {
+                self.write_item(close);
+            }
+        }
+        self.in_with = in_with;
+    }
+
+    /// Writes a parenthesised list with one item per line.
+    fn write_list_group(&mut self, open: &Item, children: &[Node], close: Option<&Item>) {
+        let indent = self.indent;
+        self.write_item(open);
+        for (item, comma) in split_commas(children) {
+            self.newline(indent + INDENT);
+            self.write_nodes(item);
+            if let Some(comma) = comma {
+                self.write_item(comma);
+            }
+        }
+        if !children.is_empty() {
+            self.newline(indent);
+        }
+        if let Some(close) = close {
+            self.write_item(close);
+        }
+    }
+
+    /// Writes a statement or subquery with one clause per line.
+    fn write_query(&mut self, nodes: &[Node]) {
+        let indent = self.indent;
+        for (index, clause) in split_clauses(nodes).iter().enumerate() {
+            if index > 0 {
+                self.newline(indent);
+            }
+            self.in_with = clause.keyword() == Some(Keyword::With);
+            match clause.keyword() {
+                Some(
+                    Keyword::Select
+                    | Keyword::With
+                    | Keyword::Group
+                    | Keyword::Order
+                    | Keyword::Values
+                    | Keyword::Set,
+                ) => self.write_list_clause(clause),
+                Some(Keyword::From) => self.write_from(clause),
+                Some(Keyword::Where | Keyword::Having) => self.write_condition(clause),
+                Some(Keyword::Create) => {
+                    self.write_nodes(clause.head);
+                    for node in clause.body {
+                        match node {
+                            Node::Group {
+                                open,
+                                children,
+                                close,
+                            } if !self.flat => {
+                                self.write_list_group(open, children, close.as_ref())
+                            }
+                            node => self.write_node(node),
+                        }
+                    }
+                }
+                _ => {
+                    self.write_nodes(clause.head);
+                    self.write_nodes(clause.body);
+                }
+            }
+        }
+    }
+
+    fn write_clause_flat(&mut self, clause: &Clause) {
+        self.write_nodes(clause.head);
+        self.write_nodes(clause.body);
+    }
+
+    /// Writes a clause made of a comma-separated list, breaking it into one item per line if
+    /// it is too long.
+    fn write_list_clause(&mut self, clause: &Clause) {
+        if self.flat || self.fits(|writer| writer.write_clause_flat(clause)) {
+            self.write_clause_flat(clause);
+            return;
+        }
+
+        let indent = self.indent;
+        self.write_nodes(clause.head);
+        for (item, comma) in split_commas(clause.body) {
+            self.newline(indent + INDENT);
+            self.write_nodes(item);
+            if let Some(comma) = comma {
+                self.write_item(comma);
+            }
+        }
+    }
+
+    /// Writes a `WHERE` or `HAVING` clause, breaking before each top-level `AND` and `OR` if it
+    /// is too long.
+    fn write_condition(&mut self, clause: &Clause) {
+        if self.flat || self.fits(|writer| writer.write_clause_flat(clause)) {
+            self.write_clause_flat(clause);
+            return;
+        }
+
+        let indent = self.indent;
+        self.write_nodes(clause.head);
+        let mut betweens = 0;
+        let mut cases = 0;
+        for node in clause.body {
+            match node.keyword() {
+                Some(Keyword::Between) => betweens += 1,
+                Some(Keyword::Case) => cases += 1,
+                Some(Keyword::End) => cases -= 1,
+                Some(Keyword::And) if betweens > 0 => betweens -= 1,
+                Some(Keyword::And | Keyword::Or) if cases == 0 => self.newline(indent + INDENT),
+                _ => {}
+            }
+            self.write_node(node);
+        }
+    }
+
+    /// Writes a `FROM` clause with each join on a line of its own and their conditions
+    /// aligned.
+    fn write_from(&mut self, clause: &Clause) {
+        let has_joins = clause
+            .body
+            .iter()
+            .any(|node| node.keyword().is_some_and(is_join_keyword));
+        if self.flat || (!has_joins && self.fits(|writer| writer.write_clause_flat(clause))) {
+            self.write_clause_flat(clause);
+            return;
+        }
+
+        let indent = self.indent;
+        self.write_nodes(clause.head);
+        for (index, (reference, comma)) in split_commas(clause.body).into_iter().enumerate() {
+            if index > 0 {
+                self.newline(indent + INDENT);
+            }
+            let (first, joins) = split_joins(reference);
+            self.write_nodes(first);
+
+            let widths: Vec<Option<usize>> = joins
+                .iter()
+                .map(|join| self.measure(|writer| writer.write_nodes(join.table)))
+                .collect();
+            let aligned = widths.iter().flatten().copied().max().unwrap_or(0);
+            for (join, width) in joins.iter().zip(widths) {
+                self.newline(indent + INDENT);
+                self.write_nodes(join.table);
+                if let (false, Some(width)) = (join.condition.is_empty(), width) {
+                    self.output
+                        .extend(std::iter::repeat_n(' ', aligned - width));
+                }
+                self.write_nodes(join.condition);
+            }
+
+            if let Some(comma) = comma {
+                self.write_item(comma);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format;
+    use crate::lexer::{Lexer, TokenKind};
+
+    const CORPUS: [&str; 8] = [
+        include_str!("../example.sql"),
+        include_str!("../simple.sql"),
+        "select a,b from t where a=1 and b<>'x' order by a desc",
+        "-- leading\nSELECT /* inline */ a -- trailing\n, b\nFROM t; /* between */ SELECT 1 FROM u\n-- dangling",
+        "with q as (select source_id, ra, dec from gaia.source where 1=contains(point('ICRS',ra,dec),circle('ICRS',266.4,-29.0,0.5))) select top 10 * from q join gaia.other o on q.source_id=o.source_id and q.ra>o.ra left outer join z using (source_id) order by ra",
+        "SELECT CASE WHEN phot_g_mean_mag < 10 THEN 'bright' WHEN phot_g_mean_mag < 15 THEN 'medium' ELSE 'faint' END AS brightness, source_id, ra, dec, parallax, pmra, pmdec FROM gaia.source",
+        "SELECT a FROM t WHERE a IN (SELECT a FROM u WHERE EXISTS (SELECT 1 FROM v WHERE v.a = u.a)) UNION ALL SELECT b FROM w",
+        "create table t (id integer primary key, name varchar(20) not null);\n\n\ninsert into t values (1, 'a'), (2, 'b');update t set name='c' where id=1;drop table t",
+    ];
+
+    /// The tokens of `source` other than whitespace, with keywords compared by kind alone
+    /// so that their case doesn't matter.
+    fn tokens(source: &str) -> Vec<(TokenKind, &str)> {
+        Lexer::lossless(source)
+            .filter(|token| token.kind != TokenKind::Whitespace)
+            .map(|token| match token.kind {
+                TokenKind::Keyword(_) => (token.kind, ""),
+                kind => (kind, token.text(source)),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn formatting_is_idempotent() {
+        for source in CORPUS {
+            let formatted = format(source).unwrap();
+            assert_eq!(format(&formatted).unwrap(), formatted, "{source}");
+        }
+    }
+
+    #[test]
+    fn formatting_keeps_every_token_and_comment() {
+        for source in CORPUS {
+            let formatted = format(source).unwrap();
+            assert_eq!(tokens(&formatted), tokens(source), "{formatted}");
+        }
+    }
+
+    #[test]
+    fn clauses_and_statements_start_lines() {
+        assert_eq!(
+            format("select a,b from t where a=1 and b<>'x' order by a desc;select 1 from u")
+                .unwrap(),
+            "SELECT a, b\nFROM t\nWHERE a = 1 AND b <> 'x'\nORDER BY a DESC;\nSELECT 1\nFROM u\n"
+        );
+    }
+
+    #[test]
+    fn comments_stay_next_to_their_tokens() {
+        assert_eq!(
+            format("-- leading\nSELECT /* inline */ a, -- trailing\n b\nFROM t\n-- dangling")
+                .unwrap(),
+            "-- leading\nSELECT /* inline */\n    a, -- trailing\n    b\nFROM t\n-- dangling\n"
+        );
+    }
+
+    #[test]
+    fn sources_that_do_not_lex_are_not_formatted() {
+        let errors = format("SELECT 'unterminated FROM t").unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(format("").unwrap(), "");
+    }
+}
@@ -0,0 +1,240 @@
+//! JSON output of tokens and the AST for tools outside Rust. Needs the `serde` feature.
+//!
+//! # Schema (version 1)
+//!
+//! Both documents are objects with a `"version"` field, which is increased whenever a change
+//! could break an existing consumer. Adding a new enum variant is such a change; adding a
+//! field to an object is not.
+//!
+//! `daoql lex --json` prints every token of the source, including whitespace and
+//! comments, so concatenating the `text` fields gives back the source:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "tokens": [
+//!     { "kind": { "Keyword": "SELECT" }, "span": { "start": 0, "length": 6 }, "text": "SELECT" },
+//!     { "kind": "Whitespace", "span": { "start": 6, "length": 1 }, "text": " " }
+//!   ]
+//! }
+//! ```
+//!
+//! `daoql parse --json` prints each statement of the script with every error in it. An
+//! unparseable statement still appears, as `{ "Error": span }`:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "statements": [
+//!     { "kind": "Query", "span": { "start": 0, "length": 15 }, "statement": { "Query": { ... } } }
+//!   ],
+//!   "errors": [
+//!     { "message": "expected `FROM`, found identifier", "span": { "start": 9, "length": 1 } }
+//!   ]
+//! }
+//! ```
+//!
+//! Values are encoded as follows:
+//!
+//! - Spans are `{ "start", "length" }` in bytes of the UTF-8 source.
+//! - Structs are objects with the Rust field names, e.g. `Ident` is
+//!   `{ "value": "ra", "quoted": false, "span": ... }`. Absent optional values are `null`.
+//! - Enums use the Rust variant names. A variant without data is a string, such as
+//!   `"Comma"` or `"Descending"`, and a variant with data is an object with the variant name
+//!   as its only key, such as `{ "Column": { "parts": [...], "span": ... } }` or
+//!   `{ "Binary": { "left": ..., "op": "Plus", "right": ..., "span": ... } }`.
+//! - Keywords are their SQL spelling, such as `"SELECT"`.
+//! - Numeric literals keep their source text, as in `{ "Number": "1.5E3" }`, so no
+//!   precision is lost.
+
+use serde::Serialize;
+
+use crate::lexer::{Lexer, Span, TokenKind};
+use crate::parser::{Script, ScriptStatement};
+
+/// The version of the schema described above.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct TokensDocument<'a> {
+    version: u32,
+    tokens: Vec<JsonToken<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonToken<'a> {
+    kind: TokenKind,
+    span: Span,
+    text: &'a str,
+}
+
+#[derive(Serialize)]
+struct ScriptDocument<'a> {
+    version: u32,
+    statements: &'a [ScriptStatement],
+    errors: Vec<JsonError>,
+}
+
+#[derive(Serialize)]
+struct JsonError {
+    message: String,
+    span: Span,
+}
+
+/// Every token of `source`, including trivia, as a pretty-printed JSON document.
+pub fn tokens(source: &str) -> String {
+    let document = TokensDocument {
+        version: SCHEMA_VERSION,
+        tokens: Lexer::lossless(source)
+            .map(|token| JsonToken {
+                kind: token.kind,
+                span: token.span,
+                text: token.text(source),
+            })
+            .collect(),
+    };
+    to_string(&document)
+}
+
+/// The statements and errors of a parsed script as a pretty-printed JSON document.
+pub fn script(script: &Script) -> String {
+    let document = ScriptDocument {
+        version: SCHEMA_VERSION,
+        statements: &script.statements,
+        errors: script
+            .errors
+            .iter()
+            .map(|error| JsonError {
+                message: error.to_string(),
+                span: error.span,
+            })
+            .collect(),
+    };
+    to_string(&document)
+}
+
+fn to_string(document: &impl Serialize) -> String {
+    // Every type in the documents serializes infallibly: all map keys are strings
+    serde_json::to_string_pretty(document).expect("the document should serialize to JSON")
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use serde_json::{json, Value};
+
+    use crate::lexer::{Keyword, TokenKind};
+    use crate::parser::ScriptStatement;
+    use crate::Script;
+
+    fn parse(document: &str) -> Value {
+        serde_json::from_str(document).unwrap()
+    }
+
+    #[test]
+    fn tokens_document_matches_the_schema() {
+        assert_eq!(
+            parse(&super::tokens("SELECT ra!")),
+            json!({
+                "version": 1,
+                "tokens": [
+                    {
+                        "kind": { "Keyword": "SELECT" },
+                        "span": { "start": 0, "length": 6 },
+                        "text": "SELECT"
+                    },
+                    { "kind": "Whitespace", "span": { "start": 6, "length": 1 }, "text": " " },
+                    { "kind": "Identifier", "span": { "start": 7, "length": 2 }, "text": "ra" },
+                    {
+                        "kind": { "Error": { "InvalidCharacter": "!" } },
+                        "span": { "start": 9, "length": 1 },
+                        "text": "!"
+                    },
+                ]
+            })
+        );
+        let document = parse(&super::tokens("-- c\n'x"));
+        assert_eq!(document["tokens"][0]["kind"], json!("InlineComment"));
+        assert_eq!(
+            document["tokens"][2]["kind"],
+            json!({ "Error": "UnterminatedString" })
+        );
+    }
+
+    #[test]
+    fn script_document_matches_the_schema() {
+        let source = "SELECT ra AS r FROM t WHERE x > 1.5E3; frobnicate";
+        let document = parse(&super::script(&Script::parse(source)));
+        assert_eq!(document["version"], json!(1));
+        assert_eq!(document["statements"][0]["kind"], json!("Query"));
+        assert_eq!(
+            document["statements"][0]["span"],
+            json!({ "start": 0, "length": 37 })
+        );
+
+        let select = &document["statements"][0]["statement"]["Query"]["body"]["Select"];
+        assert_eq!(select["top"], Value::Null);
+        assert_eq!(
+            select["items"][0]["Expression"]["alias"],
+            json!({ "value": "r", "quoted": false, "span": { "start": 13, "length": 1 } })
+        );
+        let condition = &select["where_clause"]["Binary"];
+        assert_eq!(condition["op"], json!("GreaterThan"));
+        assert_eq!(
+            condition["right"],
+            json!({
+                "Literal": { "value": { "Number": "1.5E3" }, "span": { "start": 32, "length": 5 } }
+            })
+        );
+
+        // An unparseable statement is kept as `{ "Error": span }`
+        assert_eq!(
+            document["statements"][1],
+            json!({
+                "kind": null,
+                "span": { "start": 39, "length": 10 },
+                "statement": { "Error": { "start": 39, "length": 10 } }
+            })
+        );
+        assert_eq!(
+            document["errors"],
+            json!([{
+                "message": "expected a statement, found identifier",
+                "span": { "start": 39, "length": 10 }
+            }])
+        );
+    }
+
+    #[test]
+    fn keywords_are_their_spelling() {
+        for &keyword in Keyword::ALL {
+            let json = serde_json::to_string(&keyword).unwrap();
+            assert_eq!(json, format!("\"{}\"", keyword.as_str()));
+            assert_eq!(serde_json::from_str::<Keyword>(&json).unwrap(), keyword);
+        }
+        let kind: TokenKind = serde_json::from_str(r#"{ "Keyword": "SELECT" }"#).unwrap();
+        assert_eq!(kind, TokenKind::Keyword(Keyword::Select));
+        // Only the upper case spelling is accepted, as that is the only one written
+        assert!(serde_json::from_str::<Keyword>("\"select\"").is_err());
+        assert!(serde_json::from_str::<Keyword>("\"SELEKT\"").is_err());
+    }
+
+    #[test]
+    fn statements_round_trip() {
+        let sources = [
+            include_str!("../example.sql"),
+            "WITH q AS (SELECT TOP 5 DISTINCT a, -b * 2 FROM s.t AS x \
+             NATURAL JOIN (SELECT c FROM u) y \
+             WHERE a IN (SELECT a FROM v) AND b BETWEEN 1 AND 0x1F OR c LIKE 'x''%' \
+             GROUP BY a HAVING COUNT(*) > 1) \
+             SELECT * FROM q UNION ALL SELECT 1 FROM w ORDER BY 1 DESC",
+            "SELECT CASE a WHEN 1 THEN 'one' ELSE NULL END, \"Quoted \"\"name\"\"\" FROM t; \
+             frobnicate; DROP TABLE t CASCADE; SELECT FROM",
+        ];
+        for source in sources {
+            let statements = Script::parse(source).statements;
+            let json = serde_json::to_string(&statements).unwrap();
+            let read: Vec<ScriptStatement> = serde_json::from_str(&json).unwrap();
+            assert_eq!(read, statements, "{source}");
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use std::fmt;
+
+/// Why some source text is not a valid token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LexError {
+    /// A character that cannot start a token.
+    InvalidCharacter(char),
+    /// A string literal without its closing `'`.
+    UnterminatedString,
+    /// A delimited identifier without its closing `"`.
+    UnterminatedIdentifier,
+    /// A delimited identifier with nothing between its quotes, `""`.
+    EmptyIdentifier,
+    /// A block comment without its closing `*/`.
+    UnterminatedComment,
+    /// A numeric literal that is missing digits, as in `1E` or `0x`, or runs into other
+    /// characters, as in `1.2.3` or `12abc`.
+    MalformedNumber,
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LexError::InvalidCharacter(c) => write!(f, "invalid character `{}`", c.escape_debug()),
+            LexError::UnterminatedString => write!(f, "unterminated string literal"),
+            LexError::UnterminatedIdentifier => write!(f, "unterminated delimited identifier"),
+            LexError::EmptyIdentifier => write!(f, "empty delimited identifier"),
+            LexError::UnterminatedComment => write!(f, "unterminated block comment"),
+            LexError::MalformedNumber => write!(f, "malformed numeric literal"),
+        }
+    }
+}
+
+impl std::error::Error for LexError {}
@@ -1,5 +1,9 @@
-#[allow(dead_code)]
-#[derive(Debug)]
+/// The reserved words of ADQL, which are those of SQL-92 and the ADQL function names.
+///
+/// Each variant is the keyword of the same name, such as `Keyword::BitLength` for
+/// `BIT_LENGTH`, so the variants are not documented individually.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Keyword {
     // SQL
     Absolute,
@@ -236,6 +240,7 @@ pub enum Keyword {
     Atan2,
     Ceiling,
     Cos,
+    Cot,
     Degrees,
     Exp,
     Floor,
@@ -266,11 +271,299 @@ pub enum Keyword {
     Point,
     Polygon,
     Region,
+    // ADQL 2.1 additions
+    BitAnd,
+    BitNot,
+    BitOr,
+    BitXor,
+    Ilike,
+    InUnit,
+    Offset,
 }
 
 impl Keyword {
+    /// Every keyword, in the order they are declared.
+    pub const ALL: &'static [Keyword] = &[
+        // SQL
+        Keyword::Absolute,
+        Keyword::Action,
+        Keyword::Add,
+        Keyword::All,
+        Keyword::Allocate,
+        Keyword::Alter,
+        Keyword::And,
+        Keyword::Any,
+        Keyword::Are,
+        Keyword::As,
+        Keyword::Asc,
+        Keyword::Assertion,
+        Keyword::At,
+        Keyword::Authorization,
+        Keyword::Avg,
+        Keyword::Begin,
+        Keyword::Between,
+        Keyword::Bit,
+        Keyword::BitLength,
+        Keyword::Both,
+        Keyword::By,
+        Keyword::Cascade,
+        Keyword::Cascaded,
+        Keyword::Case,
+        Keyword::Cast,
+        Keyword::Catalog,
+        Keyword::Char,
+        Keyword::Character,
+        Keyword::CharacterLength,
+        Keyword::CharLength,
+        Keyword::Check,
+        Keyword::Close,
+        Keyword::Coalesce,
+        Keyword::Collate,
+        Keyword::Collation,
+        Keyword::Column,
+        Keyword::Commit,
+        Keyword::Connect,
+        Keyword::Connection,
+        Keyword::Constraint,
+        Keyword::Constraints,
+        Keyword::Continue,
+        Keyword::Convert,
+        Keyword::Corresponding,
+        Keyword::Count,
+        Keyword::Create,
+        Keyword::Cross,
+        Keyword::Current,
+        Keyword::CurrentDate,
+        Keyword::CurrentTime,
+        Keyword::CurrentTimestamp,
+        Keyword::CurrentUser,
+        Keyword::Cursor,
+        Keyword::Date,
+        Keyword::Day,
+        Keyword::Deallocate,
+        Keyword::Decimal,
+        Keyword::Declare,
+        Keyword::Default,
+        Keyword::Deferrable,
+        Keyword::Deferred,
+        Keyword::Delete,
+        Keyword::Desc,
+        Keyword::Describe,
+        Keyword::Descriptor,
+        Keyword::Diagnostics,
+        Keyword::Disconnect,
+        Keyword::Distinct,
+        Keyword::Domain,
+        Keyword::Double,
+        Keyword::Drop,
+        Keyword::Else,
+        Keyword::End,
+        Keyword::EndExec,
+        Keyword::Escape,
+        Keyword::Except,
+        Keyword::Exception,
+        Keyword::Exec,
+        Keyword::Execute,
+        Keyword::Exists,
+        Keyword::External,
+        Keyword::Extract,
+        Keyword::False,
+        Keyword::Fetch,
+        Keyword::First,
+        Keyword::Float,
+        Keyword::For,
+        Keyword::Foreign,
+        Keyword::Found,
+        Keyword::From,
+        Keyword::Full,
+        Keyword::Get,
+        Keyword::Global,
+        Keyword::Go,
+        Keyword::Goto,
+        Keyword::Grant,
+        Keyword::Group,
+        Keyword::Having,
+        Keyword::Hour,
+        Keyword::Identity,
+        Keyword::Immediate,
+        Keyword::In,
+        Keyword::Indicator,
+        Keyword::Initially,
+        Keyword::Inner,
+        Keyword::Input,
+        Keyword::Insensitive,
+        Keyword::Insert,
+        Keyword::Int,
+        Keyword::Integer,
+        Keyword::Intersect,
+        Keyword::Interval,
+        Keyword::Into,
+        Keyword::Is,
+        Keyword::Isolation,
+        Keyword::Join,
+        Keyword::Key,
+        Keyword::Language,
+        Keyword::Last,
+        Keyword::Leading,
+        Keyword::Left,
+        Keyword::Level,
+        Keyword::Like,
+        Keyword::Local,
+        Keyword::Lower,
+        Keyword::Match,
+        Keyword::Max,
+        Keyword::Min,
+        Keyword::Minute,
+        Keyword::Module,
+        Keyword::Month,
+        Keyword::Names,
+        Keyword::National,
+        Keyword::Natural,
+        Keyword::NChar,
+        Keyword::Next,
+        Keyword::No,
+        Keyword::Not,
+        Keyword::Null,
+        Keyword::Nullif,
+        Keyword::Numeric,
+        Keyword::OctetLength,
+        Keyword::Of,
+        Keyword::On,
+        Keyword::Only,
+        Keyword::Open,
+        Keyword::Option,
+        Keyword::Or,
+        Keyword::Order,
+        Keyword::Outer,
+        Keyword::Output,
+        Keyword::Overlaps,
+        Keyword::Pad,
+        Keyword::Partial,
+        Keyword::Position,
+        Keyword::Precision,
+        Keyword::Prepare,
+        Keyword::Preserve,
+        Keyword::Primary,
+        Keyword::Prior,
+        Keyword::Privileges,
+        Keyword::Procedure,
+        Keyword::Public,
+        Keyword::Read,
+        Keyword::Real,
+        Keyword::References,
+        Keyword::Relative,
+        Keyword::Restrict,
+        Keyword::Revoke,
+        Keyword::Right,
+        Keyword::Rollback,
+        Keyword::Rows,
+        Keyword::Schema,
+        Keyword::Scroll,
+        Keyword::Second,
+        Keyword::Section,
+        Keyword::Select,
+        Keyword::Session,
+        Keyword::SessionUser,
+        Keyword::Set,
+        Keyword::Size,
+        Keyword::Smallint,
+        Keyword::Some,
+        Keyword::Space,
+        Keyword::Sql,
+        Keyword::Sqlcode,
+        Keyword::Sqlerror,
+        Keyword::Sqlstate,
+        Keyword::Substring,
+        Keyword::Sum,
+        Keyword::SystemUser,
+        Keyword::Table,
+        Keyword::Temporary,
+        Keyword::Then,
+        Keyword::Time,
+        Keyword::Timestamp,
+        Keyword::TimezoneHour,
+        Keyword::TimezoneMinute,
+        Keyword::To,
+        Keyword::Trailing,
+        Keyword::Transaction,
+        Keyword::Translate,
+        Keyword::Translation,
+        Keyword::Trim,
+        Keyword::True,
+        Keyword::Union,
+        Keyword::Unique,
+        Keyword::Unknown,
+        Keyword::Update,
+        Keyword::Upper,
+        Keyword::Usage,
+        Keyword::User,
+        Keyword::Using,
+        Keyword::Value,
+        Keyword::Values,
+        Keyword::VarChar,
+        Keyword::Varying,
+        Keyword::View,
+        Keyword::When,
+        Keyword::Whenever,
+        Keyword::Where,
+        Keyword::With,
+        Keyword::Work,
+        Keyword::Write,
+        Keyword::Year,
+        Keyword::Zone,
+        // ADQL mathematical functions and operators
+        Keyword::Abs,
+        Keyword::Acos,
+        Keyword::Asin,
+        Keyword::Atan,
+        Keyword::Atan2,
+        Keyword::Ceiling,
+        Keyword::Cos,
+        Keyword::Cot,
+        Keyword::Degrees,
+        Keyword::Exp,
+        Keyword::Floor,
+        Keyword::Log,
+        Keyword::Log10,
+        Keyword::Mod,
+        Keyword::Pi,
+        Keyword::Power,
+        Keyword::Radians,
+        Keyword::Rand,
+        Keyword::Round,
+        Keyword::Sin,
+        Keyword::Sqrt,
+        Keyword::Tan,
+        Keyword::Top,
+        Keyword::Truncate,
+        // ADQL geometric functions and operators
+        Keyword::Area,
+        Keyword::Box,
+        Keyword::Centroid,
+        Keyword::Circle,
+        Keyword::Contains,
+        Keyword::Coord1,
+        Keyword::Coord2,
+        Keyword::Coordsys,
+        Keyword::Distance,
+        Keyword::Intersects,
+        Keyword::Point,
+        Keyword::Polygon,
+        Keyword::Region,
+        // ADQL 2.1 additions
+        Keyword::BitAnd,
+        Keyword::BitNot,
+        Keyword::BitOr,
+        Keyword::BitXor,
+        Keyword::Ilike,
+        Keyword::InUnit,
+        Keyword::Offset,
+    ];
+
+    /// The keyword spelled `string`, which must be in upper case.
     pub fn parse(string: &str) -> Option<Keyword> {
         match string {
+            // SQL
             "ABSOLUTE" => Some(Keyword::Absolute),
             "ACTION" => Some(Keyword::Action),
             "ADD" => Some(Keyword::Add),
@@ -497,7 +790,471 @@ impl Keyword {
             "WRITE" => Some(Keyword::Write),
             "YEAR" => Some(Keyword::Year),
             "ZONE" => Some(Keyword::Zone),
+            // ADQL mathematical functions and operators
+            "ABS" => Some(Keyword::Abs),
+            "ACOS" => Some(Keyword::Acos),
+            "ASIN" => Some(Keyword::Asin),
+            "ATAN" => Some(Keyword::Atan),
+            "ATAN2" => Some(Keyword::Atan2),
+            "CEILING" => Some(Keyword::Ceiling),
+            "COS" => Some(Keyword::Cos),
+            "COT" => Some(Keyword::Cot),
+            "DEGREES" => Some(Keyword::Degrees),
+            "EXP" => Some(Keyword::Exp),
+            "FLOOR" => Some(Keyword::Floor),
+            "LOG" => Some(Keyword::Log),
+            "LOG10" => Some(Keyword::Log10),
+            "MOD" => Some(Keyword::Mod),
+            "PI" => Some(Keyword::Pi),
+            "POWER" => Some(Keyword::Power),
+            "RADIANS" => Some(Keyword::Radians),
+            "RAND" => Some(Keyword::Rand),
+            "ROUND" => Some(Keyword::Round),
+            "SIN" => Some(Keyword::Sin),
+            "SQRT" => Some(Keyword::Sqrt),
+            "TAN" => Some(Keyword::Tan),
+            "TOP" => Some(Keyword::Top),
+            "TRUNCATE" => Some(Keyword::Truncate),
+            // ADQL geometric functions and operators
+            "AREA" => Some(Keyword::Area),
+            "BOX" => Some(Keyword::Box),
+            "CENTROID" => Some(Keyword::Centroid),
+            "CIRCLE" => Some(Keyword::Circle),
+            "CONTAINS" => Some(Keyword::Contains),
+            "COORD1" => Some(Keyword::Coord1),
+            "COORD2" => Some(Keyword::Coord2),
+            "COORDSYS" => Some(Keyword::Coordsys),
+            "DISTANCE" => Some(Keyword::Distance),
+            "INTERSECTS" => Some(Keyword::Intersects),
+            "POINT" => Some(Keyword::Point),
+            "POLYGON" => Some(Keyword::Polygon),
+            "REGION" => Some(Keyword::Region),
+            // ADQL 2.1 additions
+            "BIT_AND" => Some(Keyword::BitAnd),
+            "BIT_NOT" => Some(Keyword::BitNot),
+            "BIT_OR" => Some(Keyword::BitOr),
+            "BIT_XOR" => Some(Keyword::BitXor),
+            "ILIKE" => Some(Keyword::Ilike),
+            "IN_UNIT" => Some(Keyword::InUnit),
+            "OFFSET" => Some(Keyword::Offset),
             _ => None,
         }
     }
+
+    /// Whether the keyword names a function, and so can be followed by arguments.
+    pub fn is_function(&self) -> bool {
+        matches!(
+            self,
+            Keyword::Avg
+                | Keyword::BitLength
+                | Keyword::CharLength
+                | Keyword::CharacterLength
+                | Keyword::Coalesce
+                | Keyword::Count
+                | Keyword::Lower
+                | Keyword::Max
+                | Keyword::Min
+                | Keyword::Nullif
+                | Keyword::OctetLength
+                | Keyword::Sum
+                | Keyword::Upper
+                // ADQL mathematical functions
+                | Keyword::Abs
+                | Keyword::Acos
+                | Keyword::Asin
+                | Keyword::Atan
+                | Keyword::Atan2
+                | Keyword::Ceiling
+                | Keyword::Cos
+                | Keyword::Cot
+                | Keyword::Degrees
+                | Keyword::Exp
+                | Keyword::Floor
+                | Keyword::Log
+                | Keyword::Log10
+                | Keyword::Mod
+                | Keyword::Pi
+                | Keyword::Power
+                | Keyword::Radians
+                | Keyword::Rand
+                | Keyword::Round
+                | Keyword::Sin
+                | Keyword::Sqrt
+                | Keyword::Tan
+                | Keyword::Truncate
+                // ADQL geometric functions
+                | Keyword::Area
+                | Keyword::Box
+                | Keyword::Centroid
+                | Keyword::Circle
+                | Keyword::Contains
+                | Keyword::Coord1
+                | Keyword::Coord2
+                | Keyword::Coordsys
+                | Keyword::Distance
+                | Keyword::Intersects
+                | Keyword::Point
+                | Keyword::Polygon
+                | Keyword::Region
+                // ADQL 2.1 functions
+                | Keyword::BitAnd
+                | Keyword::BitNot
+                | Keyword::BitOr
+                | Keyword::BitXor
+                | Keyword::InUnit
+        )
+    }
+
+    /// The upper-case spelling of the keyword.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            // SQL
+            Keyword::Absolute => "ABSOLUTE",
+            Keyword::Action => "ACTION",
+            Keyword::Add => "ADD",
+            Keyword::All => "ALL",
+            Keyword::Allocate => "ALLOCATE",
+            Keyword::Alter => "ALTER",
+            Keyword::And => "AND",
+            Keyword::Any => "ANY",
+            Keyword::Are => "ARE",
+            Keyword::As => "AS",
+            Keyword::Asc => "ASC",
+            Keyword::Assertion => "ASSERTION",
+            Keyword::At => "AT",
+            Keyword::Authorization => "AUTHORIZATION",
+            Keyword::Avg => "AVG",
+            Keyword::Begin => "BEGIN",
+            Keyword::Between => "BETWEEN",
+            Keyword::Bit => "BIT",
+            Keyword::BitLength => "BIT_LENGTH",
+            Keyword::Both => "BOTH",
+            Keyword::By => "BY",
+            Keyword::Cascade => "CASCADE",
+            Keyword::Cascaded => "CASCADED",
+            Keyword::Case => "CASE",
+            Keyword::Cast => "CAST",
+            Keyword::Catalog => "CATALOG",
+            Keyword::Char => "CHAR",
+            Keyword::Character => "CHARACTER",
+            Keyword::CharacterLength => "CHARACTER_LENGTH",
+            Keyword::CharLength => "CHAR_LENGTH",
+            Keyword::Check => "CHECK",
+            Keyword::Close => "CLOSE",
+            Keyword::Coalesce => "COALESCE",
+            Keyword::Collate => "COLLATE",
+            Keyword::Collation => "COLLATION",
+            Keyword::Column => "COLUMN",
+            Keyword::Commit => "COMMIT",
+            Keyword::Connect => "CONNECT",
+            Keyword::Connection => "CONNECTION",
+            Keyword::Constraint => "CONSTRAINT",
+            Keyword::Constraints => "CONSTRAINTS",
+            Keyword::Continue => "CONTINUE",
+            Keyword::Convert => "CONVERT",
+            Keyword::Corresponding => "CORRESPONDING",
+            Keyword::Count => "COUNT",
+            Keyword::Create => "CREATE",
+            Keyword::Cross => "CROSS",
+            Keyword::Current => "CURRENT",
+            Keyword::CurrentDate => "CURRENT_DATE",
+            Keyword::CurrentTime => "CURRENT_TIME",
+            Keyword::CurrentTimestamp => "CURRENT_TIMESTAMP",
+            Keyword::CurrentUser => "CURRENT_USER",
+            Keyword::Cursor => "CURSOR",
+            Keyword::Date => "DATE",
+            Keyword::Day => "DAY",
+            Keyword::Deallocate => "DEALLOCATE",
+            Keyword::Decimal => "DECIMAL",
+            Keyword::Declare => "DECLARE",
+            Keyword::Default => "DEFAULT",
+            Keyword::Deferrable => "DEFERRABLE",
+            Keyword::Deferred => "DEFERRED",
+            Keyword::Delete => "DELETE",
+            Keyword::Desc => "DESC",
+            Keyword::Describe => "DESCRIBE",
+            Keyword::Descriptor => "DESCRIPTOR",
+            Keyword::Diagnostics => "DIAGNOSTICS",
+            Keyword::Disconnect => "DISCONNECT",
+            Keyword::Distinct => "DISTINCT",
+            Keyword::Domain => "DOMAIN",
+            Keyword::Double => "DOUBLE",
+            Keyword::Drop => "DROP",
+            Keyword::Else => "ELSE",
+            Keyword::End => "END",
+            Keyword::EndExec => "END-EXEC",
+            Keyword::Escape => "ESCAPE",
+            Keyword::Except => "EXCEPT",
+            Keyword::Exception => "EXCEPTION",
+            Keyword::Exec => "EXEC",
+            Keyword::Execute => "EXECUTE",
+            Keyword::Exists => "EXISTS",
+            Keyword::External => "EXTERNAL",
+            Keyword::Extract => "EXTRACT",
+            Keyword::False => "FALSE",
+            Keyword::Fetch => "FETCH",
+            Keyword::First => "FIRST",
+            Keyword::Float => "FLOAT",
+            Keyword::For => "FOR",
+            Keyword::Foreign => "FOREIGN",
+            Keyword::Found => "FOUND",
+            Keyword::From => "FROM",
+            Keyword::Full => "FULL",
+            Keyword::Get => "GET",
+            Keyword::Global => "GLOBAL",
+            Keyword::Go => "GO",
+            Keyword::Goto => "GOTO",
+            Keyword::Grant => "GRANT",
+            Keyword::Group => "GROUP",
+            Keyword::Having => "HAVING",
+            Keyword::Hour => "HOUR",
+            Keyword::Identity => "IDENTITY",
+            Keyword::Immediate => "IMMEDIATE",
+            Keyword::In => "IN",
+            Keyword::Indicator => "INDICATOR",
+            Keyword::Initially => "INITIALLY",
+            Keyword::Inner => "INNER",
+            Keyword::Input => "INPUT",
+            Keyword::Insensitive => "INSENSITIVE",
+            Keyword::Insert => "INSERT",
+            Keyword::Int => "INT",
+            Keyword::Integer => "INTEGER",
+            Keyword::Intersect => "INTERSECT",
+            Keyword::Interval => "INTERVAL",
+            Keyword::Into => "INTO",
+            Keyword::Is => "IS",
+            Keyword::Isolation => "ISOLATION",
+            Keyword::Join => "JOIN",
+            Keyword::Key => "KEY",
+            Keyword::Language => "LANGUAGE",
+            Keyword::Last => "LAST",
+            Keyword::Leading => "LEADING",
+            Keyword::Left => "LEFT",
+            Keyword::Level => "LEVEL",
+            Keyword::Like => "LIKE",
+            Keyword::Local => "LOCAL",
+            Keyword::Lower => "LOWER",
+            Keyword::Match => "MATCH",
+            Keyword::Max => "MAX",
+            Keyword::Min => "MIN",
+            Keyword::Minute => "MINUTE",
+            Keyword::Module => "MODULE",
+            Keyword::Month => "MONTH",
+            Keyword::Names => "NAMES",
+            Keyword::National => "NATIONAL",
+            Keyword::Natural => "NATURAL",
+            Keyword::NChar => "NCHAR",
+            Keyword::Next => "NEXT",
+            Keyword::No => "NO",
+            Keyword::Not => "NOT",
+            Keyword::Null => "NULL",
+            Keyword::Nullif => "NULLIF",
+            Keyword::Numeric => "NUMERIC",
+            Keyword::OctetLength => "OCTET_LENGTH",
+            Keyword::Of => "OF",
+            Keyword::On => "ON",
+            Keyword::Only => "ONLY",
+            Keyword::Open => "OPEN",
+            Keyword::Option => "OPTION",
+            Keyword::Or => "OR",
+            Keyword::Order => "ORDER",
+            Keyword::Outer => "OUTER",
+            Keyword::Output => "OUTPUT",
+            Keyword::Overlaps => "OVERLAPS",
+            Keyword::Pad => "PAD",
+            Keyword::Partial => "PARTIAL",
+            Keyword::Position => "POSITION",
+            Keyword::Precision => "PRECISION",
+            Keyword::Prepare => "PREPARE",
+            Keyword::Preserve => "PRESERVE",
+            Keyword::Primary => "PRIMARY",
+            Keyword::Prior => "PRIOR",
+            Keyword::Privileges => "PRIVILEGES",
+            Keyword::Procedure => "PROCEDURE",
+            Keyword::Public => "PUBLIC",
+            Keyword::Read => "READ",
+            Keyword::Real => "REAL",
+            Keyword::References => "REFERENCES",
+            Keyword::Relative => "RELATIVE",
+            Keyword::Restrict => "RESTRICT",
+            Keyword::Revoke => "REVOKE",
+            Keyword::Right => "RIGHT",
+            Keyword::Rollback => "ROLLBACK",
+            Keyword::Rows => "ROWS",
+            Keyword::Schema => "SCHEMA",
+            Keyword::Scroll => "SCROLL",
+            Keyword::Second => "SECOND",
+            Keyword::Section => "SECTION",
+            Keyword::Select => "SELECT",
+            Keyword::Session => "SESSION",
+            Keyword::SessionUser => "SESSION_USER",
+            Keyword::Set => "SET",
+            Keyword::Size => "SIZE",
+            Keyword::Smallint => "SMALLINT",
+            Keyword::Some => "SOME",
+            Keyword::Space => "SPACE",
+            Keyword::Sql => "SQL",
+            Keyword::Sqlcode => "SQLCODE",
+            Keyword::Sqlerror => "SQLERROR",
+            Keyword::Sqlstate => "SQLSTATE",
+            Keyword::Substring => "SUBSTRING",
+            Keyword::Sum => "SUM",
+            Keyword::SystemUser => "SYSTEM_USER",
+            Keyword::Table => "TABLE",
+            Keyword::Temporary => "TEMPORARY",
+            Keyword::Then => "THEN",
+            Keyword::Time => "TIME",
+            Keyword::Timestamp => "TIMESTAMP",
+            Keyword::TimezoneHour => "TIMEZONE_HOUR",
+            Keyword::TimezoneMinute => "TIMEZONE_MINUTE",
+            Keyword::To => "TO",
+            Keyword::Trailing => "TRAILING",
+            Keyword::Transaction => "TRANSACTION",
+            Keyword::Translate => "TRANSLATE",
+            Keyword::Translation => "TRANSLATION",
+            Keyword::Trim => "TRIM",
+            Keyword::True => "TRUE",
+            Keyword::Union => "UNION",
+            Keyword::Unique => "UNIQUE",
+            Keyword::Unknown => "UNKNOWN",
+            Keyword::Update => "UPDATE",
+            Keyword::Upper => "UPPER",
+            Keyword::Usage => "USAGE",
+            Keyword::User => "USER",
+            Keyword::Using => "USING",
+            Keyword::Value => "VALUE",
+            Keyword::Values => "VALUES",
+            Keyword::VarChar => "VARCHAR",
+            Keyword::Varying => "VARYING",
+            Keyword::View => "VIEW",
+            Keyword::When => "WHEN",
+            Keyword::Whenever => "WHENEVER",
+            Keyword::Where => "WHERE",
+            Keyword::With => "WITH",
+            Keyword::Work => "WORK",
+            Keyword::Write => "WRITE",
+            Keyword::Year => "YEAR",
+            Keyword::Zone => "ZONE",
+            // ADQL mathematical functions and operators
+            Keyword::Abs => "ABS",
+            Keyword::Acos => "ACOS",
+            Keyword::Asin => "ASIN",
+            Keyword::Atan => "ATAN",
+            Keyword::Atan2 => "ATAN2",
+            Keyword::Ceiling => "CEILING",
+            Keyword::Cos => "COS",
+            Keyword::Cot => "COT",
+            Keyword::Degrees => "DEGREES",
+            Keyword::Exp => "EXP",
+            Keyword::Floor => "FLOOR",
+            Keyword::Log => "LOG",
+            Keyword::Log10 => "LOG10",
+            Keyword::Mod => "MOD",
+            Keyword::Pi => "PI",
+            Keyword::Power => "POWER",
+            Keyword::Radians => "RADIANS",
+            Keyword::Rand => "RAND",
+            Keyword::Round => "ROUND",
+            Keyword::Sin => "SIN",
+            Keyword::Sqrt => "SQRT",
+            Keyword::Tan => "TAN",
+            Keyword::Top => "TOP",
+            Keyword::Truncate => "TRUNCATE",
+            // ADQL geometric functions and operators
+            Keyword::Area => "AREA",
+            Keyword::Box => "BOX",
+            Keyword::Centroid => "CENTROID",
+            Keyword::Circle => "CIRCLE",
+            Keyword::Contains => "CONTAINS",
+            Keyword::Coord1 => "COORD1",
+            Keyword::Coord2 => "COORD2",
+            Keyword::Coordsys => "COORDSYS",
+            Keyword::Distance => "DISTANCE",
+            Keyword::Intersects => "INTERSECTS",
+            Keyword::Point => "POINT",
+            Keyword::Polygon => "POLYGON",
+            Keyword::Region => "REGION",
+            // ADQL 2.1 additions
+            Keyword::BitAnd => "BIT_AND",
+            Keyword::BitNot => "BIT_NOT",
+            Keyword::BitOr => "BIT_OR",
+            Keyword::BitXor => "BIT_XOR",
+            Keyword::Ilike => "ILIKE",
+            Keyword::InUnit => "IN_UNIT",
+            Keyword::Offset => "OFFSET",
+        }
+    }
+}
+
+impl std::fmt::Display for Keyword {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Keywords serialize as their SQL spelling, e.g. `"SELECT"`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Keyword {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Keyword {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        Keyword::parse(&text)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown keyword `{text}`")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Keyword;
+    use crate::lexer::{Lexer, TokenKind};
+
+    #[test]
+    fn every_keyword_round_trips_through_its_spelling() {
+        for &keyword in Keyword::ALL {
+            assert_eq!(Keyword::parse(keyword.as_str()), Some(keyword));
+            assert_eq!(keyword.to_string(), keyword.as_str());
+
+            // `END-EXEC` is reserved by SQL-92 but lexes as `END`, `-` and `EXEC`
+            if keyword == Keyword::EndExec {
+                continue;
+            }
+            let lower_case = keyword.as_str().to_lowercase();
+            let token = Lexer::new(&lower_case).next().unwrap();
+            assert_eq!(token.kind, TokenKind::Keyword(keyword), "{lower_case}");
+        }
+    }
+
+    #[test]
+    fn parse_only_accepts_upper_case_keywords() {
+        assert_eq!(Keyword::parse("SELECT"), Some(Keyword::Select));
+        assert_eq!(Keyword::parse("select"), None);
+        assert_eq!(Keyword::parse("SELECTED"), None);
+        assert_eq!(Keyword::parse(""), None);
+    }
+
+    #[test]
+    fn all_lists_every_variant_once_in_declaration_order() {
+        for (index, &keyword) in Keyword::ALL.iter().enumerate() {
+            assert_eq!(keyword as usize, index, "{keyword:?} is out of place");
+        }
+
+        // The check above misses variants added after the last one in `ALL`, so count the
+        // variants in the declaration too
+        let source = include_str!("keyword.rs");
+        let start = source.find("pub enum Keyword {").unwrap();
+        let body = &source[start..start + source[start..].find('}').unwrap()];
+        let declared = body
+            .lines()
+            .skip(1)
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with("//"))
+            .count();
+        assert_eq!(Keyword::ALL.len(), declared);
+    }
 }
@@ -1,45 +1,113 @@
 mod keyword;
 pub use keyword::Keyword;
 
-#[derive(Debug)]
+use super::error::LexError;
+
+/// The kind of a [`Token`](crate::Token).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TokenKind {
+    /// `*`
     Asterisk,
+    /// `,`
     Comma,
+    /// `:`
     Colon,
+    /// `;`
     Semicolon,
+    /// `(`
     LeftParenthesis,
+    /// `)`
     RightParenthesis,
+    /// `%`
     Percent,
+    /// `.`
     Period,
+    /// `+`
     Plus,
+    /// `-`
     Minus,
+    /// `/`
     Solidus,
+    /// `<`
     LessThan,
+    /// `>`
     GreaterThan,
+    /// `=`
     Equals,
+    /// `_`
     Underscore,
+    /// `&`
     Ampersand,
+    /// `?`
     QuestionMark,
+    /// `^`
     Circumflex,
+    /// `~`
     Tilde,
+    /// `|`
     VerticalBar,
 
+    /// `<=`
+    LessThanOrEquals,
+    /// `>=`
+    GreaterThanOrEquals,
+    /// `<>` or `!=`
+    NotEquals,
+    /// `||`
+    Concatenate,
+
+    /// A reserved word, matched case-insensitively.
     Keyword(Keyword),
+    /// A regular identifier such as `ra`.
     Identifier,
+    /// A double-quoted identifier such as `"Right Ascension"`.
+    DelimitedIdentifier,
 
+    /// A string literal such as `'M31'`.
     SingleQuotedStringLiteral,
-    DoubleQuotedStringLiteral,
 
+    /// An unsigned integer such as `42`.
     IntegerLiteral,
+    /// A number with a decimal point such as `1.5` or `.5`.
     DecimalLiteral,
+    /// A hexadecimal integer such as `0x1F`.
     HexadecimalLiteral,
+    /// A number with an exponent such as `1.5E3`.
     ExponentLiteral,
 
+    /// A run of whitespace, only produced by [`Lexer::lossless`](crate::Lexer::lossless).
+    Whitespace,
+    /// A `--` comment up to the end of the line, excluding the newline.
     InlineComment,
+    /// A `/* ... */` comment.
+    BlockComment,
+
+    /// Source text that is not a valid token.
+    Error(LexError),
 }
 
 impl TokenKind {
-    pub fn parse_single_character(c: char) -> Option<TokenKind> {
+    /// Whether the token is whitespace or a comment, which the parser skips.
+    pub fn is_trivia(&self) -> bool {
+        matches!(
+            self,
+            TokenKind::Whitespace | TokenKind::InlineComment | TokenKind::BlockComment
+        )
+    }
+
+    pub(crate) fn parse_double_character(first: char, second: char) -> Option<TokenKind> {
+        match (first, second) {
+            ('<', '=') => Some(TokenKind::LessThanOrEquals),
+            ('>', '=') => Some(TokenKind::GreaterThanOrEquals),
+            ('<', '>') => Some(TokenKind::NotEquals),
+            ('!', '=') => Some(TokenKind::NotEquals),
+            ('|', '|') => Some(TokenKind::Concatenate),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn parse_single_character(c: char) -> Option<TokenKind> {
         match c {
             '*' => Some(TokenKind::Asterisk),
             ',' => Some(TokenKind::Comma),
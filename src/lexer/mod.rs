@@ -1,5 +1,15 @@
+//! Splitting ADQL source text into tokens, and locating them by line and column.
+
+mod error;
 mod kind;
+mod number;
+mod source_map;
 mod token;
 mod tokenizer;
 
+pub use error::LexError;
+pub use kind::{Keyword, TokenKind};
+pub use number::Decimal;
+pub use source_map::{Location, SourceMap};
+pub use token::{Span, Token};
 pub use tokenizer::Lexer;
@@ -0,0 +1,281 @@
+use std::fmt;
+
+/// An exact decimal number `digits × 10^exponent`.
+///
+/// The digits are kept as text so that literals of any size or precision survive unchanged.
+/// Values are normalised (no leading zeros, trailing zeros folded into the exponent), so two
+/// decimals are equal exactly when they denote the same number. The exponent is an `i128`, so
+/// every exponent that fits in an `i64` is kept exactly, along with the shift from the digits.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Decimal {
+    digits: String,
+    exponent: i128,
+}
+
+impl Decimal {
+    /// Parses an unsigned exact or approximate numeric literal such as `12`, `.5`, `1.` or
+    /// `6.02E+23`. Returns `None` if the text is not such a literal or its exponent does not
+    /// fit in an `i128`.
+    pub fn parse(text: &str) -> Option<Decimal> {
+        let (mantissa, exponent) = match text.find(['e', 'E']) {
+            Some(index) => (&text[..index], text[index + 1..].parse::<i128>().ok()?),
+            None => (text, 0),
+        };
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if integer.is_empty() && fraction.is_empty() {
+            return None;
+        }
+        if !integer
+            .bytes()
+            .chain(fraction.bytes())
+            .all(|b| b.is_ascii_digit())
+        {
+            return None;
+        }
+
+        let fraction_length = i128::try_from(fraction.len()).ok()?;
+        Decimal::normalise(
+            format!("{integer}{fraction}"),
+            exponent.checked_sub(fraction_length)?,
+        )
+    }
+
+    /// Parses the digits of a hexadecimal literal, without the `0x` prefix.
+    pub fn from_hexadecimal(text: &str) -> Option<Decimal> {
+        if text.is_empty() {
+            return None;
+        }
+
+        // Least significant digit first
+        let mut digits = vec![0u32];
+        for c in text.chars() {
+            let mut carry = c.to_digit(16)?;
+            for digit in &mut digits {
+                let value = *digit * 16 + carry;
+                *digit = value % 10;
+                carry = value / 10;
+            }
+            while carry > 0 {
+                digits.push(carry % 10);
+                carry /= 10;
+            }
+        }
+
+        let digits = digits
+            .iter()
+            .rev()
+            .filter_map(|digit| char::from_digit(*digit, 10))
+            .collect();
+        Decimal::normalise(digits, 0)
+    }
+
+    /// Strips the zeros from `digits`. Fails if the exponent of the last digit or of the first
+    /// digit would not fit in an `i128`, so that `Display` can't overflow.
+    fn normalise(digits: String, exponent: i128) -> Option<Decimal> {
+        let digits = digits.trim_start_matches('0');
+        let trimmed = digits.trim_end_matches('0');
+        if trimmed.is_empty() {
+            return Some(Decimal {
+                digits: "0".to_string(),
+                exponent: 0,
+            });
+        }
+        let trailing_zeros = i128::try_from(digits.len() - trimmed.len()).ok()?;
+        let exponent = exponent.checked_add(trailing_zeros)?;
+        exponent.checked_add(i128::try_from(trimmed.len()).ok()?)?;
+        Some(Decimal {
+            digits: trimmed.to_string(),
+            exponent,
+        })
+    }
+
+    /// The digits without leading or trailing zeros, or `0` for zero.
+    pub fn digits(&self) -> &str {
+        &self.digits
+    }
+
+    /// The power of ten the digits are multiplied by.
+    pub fn exponent(&self) -> i128 {
+        self.exponent
+    }
+
+    /// Whether the value is a whole number.
+    pub fn is_integer(&self) -> bool {
+        self.exponent >= 0
+    }
+
+    /// The value as an `i64`, if it is a whole number that fits.
+    pub fn to_i64(&self) -> Option<i64> {
+        if !self.is_integer() {
+            return None;
+        }
+        let mut value: i64 = self.digits.parse().ok()?;
+        for _ in 0..self.exponent {
+            value = value.checked_mul(10)?;
+        }
+        Some(value)
+    }
+
+    /// The nearest `f64` to the value.
+    pub fn to_f64(&self) -> f64 {
+        format!("{}e{}", self.digits, self.exponent)
+            .parse()
+            .unwrap_or(f64::INFINITY)
+    }
+}
+
+impl fmt::Display for Decimal {
+    /// Writes positional notation such as `1500` or `0.015`, falling back to `1.5E-30`
+    /// style scientific notation for very large or small exponents.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const MAX_PADDING: i128 = 20;
+
+        let digits = &self.digits;
+        let length = digits.len() as i128;
+        match self.exponent {
+            exponent @ 0..=MAX_PADDING => {
+                write!(f, "{digits}{}", "0".repeat(exponent as usize))
+            }
+            exponent if exponent < 0 && exponent >= -length => {
+                let (integer, fraction) = digits.split_at((length + exponent) as usize);
+                let integer = if integer.is_empty() { "0" } else { integer };
+                write!(f, "{integer}.{fraction}")
+            }
+            exponent if exponent < 0 && exponent >= -length - MAX_PADDING => {
+                let zeros = "0".repeat((-exponent - length) as usize);
+                write!(f, "0.{zeros}{digits}")
+            }
+            exponent => {
+                let (first, rest) = digits.split_at(1);
+                // Normalising checked that the exponent of the first digit fits
+                let exponent = exponent + (length - 1);
+                match rest {
+                    "" => write!(f, "{first}E{exponent}"),
+                    rest => write!(f, "{first}.{rest}E{exponent}"),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Decimal;
+    use crate::lexer::{LexError, Lexer, TokenKind};
+
+    fn decimal(text: &str) -> Decimal {
+        Decimal::parse(text).unwrap_or_else(|| panic!("`{text}` is a valid literal"))
+    }
+
+    #[test]
+    fn leading_and_trailing_points() {
+        assert_eq!(decimal(".5"), decimal("0.5"));
+        assert_eq!(decimal(".5").to_string(), "0.5");
+        assert_eq!(decimal("5."), decimal("5"));
+        assert_eq!(decimal("5.").to_string(), "5");
+        assert_eq!(decimal("5.").to_i64(), Some(5));
+        assert_eq!(Decimal::parse("."), None);
+        assert_eq!(Decimal::parse(".e1"), None);
+    }
+
+    #[test]
+    fn values_are_normalised() {
+        let value = decimal("001500.0");
+        assert_eq!((value.digits(), value.exponent()), ("15", 2));
+        assert_eq!(decimal("0.000"), decimal("0"));
+        assert_eq!(decimal("0e99").to_string(), "0");
+        assert_eq!(decimal("1.5E3"), decimal("1500"));
+        assert_eq!(decimal("15e-3").to_string(), "0.015");
+    }
+
+    #[test]
+    fn huge_exponents_are_kept_exactly() {
+        let value = decimal("1e9223372036854775807");
+        assert_eq!(value.exponent(), i64::MAX as i128);
+        assert_eq!(value.to_string(), "1E9223372036854775807");
+        assert_eq!(value.to_i64(), None);
+        assert_eq!(value.to_f64(), f64::INFINITY);
+
+        // Beyond `i64`, and shifted by the digits
+        let value = decimal("12.5e9223372036854775808");
+        assert_eq!(value.exponent(), i64::MAX as i128);
+        assert_eq!(value.to_string(), "1.25E9223372036854775809");
+        assert_ne!(
+            decimal("1e9223372036854775808"),
+            decimal("1e9223372036854775807")
+        );
+    }
+
+    #[test]
+    fn tiny_exponents_are_kept_exactly() {
+        let value = decimal("1e-9223372036854775808");
+        assert_eq!(value.exponent(), i64::MIN as i128);
+        assert_eq!(value.to_string(), "1E-9223372036854775808");
+        assert_eq!(value.to_f64(), 0.0);
+
+        let value = decimal("0.25e-9223372036854775808");
+        assert_eq!(value.to_string(), "2.5E-9223372036854775809");
+        assert!(!value.is_integer());
+    }
+
+    #[test]
+    fn exponents_beyond_i128_are_rejected_not_wrapped() {
+        assert_eq!(Decimal::parse(&format!("1e{}", i128::MAX)), None);
+        assert_eq!(Decimal::parse(&format!("1.5e{}", i128::MIN)), None);
+        assert_eq!(
+            Decimal::parse("1e999999999999999999999999999999999999999"),
+            None
+        );
+        assert!(Decimal::parse(&format!("1e{}", i128::MAX - 1)).is_some());
+    }
+
+    #[test]
+    fn hexadecimal_literals() {
+        assert_eq!(Decimal::from_hexadecimal("1F").unwrap().to_i64(), Some(31));
+        assert_eq!(Decimal::from_hexadecimal("00").unwrap().to_string(), "0");
+        assert_eq!(
+            Decimal::from_hexadecimal("FFFFFFFFFFFFFFFFFFFF")
+                .unwrap()
+                .to_string(),
+            "1208925819614629174706175"
+        );
+        assert_eq!(Decimal::from_hexadecimal(""), None);
+        assert_eq!(Decimal::from_hexadecimal("1G"), None);
+
+        for source in ["0x1F", "0X1f"] {
+            let token = Lexer::new(source).next().unwrap();
+            assert_eq!(token.kind, TokenKind::HexadecimalLiteral);
+            assert_eq!(token.to_i64(source), Some(31));
+        }
+        for source in ["0x", "0xG"] {
+            let token = Lexer::new(source).next().unwrap();
+            assert_eq!(token.kind, TokenKind::Error(LexError::MalformedNumber));
+            assert_eq!(token.to_decimal(source), None);
+        }
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for text in [
+            "0",
+            "7",
+            "1500",
+            "0.015",
+            "123.456",
+            "1e20",
+            "1e21",
+            "0.000000000000000000000123",
+            "1.5e-40",
+            "98765432109876543210987654321",
+            "1e9223372036854775807",
+            "3.25e-9223372036854775900",
+        ] {
+            let value = decimal(text);
+            assert_eq!(
+                decimal(&value.to_string()),
+                value,
+                "{text} printed as {value}"
+            );
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use super::token::Span;
+
+/// A 1-based line and column in the source text.
+///
+/// Columns are counted from the start of the line in characters (as shown to people), in
+/// UTF-8 bytes and in UTF-16 code units (as used by editors speaking the Language Server
+/// Protocol).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Location {
+    /// The 1-based line number.
+    pub line: usize,
+    /// The 1-based column, counted in Unicode scalar values.
+    pub character_column: usize,
+    /// The 1-based column, counted in bytes.
+    pub utf8_column: usize,
+    /// The 1-based column, counted in UTF-16 code units.
+    pub utf16_column: usize,
+}
+
+/// Converts byte offsets and spans into line and column numbers.
+pub struct SourceMap<'a> {
+    source: &'a str,
+    /// Byte offset at which each line starts.
+    line_starts: Vec<usize>,
+}
+
+impl<'a> SourceMap<'a> {
+    /// Indexes the line starts of `source`.
+    pub fn new(source: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        Self {
+            source,
+            line_starts,
+        }
+    }
+
+    /// The number of lines, counting the empty line after a final line terminator.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// The location of the byte `offset`, which is clamped to the end of the source and
+    /// rounded down to the start of the character it falls inside.
+    pub fn location(&self, offset: usize) -> Location {
+        let mut offset = offset.min(self.source.len());
+        while !self.source.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line_index = match self.line_starts.binary_search(&offset) {
+            Ok(index) => index,
+            Err(index) => index - 1,
+        };
+        let line_start = self.line_starts[line_index];
+        let before = &self.source[line_start..offset];
+
+        Location {
+            line: line_index + 1,
+            character_column: before.chars().count() + 1,
+            utf8_column: before.len() + 1,
+            utf16_column: before.encode_utf16().count() + 1,
+        }
+    }
+
+    /// The locations of the first byte of `span` and of the byte just past its end.
+    pub fn span_locations(&self, span: Span) -> (Location, Location) {
+        (self.location(span.start), self.location(span.end()))
+    }
+
+    /// The span of the 1-based `line`, excluding its line terminator.
+    pub fn line_span(&self, line: usize) -> Option<Span> {
+        let start = *self.line_starts.get(line.checked_sub(1)?)?;
+        let end = match self.line_starts.get(line) {
+            Some(next_start) => next_start - 1,
+            None => self.source.len(),
+        };
+        let text = &self.source[start..end];
+        let length = text.strip_suffix('\r').unwrap_or(text).len();
+        Some(Span::new(start, length))
+    }
+
+    /// The text of the 1-based `line`, excluding its line terminator.
+    pub fn line_text(&self, line: usize) -> Option<&'a str> {
+        self.line_span(line).map(|span| &self.source[span.range()])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Location, SourceMap};
+    use crate::lexer::Span;
+
+    fn location(line: usize, character: usize, utf8: usize, utf16: usize) -> Location {
+        Location {
+            line,
+            character_column: character,
+            utf8_column: utf8,
+            utf16_column: utf16,
+        }
+    }
+
+    #[test]
+    fn columns_count_characters_bytes_and_utf16_units() {
+        // `𝔸` is 1 character, 4 bytes and 2 UTF-16 code units
+        let source = "ab\n𝔸𝔸x";
+        let map = SourceMap::new(source);
+        assert_eq!(map.location(0), location(1, 1, 1, 1));
+        assert_eq!(map.location(3), location(2, 1, 1, 1));
+        assert_eq!(map.location(7), location(2, 2, 5, 3));
+        assert_eq!(map.location(11), location(2, 3, 9, 5));
+        assert_eq!(map.location(12), location(2, 4, 10, 6));
+    }
+
+    #[test]
+    fn offsets_inside_a_character_round_down() {
+        let map = SourceMap::new("é\nx");
+        assert_eq!(map.location(1), location(1, 1, 1, 1));
+        let map = SourceMap::new("a𝔸");
+        for offset in 1..5 {
+            assert_eq!(map.location(offset), location(1, 2, 2, 2));
+        }
+    }
+
+    #[test]
+    fn offsets_past_the_end_are_clamped() {
+        let map = SourceMap::new("ab\ncd");
+        assert_eq!(map.location(5), location(2, 3, 3, 3));
+        assert_eq!(map.location(100), location(2, 3, 3, 3));
+        assert_eq!(
+            map.span_locations(Span::new(1, 3)),
+            (location(1, 2, 2, 2), location(2, 2, 2, 2))
+        );
+    }
+
+    #[test]
+    fn crlf_terminators_are_left_out_of_lines() {
+        let source = "SELECT a\r\nFROM t\r\n";
+        let map = SourceMap::new(source);
+        assert_eq!(map.line_count(), 3);
+        assert_eq!(map.line_text(1), Some("SELECT a"));
+        assert_eq!(map.line_span(1), Some(Span::new(0, 8)));
+        assert_eq!(map.line_text(2), Some("FROM t"));
+        assert_eq!(map.line_span(2), Some(Span::new(10, 6)));
+        assert_eq!(map.line_text(3), Some(""));
+        // The `\r` is still a column of its line
+        assert_eq!(map.location(8), location(1, 9, 9, 9));
+        assert_eq!(map.location(10), location(2, 1, 1, 1));
+    }
+
+    #[test]
+    fn the_last_line_needs_no_terminator() {
+        let map = SourceMap::new("a\n𝔸𝔸");
+        assert_eq!(map.line_count(), 2);
+        assert_eq!(map.line_text(2), Some("𝔸𝔸"));
+        assert_eq!(map.line_span(2), Some(Span::new(2, 8)));
+        assert_eq!(map.location(10), location(2, 3, 9, 5));
+
+        let map = SourceMap::new("");
+        assert_eq!(map.line_count(), 1);
+        assert_eq!(map.line_text(1), Some(""));
+    }
+
+    #[test]
+    fn lines_out_of_range_have_no_text() {
+        let map = SourceMap::new("a\nb");
+        assert_eq!(map.line_text(0), None);
+        assert_eq!(map.line_text(3), None);
+        assert_eq!(map.line_span(usize::MAX), None);
+    }
+}
@@ -1,65 +1,190 @@
-use std::ops::Range;
+use std::{borrow::Cow, ops::Range};
 
 use super::kind::TokenKind;
+use super::number::Decimal;
+use crate::colour;
 
-#[derive(Debug)]
+/// A range of bytes in the source text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Span {
+    /// The byte offset of the first byte.
     pub start: usize,
+    /// The number of bytes.
     pub length: usize,
 }
 
 impl Span {
+    /// The span of `length` bytes from `start`.
+    pub const fn new(start: usize, length: usize) -> Self {
+        Self { start, length }
+    }
+
+    /// The smallest span that covers both spans.
+    pub fn merge(self, other: Span) -> Span {
+        let start = self.start.min(other.start);
+        let end = self.end().max(other.end());
+        Span::new(start, end - start)
+    }
+
+    /// The byte offset just past the last byte.
     pub const fn end(&self) -> usize {
         self.start + self.length
     }
 
+    /// The span as a range, for slicing the source.
     pub const fn range(&self) -> Range<usize> {
         self.start..self.end()
     }
 }
 
-#[derive(Debug)]
+/// A token of the source text. Tokens do not own their text, which is found with
+/// [`Token::text`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Token {
+    /// Where the token is in the source.
     pub span: Span,
+    /// What the token is.
     pub kind: TokenKind,
 }
 
 impl Token {
+    /// The text of the token in `source`, which must be the text it was lexed from.
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        &source[self.span.range()]
+    }
+
+    /// The contents of a character string literal or delimited identifier without the
+    /// enclosing quotes and with doubled quotes collapsed, e.g. `'O''Brien'` to `O'Brien`.
+    pub fn unescaped<'a>(&self, source: &'a str) -> Option<Cow<'a, str>> {
+        let (quote, escaped_quote) = match self.kind {
+            TokenKind::SingleQuotedStringLiteral => ("'", "''"),
+            TokenKind::DelimitedIdentifier => ("\"", "\"\""),
+            _ => return None,
+        };
+        let text = self.text(source);
+        let contents = &text[1..text.len() - 1];
+        if contents.contains(escaped_quote) {
+            Some(Cow::Owned(contents.replace(escaped_quote, quote)))
+        } else {
+            Some(Cow::Borrowed(contents))
+        }
+    }
+
+    /// The exact value of a numeric literal.
+    pub fn to_decimal(self, source: &str) -> Option<Decimal> {
+        let text = self.text(source);
+        match self.kind {
+            TokenKind::IntegerLiteral | TokenKind::DecimalLiteral | TokenKind::ExponentLiteral => {
+                Decimal::parse(text)
+            }
+            TokenKind::HexadecimalLiteral => Decimal::from_hexadecimal(&text[2..]),
+            _ => None,
+        }
+    }
+
+    /// The value of a numeric literal if it is a whole number that fits in an `i64`.
+    pub fn to_i64(self, source: &str) -> Option<i64> {
+        self.to_decimal(source)?.to_i64()
+    }
+
+    /// The value of a numeric literal rounded to the nearest `f64`.
+    pub fn to_f64(self, source: &str) -> Option<f64> {
+        self.to_decimal(source).map(|decimal| decimal.to_f64())
+    }
+
+    /// The ANSI escape sequence that starts the colour of the token in terminal output.
     pub fn begin_highlight(&self) -> &'static str {
         match self.kind {
-            TokenKind::Asterisk => "\x1b[33m",                    // Yellow
-            TokenKind::Comma => "\x1b[33m",                       // Yellow
-            TokenKind::Colon => "\x1b[33m",                       // Yellow
-            TokenKind::Semicolon => "\x1b[33m",                   // Yellow
-            TokenKind::LeftParenthesis => "\x1b[33m",             // Yellow
-            TokenKind::RightParenthesis => "\x1b[33m",            // Yellow
-            TokenKind::Percent => "\x1b[33m",                     // Yellow
-            TokenKind::Period => "\x1b[33m",                      // Yellow
-            TokenKind::Plus => "\x1b[33m",                        // Yellow
-            TokenKind::Minus => "\x1b[33m",                       // Yellow
-            TokenKind::Solidus => "\x1b[33m",                     // Yellow
-            TokenKind::LessThan => "\x1b[33m",                    // Yellow
-            TokenKind::GreaterThan => "\x1b[33m",                 // Yellow
-            TokenKind::Equals => "\x1b[33m",                      // Yellow
-            TokenKind::Underscore => "\x1b[33m",                  // Yellow
-            TokenKind::Ampersand => "\x1b[33m",                   // Yellow
-            TokenKind::QuestionMark => "\x1b[33m",                // Yellow
-            TokenKind::Circumflex => "\x1b[33m",                  // Yellow
-            TokenKind::Tilde => "\x1b[33m",                       // Yellow
-            TokenKind::VerticalBar => "\x1b[33m",                 // Yellow
-            TokenKind::Keyword(_) => "\x1b[1;31m",                // Bright Red
-            TokenKind::Identifier => "\x1b[1;34m",                // Bright Blue
-            TokenKind::SingleQuotedStringLiteral => "\x1b[1;35m", // Magenta
-            TokenKind::DoubleQuotedStringLiteral => "\x1b[1;35m", // Magenta
-            TokenKind::IntegerLiteral => "\x1b[1;36m",            // Cyan
-            TokenKind::DecimalLiteral => "\x1b[1;36m",            // Cyan
-            TokenKind::HexadecimalLiteral => "\x1b[1;36m",        // Cyan
-            TokenKind::ExponentLiteral => "\x1b[1;36m",           // Cyan
-            TokenKind::InlineComment => "\x1b[90m",               // Bright Black (Gray)
+            TokenKind::Asterisk => colour::YELLOW,
+            TokenKind::Comma => colour::YELLOW,
+            TokenKind::Colon => colour::YELLOW,
+            TokenKind::Semicolon => colour::YELLOW,
+            TokenKind::LeftParenthesis => colour::YELLOW,
+            TokenKind::RightParenthesis => colour::YELLOW,
+            TokenKind::Percent => colour::YELLOW,
+            TokenKind::Period => colour::YELLOW,
+            TokenKind::Plus => colour::YELLOW,
+            TokenKind::Minus => colour::YELLOW,
+            TokenKind::Solidus => colour::YELLOW,
+            TokenKind::LessThan => colour::YELLOW,
+            TokenKind::GreaterThan => colour::YELLOW,
+            TokenKind::Equals => colour::YELLOW,
+            TokenKind::Underscore => colour::YELLOW,
+            TokenKind::Ampersand => colour::YELLOW,
+            TokenKind::QuestionMark => colour::YELLOW,
+            TokenKind::Circumflex => colour::YELLOW,
+            TokenKind::Tilde => colour::YELLOW,
+            TokenKind::VerticalBar => colour::YELLOW,
+            TokenKind::LessThanOrEquals => colour::YELLOW,
+            TokenKind::GreaterThanOrEquals => colour::YELLOW,
+            TokenKind::NotEquals => colour::YELLOW,
+            TokenKind::Concatenate => colour::YELLOW,
+            TokenKind::Keyword(_) => colour::BRIGHT_RED,
+            TokenKind::Identifier => colour::BRIGHT_BLUE,
+            TokenKind::DelimitedIdentifier => colour::BRIGHT_BLUE,
+            TokenKind::SingleQuotedStringLiteral => colour::MAGENTA,
+            TokenKind::IntegerLiteral => colour::CYAN,
+            TokenKind::DecimalLiteral => colour::CYAN,
+            TokenKind::HexadecimalLiteral => colour::CYAN,
+            TokenKind::ExponentLiteral => colour::CYAN,
+            TokenKind::Whitespace => "",
+            TokenKind::InlineComment => colour::GRAY,
+            TokenKind::BlockComment => colour::GRAY,
+            TokenKind::Error(_) => colour::UNDERLINED_RED,
         }
     }
 
+    /// The ANSI escape sequence that ends the colour started by [`Token::begin_highlight`].
     pub fn end_highlight(&self) -> &'static str {
-        "\x1b[0m"
+        match self.kind {
+            TokenKind::Whitespace => "",
+            _ => colour::RESET,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use crate::lexer::Lexer;
+
+    /// The unescaped contents of the only token of `source`.
+    fn unescaped(source: &str) -> Option<Cow<'_, str>> {
+        let mut tokens = Lexer::new(source);
+        let token = tokens.next().unwrap();
+        assert_eq!(tokens.next(), None, "{source}");
+        token.unescaped(source)
+    }
+
+    #[test]
+    fn doubled_quotes_are_collapsed() {
+        assert_eq!(unescaped("'O''Brien'").as_deref(), Some("O'Brien"));
+        assert_eq!(unescaped("'''a'''").as_deref(), Some("'a'"));
+        assert_eq!(unescaped("\"a\"\"b\"").as_deref(), Some("a\"b"));
+        // The other kind of quote is left alone
+        assert_eq!(unescaped("'say \"hi\"'").as_deref(), Some("say \"hi\""));
+        assert_eq!(unescaped("\"it's\"").as_deref(), Some("it's"));
+    }
+
+    #[test]
+    fn contents_without_escapes_are_borrowed() {
+        assert!(matches!(unescaped("'M31'"), Some(Cow::Borrowed("M31"))));
+        assert!(matches!(unescaped("''"), Some(Cow::Borrowed(""))));
+        assert!(matches!(
+            unescaped("\"Right Ascension\""),
+            Some(Cow::Borrowed("Right Ascension"))
+        ));
+        assert!(matches!(unescaped("'O''Brien'"), Some(Cow::Owned(_))));
+    }
+
+    #[test]
+    fn only_quoted_tokens_have_contents() {
+        assert_eq!(unescaped("ra"), None);
+        assert_eq!(unescaped("42"), None);
+        assert_eq!(unescaped("'unterminated"), None);
+        assert_eq!(unescaped("\"\""), None);
     }
 }
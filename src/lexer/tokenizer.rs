@@ -1,16 +1,41 @@
+use super::error::LexError;
 use super::kind::{Keyword, TokenKind};
 use super::token::{Span, Token};
 
+/// Splits ADQL source text into [`Token`]s.
+///
+/// Comments are always emitted, since they are needed to reproduce the source, and the
+/// parser skips them as trivia. Invalid text becomes a [`TokenKind::Error`] token and lexing
+/// carries on after it.
 pub struct Lexer<'a> {
     source: &'a str,
+    /// Byte offset of the next character in `source`.
     offset: usize,
+    /// Emit whitespace as tokens so that the token texts concatenate back to `source`.
+    lossless: bool,
 }
 
 impl<'a> Lexer<'a> {
+    /// A lexer that skips whitespace.
     pub fn new(source: &'a str) -> Self {
-        Self { source, offset: 0 }
+        Self {
+            source,
+            offset: 0,
+            lossless: false,
+        }
     }
 
+    /// A lexer that also emits `Whitespace` tokens, so that every byte of `source` belongs
+    /// to exactly one token.
+    pub fn lossless(source: &'a str) -> Self {
+        Self {
+            source,
+            offset: 0,
+            lossless: true,
+        }
+    }
+
+    /// The next token, without consuming it.
     pub fn peek_next(&mut self) -> Option<Token> {
         let old_offset = self.offset;
         let next_token = self.next_token();
@@ -18,19 +43,34 @@ impl<'a> Lexer<'a> {
         next_token
     }
 
+    /// Consumes and returns the next token, or `None` at the end of the source.
     pub fn next_token(&mut self) -> Option<Token> {
-        self.skip_whitespace();
+        if self.lossless {
+            if let Some(token) = self.lex_whitespace() {
+                return Some(token);
+            }
+        } else {
+            self.skip_whitespace();
+        }
 
         // Handle comments
         if let Some(token) = self.lex_inline_comment() {
             return Some(token);
         }
+        if let Some(token) = self.lex_block_comment() {
+            return Some(token);
+        }
 
         // Handle numeric literals
         if let Some(token) = self.lex_numeric_literal() {
             return Some(token);
         }
 
+        // Handle double character tokens
+        if let Some(token) = self.lex_double_character_token() {
+            return Some(token);
+        }
+
         // Handle single character tokens
         if let Some(token) = self.lex_single_character_token() {
             return Some(token);
@@ -46,7 +86,39 @@ impl<'a> Lexer<'a> {
             return Some(token);
         }
 
-        None
+        // Anything else is a single invalid character
+        self.lex_invalid_character()
+    }
+
+    fn lex_invalid_character(&mut self) -> Option<Token> {
+        let initial_offset = self.offset;
+        let current_char = self.peek()?;
+
+        self.advance();
+        Some(Token {
+            kind: TokenKind::Error(LexError::InvalidCharacter(current_char)),
+            span: Span {
+                start: initial_offset,
+                length: self.offset - initial_offset,
+            },
+        })
+    }
+
+    fn lex_double_character_token(&mut self) -> Option<Token> {
+        let initial_offset = self.offset;
+        let first_char = self.peek()?;
+        let second_char = self.peek_ahead(1)?;
+        let kind = TokenKind::parse_double_character(first_char, second_char)?;
+
+        self.advance();
+        self.advance();
+        Some(Token {
+            kind,
+            span: Span {
+                start: initial_offset,
+                length: self.offset - initial_offset,
+            },
+        })
     }
 
     fn lex_single_character_token(&mut self) -> Option<Token> {
@@ -99,23 +171,44 @@ impl<'a> Lexer<'a> {
     fn lex_string_literal(&mut self) -> Option<Token> {
         let initial_offset = self.offset;
         let current_char = self.peek()?;
-        let (open_quote, kind) = match current_char {
-            '"' => ('"', TokenKind::DoubleQuotedStringLiteral),
-            '\'' => ('\'', TokenKind::SingleQuotedStringLiteral),
+        let (open_quote, mut kind, error) = match current_char {
+            '"' => (
+                '"',
+                TokenKind::DelimitedIdentifier,
+                LexError::UnterminatedIdentifier,
+            ),
+            '\'' => (
+                '\'',
+                TokenKind::SingleQuotedStringLiteral,
+                LexError::UnterminatedString,
+            ),
             _ => return None,
         };
         self.advance();
+        let mut terminated = false;
         while let Some(current_char) = self.peek() {
             self.advance();
             if current_char == open_quote {
+                // A doubled quote is an escaped quote, not the closing one
+                if self.peek() == Some(open_quote) {
+                    self.advance();
+                    continue;
+                }
+                terminated = true;
                 break;
             }
         }
+        let length = self.offset - initial_offset;
+        if !terminated {
+            kind = TokenKind::Error(error);
+        } else if kind == TokenKind::DelimitedIdentifier && length == 2 {
+            kind = TokenKind::Error(LexError::EmptyIdentifier);
+        }
         Some(Token {
             kind,
             span: Span {
                 start: initial_offset,
-                length: self.offset - initial_offset,
+                length,
             },
         })
     }
@@ -148,38 +241,43 @@ impl<'a> Lexer<'a> {
         })
     }
 
-    fn lex_numeric_literal(&mut self) -> Option<Token> {
+    fn lex_block_comment(&mut self) -> Option<Token> {
         let initial_offset = self.offset;
-        let first_char = self.peek()?;
-        let second_char = self.peek_ahead(1)?;
-
-        // Hexadecimal
-        if let ('0', 'x') = (first_char, second_char) {
-            return self.lex_hexadecimal_literal();
-        }
-
-        if !first_char.is_ascii_digit() {
-            return None;
-        }
-
-        let mut kind = TokenKind::IntegerLiteral;
-        while let Some(c) = self.peek() {
-            if c == '.' {
-                kind = TokenKind::DecimalLiteral;
+        let current_char = self.peek()?;
+        let next_char = self.peek_ahead(1)?;
+        match (current_char, next_char) {
+            ('/', '*') => {
                 self.advance();
-                continue;
-            } else if c == 'e' || c == 'E' {
-                kind = TokenKind::ExponentLiteral;
                 self.advance();
-                continue;
             }
+            _ => return None,
+        }
 
-            if !c.is_ascii_digit() {
+        // SQL:2003 allows bracketed comments to nest
+        let mut depth = 1;
+        while depth > 0 {
+            let Some(current_char) = self.peek() else {
                 break;
+            };
+            match (current_char, self.peek_ahead(1)) {
+                ('/', Some('*')) => {
+                    depth += 1;
+                    self.advance();
+                }
+                ('*', Some('/')) => {
+                    depth -= 1;
+                    self.advance();
+                }
+                _ => {}
             }
             self.advance();
         }
 
+        let kind = if depth == 0 {
+            TokenKind::BlockComment
+        } else {
+            TokenKind::Error(LexError::UnterminatedComment)
+        };
         Some(Token {
             kind,
             span: Span {
@@ -189,27 +287,117 @@ impl<'a> Lexer<'a> {
         })
     }
 
+    fn lex_numeric_literal(&mut self) -> Option<Token> {
+        let initial_offset = self.offset;
+        let first_char = self.peek()?;
+
+        // Hexadecimal
+        if let ('0', Some('x' | 'X')) = (first_char, self.peek_ahead(1)) {
+            return self.lex_hexadecimal_literal();
+        }
+
+        // unsigned_integer [ . [unsigned_integer] ] | . unsigned_integer
+        let leading_digits = match first_char {
+            '0'..='9' => self.skip_digits(),
+            '.' if self.peek_ahead(1).is_some_and(|c| c.is_ascii_digit()) => 0,
+            _ => return None,
+        };
+        let mut kind = TokenKind::IntegerLiteral;
+        let mut valid = true;
+        if self.peek() == Some('.') {
+            kind = TokenKind::DecimalLiteral;
+            self.advance();
+            valid = self.skip_digits() > 0 || leading_digits > 0;
+        }
+
+        // E [sign] unsigned_integer
+        if let Some('e' | 'E') = self.peek() {
+            kind = TokenKind::ExponentLiteral;
+            self.advance();
+            if let Some('+' | '-') = self.peek() {
+                self.advance();
+            }
+            valid &= self.skip_digits() > 0;
+        }
+
+        self.finish_numeric_literal(initial_offset, kind, valid)
+    }
+
     fn lex_hexadecimal_literal(&mut self) -> Option<Token> {
         let initial_offset = self.offset;
         let first_char = self.peek()?;
         let second_char = self.peek_ahead(1)?;
         match (first_char, second_char) {
-            ('0', 'x') => {}
+            ('0', 'x' | 'X') => {}
             _ => return None,
         }
 
         self.advance();
         self.advance();
 
+        let mut digits = 0;
+        while let Some(c) = self.peek() {
+            if !c.is_ascii_hexdigit() {
+                break;
+            }
+            self.advance();
+            digits += 1;
+        }
+
+        self.finish_numeric_literal(initial_offset, TokenKind::HexadecimalLiteral, digits > 0)
+    }
+
+    /// A literal that runs straight into letters, digits or another period, as in `1.2.3`,
+    /// `1e2e3` or `12abc`, is consumed whole and reported as malformed.
+    fn finish_numeric_literal(
+        &mut self,
+        initial_offset: usize,
+        kind: TokenKind,
+        mut valid: bool,
+    ) -> Option<Token> {
+        while let Some(c) = self.peek() {
+            if !(c.is_ascii_alphanumeric() || c == '_' || c == '.') {
+                break;
+            }
+            self.advance();
+            valid = false;
+        }
+
+        let kind = if valid {
+            kind
+        } else {
+            TokenKind::Error(LexError::MalformedNumber)
+        };
+        Some(Token {
+            kind,
+            span: Span {
+                start: initial_offset,
+                length: self.offset - initial_offset,
+            },
+        })
+    }
+
+    fn skip_digits(&mut self) -> usize {
+        let mut digits = 0;
         while let Some(c) = self.peek() {
-            if !c.is_digit(16) {
+            if !c.is_ascii_digit() {
                 break;
             }
             self.advance();
+            digits += 1;
+        }
+        digits
+    }
+
+    fn lex_whitespace(&mut self) -> Option<Token> {
+        let initial_offset = self.offset;
+        self.skip_whitespace();
+        if self.offset == initial_offset {
+            return None;
         }
 
         Some(Token {
-            kind: TokenKind::HexadecimalLiteral,
+            kind: TokenKind::Whitespace,
             span: Span {
                 start: initial_offset,
                 length: self.offset - initial_offset,
@@ -228,14 +416,254 @@ impl<'a> Lexer<'a> {
     }
 
     fn advance(&mut self) {
-        self.offset += 1;
+        if let Some(c) = self.peek() {
+            self.offset += c.len_utf8();
+        }
     }
 
     fn peek(&self) -> Option<char> {
-        self.source.chars().nth(self.offset)
+        self.source[self.offset..].chars().next()
     }
 
     fn peek_ahead(&self, next: usize) -> Option<char> {
-        self.source.chars().nth(self.offset + next)
+        self.source[self.offset..].chars().nth(next)
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        self.next_token()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Lexer;
+    use crate::lexer::{Keyword, LexError, Span, TokenKind};
+
+    /// The kind and text of each token of `source`, skipping whitespace.
+    fn tokens(source: &str) -> Vec<(TokenKind, &str)> {
+        Lexer::new(source)
+            .map(|token| (token.kind, token.text(source)))
+            .collect()
+    }
+
+    #[test]
+    fn spans_are_byte_offsets_around_multi_byte_characters() {
+        let source = "SELECT 'α' FROM t -- é\n/* 天 */ \"déc\"";
+        let spans: Vec<(TokenKind, Span)> = Lexer::new(source)
+            .map(|token| (token.kind, token.span))
+            .collect();
+        let span = |start, length| Span { start, length };
+        assert_eq!(
+            spans,
+            [
+                (TokenKind::Keyword(Keyword::Select), span(0, 6)),
+                (TokenKind::SingleQuotedStringLiteral, span(7, 4)),
+                (TokenKind::Keyword(Keyword::From), span(12, 4)),
+                (TokenKind::Identifier, span(17, 1)),
+                (TokenKind::InlineComment, span(19, 5)),
+                (TokenKind::BlockComment, span(25, 9)),
+                (TokenKind::DelimitedIdentifier, span(35, 6)),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_multi_byte_characters_are_whole_error_tokens() {
+        assert_eq!(
+            tokens("na\u{ef}ve €"),
+            [
+                (TokenKind::Identifier, "na"),
+                (
+                    TokenKind::Error(LexError::InvalidCharacter('\u{ef}')),
+                    "\u{ef}"
+                ),
+                (TokenKind::Identifier, "ve"),
+                (TokenKind::Error(LexError::InvalidCharacter('€')), "€"),
+            ]
+        );
+        assert_eq!(
+            tokens("'天狼星"),
+            [(TokenKind::Error(LexError::UnterminatedString), "'天狼星")]
+        );
+    }
+
+    #[test]
+    fn lossless_tokens_cover_multi_byte_source_exactly() {
+        let source =
+            "SELECT \"名前\", 'Ωmega' || '😀'\n  FROM t /* ∑ /* ∫ */ */ WHERE x <> 'é' -- fin 🚀";
+        let mut end = 0;
+        for token in Lexer::lossless(source) {
+            assert_eq!(token.span.start, end, "gap or overlap before {token:?}");
+            assert!(source.is_char_boundary(token.span.start));
+            end = token.span.start + token.span.length;
+            assert!(source.is_char_boundary(end));
+        }
+        assert_eq!(end, source.len());
+    }
+
+    #[test]
+    fn quotes_are_escaped_by_doubling() {
+        assert_eq!(
+            tokens("'O''Brien' \"a\"\"b\" '' 'x'"),
+            [
+                (TokenKind::SingleQuotedStringLiteral, "'O''Brien'"),
+                (TokenKind::DelimitedIdentifier, "\"a\"\"b\""),
+                (TokenKind::SingleQuotedStringLiteral, "''"),
+                (TokenKind::SingleQuotedStringLiteral, "'x'"),
+            ]
+        );
+        assert_eq!(
+            tokens("\"a\"\""),
+            [(
+                TokenKind::Error(LexError::UnterminatedIdentifier),
+                "\"a\"\""
+            )]
+        );
+    }
+
+    #[test]
+    fn empty_delimited_identifiers_are_errors() {
+        assert_eq!(
+            tokens("a AS \"\" FROM"),
+            [
+                (TokenKind::Identifier, "a"),
+                (TokenKind::Keyword(Keyword::As), "AS"),
+                (TokenKind::Error(LexError::EmptyIdentifier), "\"\""),
+                (TokenKind::Keyword(Keyword::From), "FROM"),
+            ]
+        );
+        // A doubled quote alone is an identifier named `"`
+        assert_eq!(
+            tokens("\"\"\"\""),
+            [(TokenKind::DelimitedIdentifier, "\"\"\"\"")]
+        );
+    }
+
+    /// The kind and span of each token of `source`, skipping whitespace.
+    fn spans(source: &str) -> Vec<(TokenKind, Span)> {
+        Lexer::new(source)
+            .map(|token| (token.kind, token.span))
+            .collect()
+    }
+
+    #[test]
+    fn two_character_operators_are_single_tokens() {
+        assert_eq!(
+            spans("a<=b >= <> != ||c"),
+            [
+                (TokenKind::Identifier, Span::new(0, 1)),
+                (TokenKind::LessThanOrEquals, Span::new(1, 2)),
+                (TokenKind::Identifier, Span::new(3, 1)),
+                (TokenKind::GreaterThanOrEquals, Span::new(5, 2)),
+                (TokenKind::NotEquals, Span::new(8, 2)),
+                (TokenKind::NotEquals, Span::new(11, 2)),
+                (TokenKind::Concatenate, Span::new(14, 2)),
+                (TokenKind::Identifier, Span::new(16, 1)),
+            ]
+        );
+        // The longest operator wins, so `<>=` is `<>` then `=`
+        assert_eq!(
+            spans("<>= |||"),
+            [
+                (TokenKind::NotEquals, Span::new(0, 2)),
+                (TokenKind::Equals, Span::new(2, 1)),
+                (TokenKind::Concatenate, Span::new(4, 2)),
+                (TokenKind::VerticalBar, Span::new(6, 1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn separated_characters_are_not_merged() {
+        assert_eq!(
+            spans("< = | | > ="),
+            [
+                (TokenKind::LessThan, Span::new(0, 1)),
+                (TokenKind::Equals, Span::new(2, 1)),
+                (TokenKind::VerticalBar, Span::new(4, 1)),
+                (TokenKind::VerticalBar, Span::new(6, 1)),
+                (TokenKind::GreaterThan, Span::new(8, 1)),
+                (TokenKind::Equals, Span::new(10, 1)),
+            ]
+        );
+        assert_eq!(
+            spans("a ! b !"),
+            [
+                (TokenKind::Identifier, Span::new(0, 1)),
+                (
+                    TokenKind::Error(LexError::InvalidCharacter('!')),
+                    Span::new(2, 1)
+                ),
+                (TokenKind::Identifier, Span::new(4, 1)),
+                (
+                    TokenKind::Error(LexError::InvalidCharacter('!')),
+                    Span::new(6, 1)
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn block_comments_nest() {
+        assert_eq!(
+            tokens("a /* b /* c */ d */ e"),
+            [
+                (TokenKind::Identifier, "a"),
+                (TokenKind::BlockComment, "/* b /* c */ d */"),
+                (TokenKind::Identifier, "e"),
+            ]
+        );
+        assert_eq!(
+            tokens("/**/ /*/ */ x"),
+            [
+                (TokenKind::BlockComment, "/**/"),
+                (TokenKind::BlockComment, "/*/ */"),
+                (TokenKind::Identifier, "x"),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_block_comment_ends_when_its_depth_returns_to_zero() {
+        assert_eq!(
+            tokens("/* a */ */"),
+            [
+                (TokenKind::BlockComment, "/* a */"),
+                (TokenKind::Asterisk, "*"),
+                (TokenKind::Solidus, "/"),
+            ]
+        );
+    }
+
+    #[test]
+    fn unterminated_block_comments_run_to_the_end() {
+        assert_eq!(
+            spans("x /* a /* b */ y"),
+            [
+                (TokenKind::Identifier, Span::new(0, 1)),
+                (
+                    TokenKind::Error(LexError::UnterminatedComment),
+                    Span::new(2, 14)
+                ),
+            ]
+        );
+        assert_eq!(
+            spans("/*"),
+            [(
+                TokenKind::Error(LexError::UnterminatedComment),
+                Span::new(0, 2)
+            )]
+        );
+        assert_eq!(
+            spans("/* é *"),
+            [(
+                TokenKind::Error(LexError::UnterminatedComment),
+                Span::new(0, 7)
+            )]
+        );
     }
 }
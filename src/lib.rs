@@ -0,0 +1,49 @@
+//! A lexer, parser and formatter for ADQL, the Astronomical Data Query Language.
+//!
+//! Lexing produces [`Token`]s that refer back into the source by [`Span`]:
+//!
+//! ```
+//! use daoql::{Keyword, Lexer, TokenKind};
+//!
+//! let source = "SELECT ra FROM gaia.source";
+//! let mut tokens = Lexer::new(source);
+//! let select = tokens.next().unwrap();
+//! assert_eq!(select.kind, TokenKind::Keyword(Keyword::Select));
+//! assert_eq!(tokens.next().unwrap().text(source), "ra");
+//! ```
+//!
+//! Parsing a script splits it into statements and recovers from errors, so that every error
+//! in the source is reported at once:
+//!
+//! ```
+//! use daoql::{Script, Statement};
+//!
+//! let script = Script::parse("SELECT ra FROM gaia.source; SELECT a b c;");
+//! assert!(matches!(script.statements[0].statement, Statement::Query(_)));
+//! assert_eq!(script.errors.len(), 1);
+//! assert_eq!(script.errors[0].to_string(), "expected `FROM`, found identifier");
+//! ```
+//!
+//! The AST prints back to ADQL through `Display`, and can be walked with the traits in
+//! [`parser::visit`], [`parser::visit_mut`] and [`parser::fold`]. Errors render in the style
+//! of rustc with [`diagnostics`], and [`formatter`] lays out whole files. Statements run
+//! against tables in memory and CSV files with [`engine::Database`].
+
+#![deny(missing_docs)]
+
+mod colour;
+pub mod diagnostics;
+pub mod engine;
+pub mod formatter;
+#[cfg(feature = "serde")]
+pub mod json;
+pub mod lexer;
+pub mod parser;
+#[cfg(feature = "repl")]
+pub mod repl;
+
+pub use lexer::{Decimal, Keyword, LexError, Lexer, Location, SourceMap, Span, Token, TokenKind};
+pub use parser::{
+    parse_query, ParseError, ParseErrorKind, Query, Script, ScriptStatement, Statement,
+    StatementKind,
+};
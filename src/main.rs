@@ -1,51 +1,515 @@
-mod lexer;
+//! The `daoql` command line tool. Run `daoql help` for the commands and exit codes.
+
 use std::{
     env,
-    fs::File,
-    io::{self, BufReader, Read},
+    fs::{self, File},
+    io::{self, BufReader, IsTerminal, Read},
     path::Path,
     process, str,
 };
 
-use lexer::Lexer;
+use daoql::diagnostics::{Diagnostic, Renderer};
+use daoql::engine::{Database, Outcome};
+use daoql::{formatter, Lexer, Script, SourceMap, TokenKind};
+
+/// The command ran and found nothing wrong.
+const SUCCESS: i32 = 0;
+/// The input has errors, is not formatted, or failed to run.
+const FAILURE: i32 = 1;
+/// The command line is wrong or a file can't be read or written.
+const USAGE_ERROR: i32 = 2;
+
+const USAGE: &str = "\
+Usage: daoql <command> [options] <path>...
+
+Commands:
+  lex        Print the tokens of a file
+  parse      Print the syntax tree of a file
+  check      Check files for errors
+  fmt        Format files in place
+  highlight  Print a file with syntax highlighting
+  run        Execute a file against tables in memory and CSV files
+  repl       Run statements interactively
+  help       Print this message
+
+A path of `-` reads standard input. Run `daoql <command> --help` for the options of a command.
+
+Exit codes:
+  0  Success
+  1  The input has errors, is not formatted, or failed to run
+  2  The command line is wrong or a file can't be read or written";
+
+const LEX_USAGE: &str = "\
+Usage: daoql lex [--json] <path>
+
+Prints each token with its line and column. Whitespace and comments are left out, apart from
+with `--json`, which prints every token as JSON and needs the `serde` feature.";
+
+const PARSE_USAGE: &str = "\
+Usage: daoql parse [--json] <path>
+
+Prints the syntax tree of each statement. `--json` prints the statements and errors as JSON
+and needs the `serde` feature.";
+
+const CHECK_USAGE: &str = "\
+Usage: daoql check <path>...
+
+Reports the syntax errors in each file, exiting with 1 if there are any.";
+
+const FMT_USAGE: &str = "\
+Usage: daoql fmt [--check] <path>...
+
+Formats each file in place, or prints standard input formatted when the path is `-`. With
+`--check`, lists the files that are not formatted instead, exiting with 1 if there are any.";
+
+const HIGHLIGHT_USAGE: &str = "\
+Usage: daoql highlight <path>
+
+Prints the file with ANSI colours for each kind of token.";
+
+const RUN_USAGE: &str = "\
+Usage: daoql run [--data <dir>] [--format table|csv] <path>
+
+Executes each statement in order, stopping at the first error. Nothing is run if the file has
+syntax errors.
+
+Options:
+  --data <dir>       Read the table `a.b` from `<dir>/a/b.csv` (default: the current directory)
+  --format <format>  Print query results as aligned tables (default) or as CSV";
+
+const REPL_USAGE: &str = "\
+Usage: daoql repl [--data <dir>]
+
+Runs statements as they are entered, with history kept in `~/.daoql_history`. Enter `.help`
+in the shell for its commands.
+
+Options:
+  --data <dir>  Read the table `a.b` from `<dir>/a/b.csv` (default: the current directory)";
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let args: Vec<String> = env::args().skip(1).collect();
+    let Some(command) = args.first() else {
+        eprintln!("{USAGE}");
+        process::exit(USAGE_ERROR);
+    };
+    let args = &args[1..];
+    let exit_code = match command.as_str() {
+        "lex" => lex(args),
+        "parse" => parse(args),
+        "check" => check(args),
+        "fmt" => format_files(args),
+        "highlight" => highlight(args),
+        "run" => run(args),
+        "repl" => repl(args),
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            SUCCESS
+        }
+        other => {
+            eprintln!("Unknown command `{other}`. Run `daoql help` for the list of commands.");
+            USAGE_ERROR
+        }
+    };
+    process::exit(exit_code);
+}
+
+/// The flags, options and paths given to a command.
+struct Arguments {
+    flags: Vec<&'static str>,
+    options: Vec<(&'static str, String)>,
+    paths: Vec<String>,
+}
 
-    if args.len() < 2 {
-        eprintln!("Give a path to the SQL file.");
-        process::exit(1);
+impl Arguments {
+    /// Splits `args` into the `flags` and `options` a command accepts and paths. Prints
+    /// `usage` for `--help`, and an error for anything else starting with `--`, returning the
+    /// exit code in both cases.
+    fn parse(
+        args: &[String],
+        usage: &str,
+        flags: &[&'static str],
+        options: &[&'static str],
+    ) -> Result<Self, i32> {
+        let mut arguments = Arguments {
+            flags: Vec::new(),
+            options: Vec::new(),
+            paths: Vec::new(),
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if arg == "--help" || arg == "-h" {
+                println!("{usage}");
+                return Err(SUCCESS);
+            }
+            if let Some(flag) = flags.iter().find(|flag| *flag == arg) {
+                arguments.flags.push(flag);
+            } else if let Some(option) = options.iter().find(|option| *option == arg) {
+                let Some(value) = args.next() else {
+                    eprintln!("`{option}` needs a value.\n\n{usage}");
+                    return Err(USAGE_ERROR);
+                };
+                arguments.options.push((option, value.clone()));
+            } else if arg.starts_with('-') && arg != "-" {
+                eprintln!("Unknown option `{arg}`.\n\n{usage}");
+                return Err(USAGE_ERROR);
+            } else {
+                arguments.paths.push(arg.clone());
+            }
+        }
+        Ok(arguments)
     }
 
-    let file_path = &args[1];
-    let text = read_file(file_path).expect("Can't read file!");
+    fn has_flag(&self, flag: &str) -> bool {
+        self.flags.contains(&flag)
+    }
 
-    println!("Lexing {file_path}");
-    let mut lexer = Lexer::new(&text);
-    let mut position = 0;
-    let mut highlighted_text = String::new();
+    fn option(&self, option: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(name, _)| *name == option)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The only path, or the exit code after printing `usage` if there isn't exactly one.
+    fn single_path(&self, usage: &str) -> Result<&str, i32> {
+        match self.paths.as_slice() {
+            [path] => Ok(path),
+            _ => {
+                eprintln!("Give one path.\n\n{usage}");
+                Err(USAGE_ERROR)
+            }
+        }
+    }
 
-    while let Some(token) = lexer.next_token() {
-        // Append text before the token
-        if position < token.span.start {
-            highlighted_text.push_str(&text[position..token.span.start]);
+    /// The paths, or the exit code after printing `usage` if there are none.
+    fn some_paths(&self, usage: &str) -> Result<&[String], i32> {
+        if self.paths.is_empty() {
+            eprintln!("Give at least one path.\n\n{usage}");
+            return Err(USAGE_ERROR);
         }
+        Ok(&self.paths)
+    }
+}
+
+/// The text of a file or of standard input, with the name to use in diagnostics.
+struct Input {
+    name: String,
+    text: String,
+}
+
+impl Input {
+    /// Reads `path`, or standard input for `-`, returning the exit code if it can't be read.
+    fn read(path: &str) -> Result<Self, i32> {
+        let (name, text) = if path == "-" {
+            let mut text = String::new();
+            (
+                "<stdin>",
+                io::stdin().read_to_string(&mut text).map(|_| text),
+            )
+        } else {
+            (path, read_file(path))
+        };
+        match text {
+            Ok(text) => Ok(Input {
+                name: name.to_string(),
+                text,
+            }),
+            Err(error) => {
+                eprintln!("Can't read {name}: {error}");
+                Err(USAGE_ERROR)
+            }
+        }
+    }
+
+    /// Prints `diagnostics` to standard error, returning the exit code.
+    fn report(&self, diagnostics: impl IntoIterator<Item = Diagnostic>) -> i32 {
+        let renderer = Renderer::new(&self.name, &self.text, io::stderr().is_terminal());
+        let mut exit_code = SUCCESS;
+        for diagnostic in diagnostics {
+            eprintln!("{}", renderer.render(&diagnostic));
+            exit_code = FAILURE;
+        }
+        exit_code
+    }
+
+    /// Parses the input, printing the errors in it.
+    fn parse(&self) -> (Script, i32) {
+        let script = Script::parse(&self.text);
+        let exit_code = self.report(script.errors.iter().map(Diagnostic::from_parse_error));
+        (script, exit_code)
+    }
+}
 
-        // Append the highlighted token
+/// `lex [--json] <path>`
+fn lex(args: &[String]) -> i32 {
+    let (input, json) =
+        match Arguments::parse(args, LEX_USAGE, &["--json"], &[]).and_then(|arguments| {
+            let input = Input::read(arguments.single_path(LEX_USAGE)?)?;
+            Ok((input, arguments.has_flag("--json")))
+        }) {
+            Ok(parsed) => parsed,
+            Err(exit_code) => return exit_code,
+        };
+
+    if json {
+        return print_tokens_json(&input);
+    }
+    let source_map = SourceMap::new(&input.text);
+    let mut errors = Vec::new();
+    for token in Lexer::new(&input.text).filter(|token| !token.kind.is_trivia()) {
+        let location = source_map.location(token.span.start);
+        let position = format!("{}:{}", location.line, location.character_column);
+        println!(
+            "{position:<8} {:<24} {:?}",
+            format!("{:?}", token.kind),
+            token.text(&input.text)
+        );
+        if let TokenKind::Error(error) = token.kind {
+            errors.push(Diagnostic::from_lex_error(error, token.span));
+        }
+    }
+    input.report(errors)
+}
+
+/// `parse [--json] <path>`
+fn parse(args: &[String]) -> i32 {
+    let (input, json) =
+        match Arguments::parse(args, PARSE_USAGE, &["--json"], &[]).and_then(|arguments| {
+            let input = Input::read(arguments.single_path(PARSE_USAGE)?)?;
+            Ok((input, arguments.has_flag("--json")))
+        }) {
+            Ok(parsed) => parsed,
+            Err(exit_code) => return exit_code,
+        };
+
+    if json {
+        return print_script_json(&input);
+    }
+    let (script, exit_code) = input.parse();
+    for statement in &script.statements {
+        println!("{:#?}", statement.statement);
+    }
+    exit_code
+}
+
+/// `check <path>...`
+fn check(args: &[String]) -> i32 {
+    let arguments = match Arguments::parse(args, CHECK_USAGE, &[], &[]) {
+        Ok(arguments) => arguments,
+        Err(exit_code) => return exit_code,
+    };
+    let paths = match arguments.some_paths(CHECK_USAGE) {
+        Ok(paths) => paths,
+        Err(exit_code) => return exit_code,
+    };
+
+    let mut exit_code = SUCCESS;
+    for path in paths {
+        let file_exit_code = match Input::read(path) {
+            Ok(input) => input.parse().1,
+            Err(file_exit_code) => file_exit_code,
+        };
+        exit_code = exit_code.max(file_exit_code);
+    }
+    exit_code
+}
+
+/// `fmt [--check] <path>...` rewrites each file in place, or with `--check` lists the files
+/// that are not formatted. Returns the exit code.
+fn format_files(args: &[String]) -> i32 {
+    let arguments = match Arguments::parse(args, FMT_USAGE, &["--check"], &[]) {
+        Ok(arguments) => arguments,
+        Err(exit_code) => return exit_code,
+    };
+    let check = arguments.has_flag("--check");
+    let paths = match arguments.some_paths(FMT_USAGE) {
+        Ok(paths) => paths,
+        Err(exit_code) => return exit_code,
+    };
+
+    let mut exit_code = SUCCESS;
+    for path in paths {
+        let input = match Input::read(path) {
+            Ok(input) => input,
+            Err(file_exit_code) => {
+                exit_code = exit_code.max(file_exit_code);
+                continue;
+            }
+        };
+        let formatted = match formatter::format(&input.text) {
+            Ok(formatted) => formatted,
+            Err(errors) => {
+                let diagnostics = errors
+                    .into_iter()
+                    .map(|(error, span)| Diagnostic::from_lex_error(error, span));
+                exit_code = exit_code.max(input.report(diagnostics));
+                continue;
+            }
+        };
+        if check {
+            if formatted != input.text {
+                println!("{} is not formatted", input.name);
+                exit_code = exit_code.max(FAILURE);
+            }
+        } else if path == "-" {
+            print!("{formatted}");
+        } else if formatted != input.text {
+            if let Err(error) = fs::write(path, formatted) {
+                eprintln!("Can't write {path}: {error}");
+                exit_code = USAGE_ERROR;
+            }
+        }
+    }
+    exit_code
+}
+
+/// `highlight <path>`
+fn highlight(args: &[String]) -> i32 {
+    let input = match Arguments::parse(args, HIGHLIGHT_USAGE, &[], &[])
+        .and_then(|arguments| Input::read(arguments.single_path(HIGHLIGHT_USAGE)?))
+    {
+        Ok(input) => input,
+        Err(exit_code) => return exit_code,
+    };
+
+    let mut highlighted_text = String::new();
+    for token in Lexer::lossless(&input.text) {
         highlighted_text.push_str(token.begin_highlight());
-        highlighted_text.push_str(&text[token.span.range()]);
+        highlighted_text.push_str(token.text(&input.text));
         highlighted_text.push_str(token.end_highlight());
+    }
+    print!("{highlighted_text}");
+    SUCCESS
+}
+
+/// `run [--data <dir>] [--format table|csv] <path>`
+fn run(args: &[String]) -> i32 {
+    let arguments = match Arguments::parse(args, RUN_USAGE, &[], &["--data", "--format"]) {
+        Ok(arguments) => arguments,
+        Err(exit_code) => return exit_code,
+    };
+    let csv = match arguments.option("--format") {
+        None | Some("table") => false,
+        Some("csv") => true,
+        Some(other) => {
+            eprintln!("Unknown format `{other}`.\n\n{RUN_USAGE}");
+            return USAGE_ERROR;
+        }
+    };
+    let input = match arguments.single_path(RUN_USAGE).and_then(Input::read) {
+        Ok(input) => input,
+        Err(exit_code) => return exit_code,
+    };
+
+    let (script, exit_code) = input.parse();
+    if exit_code != SUCCESS {
+        return exit_code;
+    }
+    let mut database = Database::with_data_directory(arguments.option("--data").unwrap_or("."));
+    let mut first = true;
+    for statement in &script.statements {
+        let outcome = match database.execute(&statement.statement) {
+            Ok(outcome) => outcome,
+            Err(error) => return input.report([Diagnostic::from_execution_error(&error)]),
+        };
+        match outcome {
+            Outcome::Rows(result) => {
+                if !first {
+                    println!();
+                }
+                first = false;
+                if csv {
+                    print!("{}", result.to_csv());
+                } else {
+                    println!("{result}");
+                }
+            }
+            // Counts would corrupt CSV output, so they are only printed with tables
+            Outcome::RowsAffected(count) if !csv => {
+                if !first {
+                    println!();
+                }
+                first = false;
+                println!(
+                    "({count} {} affected)",
+                    if count == 1 { "row" } else { "rows" }
+                );
+            }
+            Outcome::RowsAffected(_) | Outcome::Done => {}
+        }
+    }
+    SUCCESS
+}
 
-        // Update the position
-        position = token.span.end();
+/// `repl [--data <dir>]`
+#[cfg(feature = "repl")]
+fn repl(args: &[String]) -> i32 {
+    let arguments = match Arguments::parse(args, REPL_USAGE, &[], &["--data"]) {
+        Ok(arguments) => arguments,
+        Err(exit_code) => return exit_code,
+    };
+    if let Some(path) = arguments.paths.first() {
+        eprintln!("Unexpected argument `{path}`.\n\n{REPL_USAGE}");
+        return USAGE_ERROR;
     }
 
-    // Append any remaining text after the last token
-    if position < text.len() {
-        highlighted_text.push_str(&text[position..]);
+    let database = Database::with_data_directory(arguments.option("--data").unwrap_or("."));
+    let history = env::var_os("HOME").map(|home| Path::new(&home).join(".daoql_history"));
+    match daoql::repl::Repl::new(database).run(history.as_deref()) {
+        Ok(()) => SUCCESS,
+        Err(error) => {
+            eprintln!("Can't read input: {error}");
+            USAGE_ERROR
+        }
     }
+}
 
-    print!("{highlighted_text}");
+#[cfg(not(feature = "repl"))]
+fn repl(args: &[String]) -> i32 {
+    if let Err(exit_code) = Arguments::parse(args, REPL_USAGE, &[], &["--data"]) {
+        return exit_code;
+    }
+    eprintln!("`repl` needs daoql to be built with the `repl` feature.");
+    USAGE_ERROR
+}
+
+/// `lex --json <path>` prints every token as JSON. Returns the exit code, which is 1 if any
+/// of the tokens is an error.
+#[cfg(feature = "serde")]
+fn print_tokens_json(input: &Input) -> i32 {
+    println!("{}", daoql::json::tokens(&input.text));
+    let has_errors =
+        Lexer::lossless(&input.text).any(|token| matches!(token.kind, TokenKind::Error(_)));
+    if has_errors {
+        FAILURE
+    } else {
+        SUCCESS
+    }
+}
+
+/// `parse --json <path>` prints the parsed script as JSON. Returns the exit code, which is 1
+/// if the script has errors.
+#[cfg(feature = "serde")]
+fn print_script_json(input: &Input) -> i32 {
+    let script = Script::parse(&input.text);
+    println!("{}", daoql::json::script(&script));
+    if script.errors.is_empty() {
+        SUCCESS
+    } else {
+        FAILURE
+    }
+}
+
+#[cfg(not(feature = "serde"))]
+fn print_tokens_json(_input: &Input) -> i32 {
+    eprintln!("`--json` needs daoql to be built with the `serde` feature.");
+    USAGE_ERROR
+}
+
+#[cfg(not(feature = "serde"))]
+fn print_script_json(input: &Input) -> i32 {
+    print_tokens_json(input)
 }
 
 fn read_file<P: AsRef<Path>>(path: P) -> io::Result<String> {
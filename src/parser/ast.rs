@@ -0,0 +1,796 @@
+use crate::lexer::Span;
+
+/// A single statement of a script.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Statement {
+    /// A query, `SELECT ...` or `WITH ...`.
+    Query(Box<Query>),
+    /// `CREATE TABLE ...`
+    CreateTable(Box<CreateTable>),
+    /// `DROP TABLE ...`
+    DropTable(DropTable),
+    /// `INSERT INTO ...`
+    Insert(Box<Insert>),
+    /// `UPDATE ...`
+    Update(Box<Update>),
+    /// `DELETE FROM ...`
+    Delete(Box<Delete>),
+    /// `BEGIN`, `COMMIT` or `ROLLBACK`.
+    Transaction {
+        /// Which of the transaction statements this is.
+        kind: TransactionKind,
+        /// The span of the node in the source.
+        span: Span,
+    },
+    /// A statement that failed to parse.
+    Error(Span),
+}
+
+/// The transaction control statements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TransactionKind {
+    /// `BEGIN [TRANSACTION | WORK]`
+    Begin,
+    /// `COMMIT [TRANSACTION | WORK]`
+    Commit,
+    /// `ROLLBACK [TRANSACTION | WORK]`
+    Rollback,
+}
+
+/// `CREATE TABLE name (column, ..., constraint, ...)`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateTable {
+    /// The name of the new table.
+    pub name: ObjectName,
+    /// The column definitions, in order.
+    pub columns: Vec<ColumnDefinition>,
+    /// The table constraints that follow the columns.
+    pub constraints: Vec<TableConstraint>,
+    /// The span of the node in the source.
+    pub span: Span,
+}
+
+/// `name type [constraint ...]`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColumnDefinition {
+    /// The name of the column.
+    pub name: Ident,
+    /// The declared type of the column.
+    pub data_type: DataType,
+    /// The constraints after the type, in order.
+    pub constraints: Vec<ColumnConstraint>,
+    /// The span of the node in the source.
+    pub span: Span,
+}
+
+/// The type of a column in `CREATE TABLE`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DataType {
+    /// `SMALLINT`
+    Smallint,
+    /// `INT` or `INTEGER`
+    Integer,
+    /// `REAL`
+    Real,
+    /// `DOUBLE PRECISION`
+    DoublePrecision,
+    /// `FLOAT[(precision)]`
+    Float(Option<u64>),
+    /// `DECIMAL[(precision[, scale])]`
+    Decimal {
+        /// The total number of digits.
+        precision: Option<u64>,
+        /// The number of digits after the decimal point.
+        scale: Option<u64>,
+    },
+    /// `NUMERIC[(precision[, scale])]`
+    Numeric {
+        /// The total number of digits.
+        precision: Option<u64>,
+        /// The number of digits after the decimal point.
+        scale: Option<u64>,
+    },
+    /// `CHAR[(length)]` or `CHARACTER[(length)]`
+    Char(Option<u64>),
+    /// `VARCHAR[(length)]` or `CHAR[ACTER] VARYING[(length)]`
+    Varchar(Option<u64>),
+    /// `DATE`
+    Date,
+    /// `TIME`
+    Time,
+    /// `TIMESTAMP`
+    Timestamp,
+    /// Any other type name, such as `BOOLEAN` or `BIGINT`.
+    Custom(Ident),
+}
+
+/// `[CONSTRAINT name] kind`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColumnConstraint {
+    /// The name given by `CONSTRAINT name`, if any.
+    pub name: Option<Ident>,
+    /// What the constraint requires of the column.
+    pub kind: ColumnConstraintKind,
+    /// The span of the node in the source.
+    pub span: Span,
+}
+
+/// The kinds of column constraint.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColumnConstraintKind {
+    /// `NOT NULL`
+    NotNull,
+    /// `NULL`
+    Null,
+    /// `PRIMARY KEY`
+    PrimaryKey,
+    /// `UNIQUE`
+    Unique,
+    /// `DEFAULT expr`
+    Default(Expr),
+    /// `CHECK (expr)`
+    Check(Expr),
+    /// `REFERENCES table [(column, ...)]`
+    References(ForeignKeyTarget),
+}
+
+/// `[CONSTRAINT name] kind`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableConstraint {
+    /// The name given by `CONSTRAINT name`, if any.
+    pub name: Option<Ident>,
+    /// What the constraint requires of the table.
+    pub kind: TableConstraintKind,
+    /// The span of the node in the source.
+    pub span: Span,
+}
+
+/// The kinds of table constraint.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TableConstraintKind {
+    /// `PRIMARY KEY (column, ...)`
+    PrimaryKey(Vec<Ident>),
+    /// `UNIQUE (column, ...)`
+    Unique(Vec<Ident>),
+    /// `FOREIGN KEY (column, ...) REFERENCES table [(column, ...)]`
+    ForeignKey {
+        /// The referencing columns of this table.
+        columns: Vec<Ident>,
+        /// The referenced table and columns.
+        target: ForeignKeyTarget,
+    },
+    /// `CHECK (expr)`
+    Check(Expr),
+}
+
+/// The `table [(column, ...)]` referenced by a foreign key. No columns means the primary
+/// key of the table.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ForeignKeyTarget {
+    /// The referenced table.
+    pub table: ObjectName,
+    /// The referenced columns.
+    pub columns: Vec<Ident>,
+    /// The span of the node in the source.
+    pub span: Span,
+}
+
+/// `DROP TABLE name, ... [CASCADE | RESTRICT]`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DropTable {
+    /// The tables to drop.
+    pub names: Vec<ObjectName>,
+    /// Whether dependent objects are dropped as well, if given.
+    pub behaviour: Option<DropBehaviour>,
+    /// The span of the node in the source.
+    pub span: Span,
+}
+
+/// What `DROP TABLE` does with objects that depend on the table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DropBehaviour {
+    /// `CASCADE`, which drops them too.
+    Cascade,
+    /// `RESTRICT`, which refuses to drop the table.
+    Restrict,
+}
+
+/// `INSERT INTO table [(column, ...)] source`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Insert {
+    /// The table to insert into.
+    pub table: ObjectName,
+    /// The columns to fill, or empty for every column in order.
+    pub columns: Vec<Ident>,
+    /// The rows to insert.
+    pub source: InsertSource,
+    /// The span of the node in the source.
+    pub span: Span,
+}
+
+/// Where the rows of an `INSERT` come from.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InsertSource {
+    /// `VALUES (expr, ...), ...`
+    Values(Vec<Vec<Expr>>),
+    /// `SELECT ...`
+    Query(Box<Query>),
+}
+
+/// `UPDATE table SET column = expr, ... [WHERE condition]`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Update {
+    /// The table to update.
+    pub table: ObjectName,
+    /// The `SET` list.
+    pub assignments: Vec<Assignment>,
+    /// The condition that selects the rows to update, if any.
+    pub where_clause: Option<Expr>,
+    /// The span of the node in the source.
+    pub span: Span,
+}
+
+/// `column = expr`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Assignment {
+    /// The column to set.
+    pub column: Ident,
+    /// The new value of the column.
+    pub value: Expr,
+    /// The span of the node in the source.
+    pub span: Span,
+}
+
+/// `DELETE FROM table [WHERE condition]`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Delete {
+    /// The table to delete from.
+    pub table: ObjectName,
+    /// The condition that selects the rows to delete, if any.
+    pub where_clause: Option<Expr>,
+    /// The span of the node in the source.
+    pub span: Span,
+}
+
+/// A complete query: `[WITH ...] body [ORDER BY ...] [OFFSET n]`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Query {
+    /// The common table expressions of the `WITH` clause.
+    pub with: Vec<CommonTableExpression>,
+    /// The `SELECT`s and set operations of the query.
+    pub body: SetExpr,
+    /// The `ORDER BY` list.
+    pub order_by: Vec<OrderByItem>,
+    /// The number of rows skipped by `OFFSET`, if given.
+    pub offset: Option<Limit>,
+    /// The span of the node in the source.
+    pub span: Span,
+}
+
+/// `name [(column, ...)] AS (query)`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommonTableExpression {
+    /// The name the query is bound to.
+    pub name: Ident,
+    /// The column names, or empty to keep those of the query.
+    pub columns: Vec<Ident>,
+    /// The bound query.
+    pub query: Box<Query>,
+    /// The span of the node in the source.
+    pub span: Span,
+}
+
+/// The body of a query, which combines `SELECT`s with set operations.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SetExpr {
+    /// A single `SELECT`.
+    Select(Box<Select>),
+    /// A parenthesised query, which may have its own `WITH`, `ORDER BY` and `OFFSET`.
+    Query(Box<Query>),
+    /// `left op [ALL] right`
+    SetOperation {
+        /// The set operation.
+        op: SetOperator,
+        /// Whether `ALL` keeps duplicate rows.
+        all: bool,
+        /// The left operand.
+        left: Box<SetExpr>,
+        /// The right operand.
+        right: Box<SetExpr>,
+        /// The span of the node in the source.
+        span: Span,
+    },
+}
+
+/// The operators that combine two queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SetOperator {
+    /// `UNION`
+    Union,
+    /// `INTERSECT`
+    Intersect,
+    /// `EXCEPT`
+    Except,
+}
+
+/// The `SELECT ... FROM ... [WHERE] [GROUP BY] [HAVING]` part of a query.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Select {
+    /// Whether `DISTINCT` removes duplicate rows.
+    pub distinct: bool,
+    /// The maximum number of rows from `TOP n`, if given.
+    pub top: Option<Limit>,
+    /// The select list.
+    pub items: Vec<SelectItem>,
+    /// The comma-separated tables of the `FROM` clause.
+    pub from: Vec<TableReference>,
+    /// The `WHERE` condition, if any.
+    pub where_clause: Option<Expr>,
+    /// The `GROUP BY` list.
+    pub group_by: Vec<Expr>,
+    /// The `HAVING` condition, if any.
+    pub having: Option<Expr>,
+    /// The span of the node in the source.
+    pub span: Span,
+}
+
+/// An unsigned integer row count as used by `TOP` and `OFFSET`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Limit {
+    /// The number of rows.
+    pub value: u64,
+    /// The span of the node in the source.
+    pub span: Span,
+}
+
+/// An item of a select list.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SelectItem {
+    /// `*`
+    Wildcard(Span),
+    /// `t.*`
+    QualifiedWildcard(ObjectName, Span),
+    /// `expr [AS alias]`
+    Expression {
+        /// The selected value.
+        expr: Expr,
+        /// The name of the output column, if given.
+        alias: Option<Ident>,
+        /// The span of the node in the source.
+        span: Span,
+    },
+    /// A select list that failed to parse.
+    Error(Span),
+}
+
+/// A table in a `FROM` clause.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TableReference {
+    /// A named table, `name [[AS] alias]`.
+    Table {
+        /// The name of the table.
+        name: ObjectName,
+        /// The name the table is referred to by in the query, if given.
+        alias: Option<Ident>,
+        /// The span of the node in the source.
+        span: Span,
+    },
+    /// A subquery in `FROM`, `(query) [AS] alias`.
+    Derived {
+        /// The subquery.
+        query: Box<Query>,
+        /// The name the subquery is referred to by, which is required.
+        alias: Ident,
+        /// The span of the node in the source.
+        span: Span,
+    },
+    /// Two tables joined together.
+    Join(Box<Join>),
+    /// A table reference that failed to parse.
+    Error(Span),
+}
+
+/// `left [NATURAL] [kind] JOIN right [constraint]`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Join {
+    /// The left table.
+    pub left: TableReference,
+    /// The right table.
+    pub right: TableReference,
+    /// How rows without a match are treated.
+    pub kind: JoinKind,
+    /// How rows of the two tables are matched.
+    pub constraint: JoinConstraint,
+    /// The span of the node in the source.
+    pub span: Span,
+}
+
+/// The kinds of join.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JoinKind {
+    /// `[INNER] JOIN`
+    Inner,
+    /// `LEFT [OUTER] JOIN`
+    Left,
+    /// `RIGHT [OUTER] JOIN`
+    Right,
+    /// `FULL [OUTER] JOIN`
+    Full,
+    /// `CROSS JOIN`
+    Cross,
+}
+
+/// How the rows of a join are matched.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum JoinConstraint {
+    /// `ON condition`
+    On(Expr),
+    /// `USING (column, ...)`
+    Using(Vec<Ident>),
+    /// `NATURAL`, which joins on every column name the two sides share.
+    Natural,
+    /// No constraint, as for `CROSS JOIN`.
+    None,
+}
+
+/// `expr [ASC | DESC]`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrderByItem {
+    /// The sort key.
+    pub expr: Expr,
+    /// The sort direction, if given. The default is ascending.
+    pub direction: Option<SortDirection>,
+    /// The span of the node in the source.
+    pub span: Span,
+}
+
+/// The direction of a sort.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SortDirection {
+    /// `ASC`
+    Ascending,
+    /// `DESC`
+    Descending,
+}
+
+/// A value expression.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Expr {
+    /// A column reference, possibly qualified by its table.
+    Column(ObjectName),
+    /// A constant value.
+    Literal(Literal),
+    /// `op operand`
+    Unary {
+        /// The operator.
+        op: UnaryOperator,
+        /// The operand.
+        operand: Box<Expr>,
+        /// The span of the node in the source.
+        span: Span,
+    },
+    /// `left op right`
+    Binary {
+        /// The left operand.
+        left: Box<Expr>,
+        /// The operator.
+        op: BinaryOperator,
+        /// The right operand.
+        right: Box<Expr>,
+        /// The span of the node in the source.
+        span: Span,
+    },
+    /// `expr [NOT] BETWEEN low AND high`
+    Between {
+        /// The tested value.
+        expr: Box<Expr>,
+        /// Whether the test is `NOT BETWEEN`.
+        negated: bool,
+        /// The lower bound, inclusive.
+        low: Box<Expr>,
+        /// The upper bound, inclusive.
+        high: Box<Expr>,
+        /// The span of the node in the source.
+        span: Span,
+    },
+    /// `expr [NOT] LIKE pattern [ESCAPE escape]`, or `ILIKE` when `case_insensitive` is set.
+    Like {
+        /// The tested value.
+        expr: Box<Expr>,
+        /// Whether the test is `NOT LIKE`.
+        negated: bool,
+        /// Whether the operator is `ILIKE`.
+        case_insensitive: bool,
+        /// The pattern, in which `%` and `_` are wildcards.
+        pattern: Box<Expr>,
+        /// The character that escapes a wildcard, if given.
+        escape: Option<Box<Expr>>,
+        /// The span of the node in the source.
+        span: Span,
+    },
+    /// `expr [NOT] IN (value, ...)`
+    InList {
+        /// The tested value.
+        expr: Box<Expr>,
+        /// Whether the test is `NOT IN`.
+        negated: bool,
+        /// The values to compare against.
+        list: Vec<Expr>,
+        /// The span of the node in the source.
+        span: Span,
+    },
+    /// `expr [NOT] IN (SELECT ...)`
+    InSubquery {
+        /// The tested value.
+        expr: Box<Expr>,
+        /// Whether the test is `NOT IN`.
+        negated: bool,
+        /// The query whose rows are compared against.
+        subquery: Box<Query>,
+        /// The span of the node in the source.
+        span: Span,
+    },
+    /// `expr IS [NOT] NULL`
+    IsNull {
+        /// The tested value.
+        expr: Box<Expr>,
+        /// Whether the test is `IS NOT NULL`.
+        negated: bool,
+        /// The span of the node in the source.
+        span: Span,
+    },
+    /// `EXISTS (SELECT ...)`
+    Exists {
+        /// The query tested for rows.
+        subquery: Box<Query>,
+        /// The span of the node in the source.
+        span: Span,
+    },
+    /// `CASE [operand] WHEN ... THEN ... [ELSE ...] END`
+    Case {
+        /// The value compared with each `WHEN`, if given. Without it each `WHEN` is a condition.
+        operand: Option<Box<Expr>>,
+        /// The `WHEN ... THEN ...` branches, in order.
+        branches: Vec<WhenClause>,
+        /// The `ELSE` result, if given.
+        else_result: Option<Box<Expr>>,
+        /// The span of the node in the source.
+        span: Span,
+    },
+    /// A function call.
+    Function(FunctionCall),
+    /// A scalar subquery `(SELECT ...)`.
+    Subquery {
+        /// The query, which should return a single value.
+        query: Box<Query>,
+        /// The span of the node in the source.
+        span: Span,
+    },
+    /// An expression that failed to parse.
+    Error(Span),
+}
+
+/// The prefix operators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UnaryOperator {
+    /// `NOT`
+    Not,
+    /// `+`
+    Plus,
+    /// `-`
+    Minus,
+    /// `~`
+    BitwiseNot,
+}
+
+/// The infix operators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BinaryOperator {
+    /// `OR`
+    Or,
+    /// `AND`
+    And,
+    /// `=`
+    Equals,
+    /// `<>` or `!=`
+    NotEquals,
+    /// `<`
+    LessThan,
+    /// `<=`
+    LessThanOrEquals,
+    /// `>`
+    GreaterThan,
+    /// `>=`
+    GreaterThanOrEquals,
+    /// `|`
+    BitwiseOr,
+    /// `^`
+    BitwiseXor,
+    /// `&`
+    BitwiseAnd,
+    /// `||`
+    Concatenate,
+    /// `+`
+    Plus,
+    /// `-`
+    Minus,
+    /// `*`
+    Multiply,
+    /// `/`
+    Divide,
+}
+
+/// `WHEN condition THEN result`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WhenClause {
+    /// The condition, or the value compared with the operand of the `CASE`.
+    pub condition: Expr,
+    /// The result of the branch.
+    pub result: Expr,
+    /// The span of the node in the source.
+    pub span: Span,
+}
+
+/// A call such as `ABS(x)`, `COUNT(DISTINCT x)` or `COUNT(*)`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FunctionCall {
+    /// The name of the function.
+    pub name: Ident,
+    /// Whether `DISTINCT` comes before the arguments.
+    pub distinct: bool,
+    /// The arguments.
+    pub args: FunctionArguments,
+    /// The span of the node in the source.
+    pub span: Span,
+}
+
+/// The arguments of a function call.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FunctionArguments {
+    /// `(*)`
+    Wildcard(Span),
+    /// `(expr, ...)`, which may be empty.
+    List(Vec<Expr>),
+}
+
+/// A constant value.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Literal {
+    /// The value.
+    pub value: LiteralValue,
+    /// The span of the node in the source.
+    pub span: Span,
+}
+
+/// The kinds of constant value.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LiteralValue {
+    /// The source text of a numeric literal.
+    Number(String),
+    /// The contents of a string literal, with `''` unescaped.
+    String(String),
+    /// `TRUE` or `FALSE`
+    Boolean(bool),
+    /// `NULL`
+    Null,
+}
+
+/// A regular or delimited (double-quoted) identifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ident {
+    /// The name, without the quotes of a delimited identifier.
+    pub value: String,
+    /// Whether the identifier is delimited, which makes it case-sensitive.
+    pub quoted: bool,
+    /// The span of the node in the source.
+    pub span: Span,
+}
+
+/// A period-separated identifier chain such as `schema.table` or `t.column`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ObjectName {
+    /// The identifiers, from the outermost qualifier to the name itself.
+    pub parts: Vec<Ident>,
+    /// The span of the node in the source.
+    pub span: Span,
+}
+
+impl Statement {
+    /// The span of the whole statement.
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::Query(query) => query.span,
+            Statement::CreateTable(create) => create.span,
+            Statement::DropTable(drop) => drop.span,
+            Statement::Insert(insert) => insert.span,
+            Statement::Update(update) => update.span,
+            Statement::Delete(delete) => delete.span,
+            Statement::Transaction { span, .. } | Statement::Error(span) => *span,
+        }
+    }
+}
+
+impl SetExpr {
+    /// The span of the whole query body.
+    pub fn span(&self) -> Span {
+        match self {
+            SetExpr::Select(select) => select.span,
+            SetExpr::Query(query) => query.span,
+            SetExpr::SetOperation { span, .. } => *span,
+        }
+    }
+}
+
+impl TableReference {
+    /// The span of the whole table reference.
+    pub fn span(&self) -> Span {
+        match self {
+            TableReference::Table { span, .. }
+            | TableReference::Derived { span, .. }
+            | TableReference::Error(span) => *span,
+            TableReference::Join(join) => join.span,
+        }
+    }
+}
+
+impl Expr {
+    /// The span of the whole expression.
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::Column(name) => name.span,
+            Expr::Literal(literal) => literal.span,
+            Expr::Function(function) => function.span,
+            Expr::Unary { span, .. }
+            | Expr::Binary { span, .. }
+            | Expr::Between { span, .. }
+            | Expr::Like { span, .. }
+            | Expr::InList { span, .. }
+            | Expr::InSubquery { span, .. }
+            | Expr::IsNull { span, .. }
+            | Expr::Exists { span, .. }
+            | Expr::Case { span, .. }
+            | Expr::Subquery { span, .. }
+            | Expr::Error(span) => *span,
+        }
+    }
+}
@@ -0,0 +1,529 @@
+use crate::lexer::{Keyword, TokenKind};
+
+use super::{
+    ColumnConstraint, ColumnConstraintKind, ColumnDefinition, CreateTable, DataType, DropBehaviour,
+    DropTable, Expr, ForeignKeyTarget, ParseError, ParseErrorKind, Parser, TableConstraint,
+    TableConstraintKind,
+};
+
+impl Parser<'_> {
+    pub(super) fn parse_create_table(&mut self) -> Result<CreateTable, ParseError> {
+        let start = self.expect_keyword(Keyword::Create, "`CREATE`")?.span;
+        self.expect_keyword(Keyword::Table, "`TABLE`")?;
+        let name = self.parse_object_name()?;
+
+        // Columns and table constraints may be given in any order
+        self.expect(TokenKind::LeftParenthesis, "`(`")?;
+        let mut columns = Vec::new();
+        let mut constraints = Vec::new();
+        loop {
+            if self.is_table_constraint_start() {
+                constraints.push(self.parse_table_constraint()?);
+            } else {
+                columns.push(self.parse_column_definition()?);
+            }
+            if self.consume(TokenKind::Comma).is_none() {
+                break;
+            }
+        }
+        self.expect(TokenKind::RightParenthesis, "`)`")?;
+
+        Ok(CreateTable {
+            name,
+            columns,
+            constraints,
+            span: self.span_from(start),
+        })
+    }
+
+    pub(super) fn parse_drop_table(&mut self) -> Result<DropTable, ParseError> {
+        let start = self.expect_keyword(Keyword::Drop, "`DROP`")?.span;
+        self.expect_keyword(Keyword::Table, "`TABLE`")?;
+        let names = self.parse_comma_separated(Self::parse_object_name)?;
+        let behaviour = if self.consume_keyword(Keyword::Cascade).is_some() {
+            Some(DropBehaviour::Cascade)
+        } else if self.consume_keyword(Keyword::Restrict).is_some() {
+            Some(DropBehaviour::Restrict)
+        } else {
+            None
+        };
+        Ok(DropTable {
+            names,
+            behaviour,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_column_definition(&mut self) -> Result<ColumnDefinition, ParseError> {
+        let name = self.parse_identifier()?;
+        let data_type = self.parse_data_type()?;
+        let mut constraints = Vec::new();
+        while let Some(constraint) = self.parse_column_constraint()? {
+            constraints.push(constraint);
+        }
+        Ok(ColumnDefinition {
+            span: self.span_from(name.span),
+            name,
+            data_type,
+            constraints,
+        })
+    }
+
+    fn parse_data_type(&mut self) -> Result<DataType, ParseError> {
+        if self.is_identifier(0) {
+            return self.parse_identifier().map(DataType::Custom);
+        }
+        let Some(token) = self.next_token() else {
+            return Err(self.unexpected("a data type"));
+        };
+
+        let data_type = match token.kind {
+            TokenKind::Keyword(Keyword::Smallint) => DataType::Smallint,
+            TokenKind::Keyword(Keyword::Int | Keyword::Integer) => DataType::Integer,
+            TokenKind::Keyword(Keyword::Real) => DataType::Real,
+            TokenKind::Keyword(Keyword::Double) => {
+                self.expect_keyword(Keyword::Precision, "`PRECISION`")?;
+                DataType::DoublePrecision
+            }
+            TokenKind::Keyword(Keyword::Float) => DataType::Float(self.parse_type_length()?),
+            TokenKind::Keyword(Keyword::Decimal) => {
+                let (precision, scale) = self.parse_precision_and_scale()?;
+                DataType::Decimal { precision, scale }
+            }
+            TokenKind::Keyword(Keyword::Numeric) => {
+                let (precision, scale) = self.parse_precision_and_scale()?;
+                DataType::Numeric { precision, scale }
+            }
+            TokenKind::Keyword(Keyword::Char | Keyword::Character) => {
+                if self.consume_keyword(Keyword::Varying).is_some() {
+                    DataType::Varchar(self.parse_type_length()?)
+                } else {
+                    DataType::Char(self.parse_type_length()?)
+                }
+            }
+            TokenKind::Keyword(Keyword::VarChar) => DataType::Varchar(self.parse_type_length()?),
+            TokenKind::Keyword(Keyword::Date) => DataType::Date,
+            TokenKind::Keyword(Keyword::Time) => DataType::Time,
+            TokenKind::Keyword(Keyword::Timestamp) => DataType::Timestamp,
+            found => {
+                return Err(ParseError {
+                    kind: ParseErrorKind::UnexpectedToken {
+                        expected: "a data type",
+                        found,
+                    },
+                    span: token.span,
+                })
+            }
+        };
+        Ok(data_type)
+    }
+
+    /// An optional `(length)`.
+    fn parse_type_length(&mut self) -> Result<Option<u64>, ParseError> {
+        if self.consume(TokenKind::LeftParenthesis).is_none() {
+            return Ok(None);
+        }
+        let length = self.parse_limit()?.value;
+        self.expect(TokenKind::RightParenthesis, "`)`")?;
+        Ok(Some(length))
+    }
+
+    /// An optional `(precision[, scale])`.
+    fn parse_precision_and_scale(&mut self) -> Result<(Option<u64>, Option<u64>), ParseError> {
+        if self.consume(TokenKind::LeftParenthesis).is_none() {
+            return Ok((None, None));
+        }
+        let precision = self.parse_limit()?.value;
+        let scale = match self.consume(TokenKind::Comma) {
+            Some(_) => Some(self.parse_limit()?.value),
+            None => None,
+        };
+        self.expect(TokenKind::RightParenthesis, "`)`")?;
+        Ok((Some(precision), scale))
+    }
+
+    /// The next column constraint, or `None` at the end of the column definition.
+    fn parse_column_constraint(&mut self) -> Result<Option<ColumnConstraint>, ParseError> {
+        let start = self.current_span();
+        let name = match self.consume_keyword(Keyword::Constraint) {
+            Some(_) => Some(self.parse_identifier()?),
+            None => None,
+        };
+
+        let kind = if self.consume_keyword(Keyword::Not).is_some() {
+            self.expect_keyword(Keyword::Null, "`NULL`")?;
+            ColumnConstraintKind::NotNull
+        } else if self.consume_keyword(Keyword::Null).is_some() {
+            ColumnConstraintKind::Null
+        } else if self.consume_keyword(Keyword::Primary).is_some() {
+            self.expect_keyword(Keyword::Key, "`KEY`")?;
+            ColumnConstraintKind::PrimaryKey
+        } else if self.consume_keyword(Keyword::Unique).is_some() {
+            ColumnConstraintKind::Unique
+        } else if self.consume_keyword(Keyword::Default).is_some() {
+            ColumnConstraintKind::Default(self.parse_expression()?)
+        } else if self.check_keyword(Keyword::Check) {
+            ColumnConstraintKind::Check(self.parse_check()?)
+        } else if self.check_keyword(Keyword::References) {
+            ColumnConstraintKind::References(self.parse_foreign_key_target()?)
+        } else if name.is_some() {
+            return Err(self.unexpected("a column constraint"));
+        } else {
+            return Ok(None);
+        };
+
+        Ok(Some(ColumnConstraint {
+            name,
+            kind,
+            span: self.span_from(start),
+        }))
+    }
+
+    fn is_table_constraint_start(&self) -> bool {
+        [
+            Keyword::Constraint,
+            Keyword::Primary,
+            Keyword::Unique,
+            Keyword::Foreign,
+            Keyword::Check,
+        ]
+        .into_iter()
+        .any(|keyword| self.check_keyword(keyword))
+    }
+
+    fn parse_table_constraint(&mut self) -> Result<TableConstraint, ParseError> {
+        let start = self.current_span();
+        let name = match self.consume_keyword(Keyword::Constraint) {
+            Some(_) => Some(self.parse_identifier()?),
+            None => None,
+        };
+
+        let kind = if self.consume_keyword(Keyword::Primary).is_some() {
+            self.expect_keyword(Keyword::Key, "`KEY`")?;
+            TableConstraintKind::PrimaryKey(self.parse_parenthesized_identifiers()?)
+        } else if self.consume_keyword(Keyword::Unique).is_some() {
+            TableConstraintKind::Unique(self.parse_parenthesized_identifiers()?)
+        } else if self.consume_keyword(Keyword::Foreign).is_some() {
+            self.expect_keyword(Keyword::Key, "`KEY`")?;
+            let columns = self.parse_parenthesized_identifiers()?;
+            let target = self.parse_foreign_key_target()?;
+            TableConstraintKind::ForeignKey { columns, target }
+        } else if self.check_keyword(Keyword::Check) {
+            TableConstraintKind::Check(self.parse_check()?)
+        } else {
+            return Err(self.unexpected("a table constraint"));
+        };
+
+        Ok(TableConstraint {
+            name,
+            kind,
+            span: self.span_from(start),
+        })
+    }
+
+    /// `CHECK (expr)`
+    fn parse_check(&mut self) -> Result<Expr, ParseError> {
+        self.expect_keyword(Keyword::Check, "`CHECK`")?;
+        self.expect(TokenKind::LeftParenthesis, "`(`")?;
+        let condition = self.parse_expression()?;
+        self.expect(TokenKind::RightParenthesis, "`)`")?;
+        Ok(condition)
+    }
+
+    /// `REFERENCES table [(column, ...)]`
+    fn parse_foreign_key_target(&mut self) -> Result<ForeignKeyTarget, ParseError> {
+        let start = self
+            .expect_keyword(Keyword::References, "`REFERENCES`")?
+            .span;
+        let table = self.parse_object_name()?;
+        let columns = if self.peek_kind() == Some(TokenKind::LeftParenthesis) {
+            self.parse_parenthesized_identifiers()?
+        } else {
+            Vec::new()
+        };
+        Ok(ForeignKeyTarget {
+            table,
+            columns,
+            span: self.span_from(start),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{
+        ColumnConstraintKind, CreateTable, DataType, DropBehaviour, Statement, TableConstraintKind,
+    };
+    use crate::Script;
+
+    /// The statements of `source`, which must parse cleanly.
+    fn statements(source: &str) -> Vec<Statement> {
+        let script = Script::parse(source);
+        assert!(script.errors.is_empty(), "{source}: {:?}", script.errors);
+        script
+            .statements
+            .into_iter()
+            .map(|statement| statement.statement)
+            .collect()
+    }
+
+    fn create_table(source: &str) -> CreateTable {
+        match statements(source).remove(0) {
+            Statement::CreateTable(create) => *create,
+            statement => panic!("{statement:?}"),
+        }
+    }
+
+    /// The name, type and printed constraints of each column.
+    fn columns(create: &CreateTable) -> Vec<(&str, &DataType, Vec<String>)> {
+        create
+            .columns
+            .iter()
+            .map(|column| {
+                let constraints = column.constraints.iter().map(|c| c.to_string()).collect();
+                (column.name.value.as_str(), &column.data_type, constraints)
+            })
+            .collect()
+    }
+
+    /// Each error of `source` with the text it points at.
+    fn errors(source: &str) -> Vec<(String, &str)> {
+        Script::parse(source)
+            .errors
+            .iter()
+            .map(|error| (error.to_string(), &source[error.span.range()]))
+            .collect()
+    }
+
+    #[test]
+    fn example_tables_keep_every_type_and_constraint() {
+        let tables: Vec<CreateTable> = statements(include_str!("../../example.sql"))
+            .into_iter()
+            .filter_map(|statement| match statement {
+                Statement::CreateTable(create) => Some(*create),
+                _ => None,
+            })
+            .collect();
+        let names: Vec<String> = tables.iter().map(|table| table.name.to_string()).collect();
+        assert_eq!(names, ["employees", "departments", "salaries"]);
+
+        let primary_key = || vec!["PRIMARY KEY".to_string()];
+        let varchar = |length| DataType::Varchar(Some(length));
+        assert_eq!(
+            columns(&tables[0]),
+            [
+                ("id", &DataType::Integer, primary_key()),
+                ("first_name", &varchar(50), vec![]),
+                ("last_name", &varchar(50), vec![]),
+                ("birth_date", &DataType::Date, vec![]),
+                ("hire_date", &DataType::Date, vec![]),
+                ("department_id", &DataType::Integer, vec![]),
+            ]
+        );
+        assert!(tables[0].constraints.is_empty());
+        assert_eq!(
+            columns(&tables[1]),
+            [
+                ("id", &DataType::Integer, primary_key()),
+                ("name", &varchar(100), vec![]),
+                ("location", &varchar(100), vec![]),
+            ]
+        );
+
+        let decimal = DataType::Decimal {
+            precision: Some(10),
+            scale: Some(2),
+        };
+        assert_eq!(
+            columns(&tables[2]),
+            [
+                ("employee_id", &DataType::Integer, vec![]),
+                ("amount", &decimal, vec![]),
+                ("pay_date", &DataType::Date, vec![]),
+            ]
+        );
+        let [constraint] = &tables[2].constraints[..] else {
+            panic!("{:?}", tables[2].constraints);
+        };
+        assert_eq!(constraint.name, None);
+        let TableConstraintKind::PrimaryKey(key) = &constraint.kind else {
+            panic!("{constraint:?}");
+        };
+        let key: Vec<&str> = key.iter().map(|column| column.value.as_str()).collect();
+        assert_eq!(key, ["employee_id", "pay_date"]);
+    }
+
+    #[test]
+    fn every_data_type_is_kept() {
+        let create = create_table(
+            "CREATE TABLE t (a SMALLINT, b INT, c REAL, d DOUBLE PRECISION, e FLOAT, f FLOAT(24), \
+             g DECIMAL, h NUMERIC(5), i NUMERIC(5, 0), j CHAR, k CHARACTER(3), \
+             l CHARACTER VARYING(8), m CHAR VARYING, n VARCHAR, o TIME, p TIMESTAMP, q BOOLEAN)",
+        );
+        let types: Vec<&DataType> = create.columns.iter().map(|c| &c.data_type).collect();
+        assert_eq!(
+            types[..16],
+            [
+                &DataType::Smallint,
+                &DataType::Integer,
+                &DataType::Real,
+                &DataType::DoublePrecision,
+                &DataType::Float(None),
+                &DataType::Float(Some(24)),
+                &DataType::Decimal {
+                    precision: None,
+                    scale: None
+                },
+                &DataType::Numeric {
+                    precision: Some(5),
+                    scale: None
+                },
+                &DataType::Numeric {
+                    precision: Some(5),
+                    scale: Some(0)
+                },
+                &DataType::Char(None),
+                &DataType::Char(Some(3)),
+                &DataType::Varchar(Some(8)),
+                &DataType::Varchar(None),
+                &DataType::Varchar(None),
+                &DataType::Time,
+                &DataType::Timestamp,
+            ]
+        );
+        let DataType::Custom(name) = types[16] else {
+            panic!("{:?}", types[16]);
+        };
+        assert_eq!(name.value, "BOOLEAN");
+    }
+
+    #[test]
+    fn constraints_keep_their_names_and_details() {
+        let create = create_table(
+            "CREATE TABLE s.t (\
+             id INTEGER CONSTRAINT pk PRIMARY KEY NOT NULL, \
+             parent INTEGER NULL REFERENCES s.t (id) UNIQUE, \
+             weight REAL DEFAULT -1 CHECK (weight <> 0), \
+             CONSTRAINT pair UNIQUE (id, parent), \
+             FOREIGN KEY (parent) REFERENCES u, \
+             CHECK (id > 0))",
+        );
+        assert_eq!(create.name.to_string(), "s.t");
+        assert_eq!(
+            columns(&create),
+            [
+                (
+                    "id",
+                    &DataType::Integer,
+                    vec![
+                        "CONSTRAINT pk PRIMARY KEY".to_string(),
+                        "NOT NULL".to_string()
+                    ]
+                ),
+                (
+                    "parent",
+                    &DataType::Integer,
+                    vec![
+                        "NULL".to_string(),
+                        "REFERENCES s.t (id)".to_string(),
+                        "UNIQUE".to_string()
+                    ]
+                ),
+                (
+                    "weight",
+                    &DataType::Real,
+                    vec!["DEFAULT -1".to_string(), "CHECK (weight <> 0)".to_string()]
+                ),
+            ]
+        );
+        let name = create.columns[0].constraints[0].name.as_ref().unwrap();
+        assert_eq!(name.value, "pk");
+        let ColumnConstraintKind::References(target) = &create.columns[1].constraints[1].kind
+        else {
+            panic!("{:?}", create.columns[1].constraints);
+        };
+        assert_eq!(target.table.to_string(), "s.t");
+        assert_eq!(target.columns[0].value, "id");
+
+        let constraints: Vec<(Option<&str>, String)> = create
+            .constraints
+            .iter()
+            .map(|constraint| {
+                let name = constraint.name.as_ref().map(|name| name.value.as_str());
+                (name, constraint.to_string())
+            })
+            .collect();
+        assert_eq!(
+            constraints,
+            [
+                (
+                    Some("pair"),
+                    "CONSTRAINT pair UNIQUE (id, parent)".to_string()
+                ),
+                (None, "FOREIGN KEY (parent) REFERENCES u".to_string()),
+                (None, "CHECK (id > 0)".to_string()),
+            ]
+        );
+        let TableConstraintKind::ForeignKey { columns, target } = &create.constraints[1].kind
+        else {
+            panic!("{:?}", create.constraints[1]);
+        };
+        assert_eq!(columns[0].value, "parent");
+        assert!(target.columns.is_empty());
+    }
+
+    #[test]
+    fn drop_table_keeps_every_name_and_behaviour() {
+        let behaviours: Vec<(Vec<String>, Option<DropBehaviour>)> =
+            statements("DROP TABLE a; DROP TABLE s.b, c CASCADE; DROP TABLE d RESTRICT")
+                .into_iter()
+                .map(|statement| match statement {
+                    Statement::DropTable(drop) => {
+                        let names = drop.names.iter().map(|name| name.to_string()).collect();
+                        (names, drop.behaviour)
+                    }
+                    statement => panic!("{statement:?}"),
+                })
+                .collect();
+        assert_eq!(
+            behaviours,
+            [
+                (vec!["a".to_string()], None),
+                (
+                    vec!["s.b".to_string(), "c".to_string()],
+                    Some(DropBehaviour::Cascade)
+                ),
+                (vec!["d".to_string()], Some(DropBehaviour::Restrict)),
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_tables_are_errors() {
+        let error = |message: &str, text: &'static str| (message.to_string(), text);
+        assert_eq!(
+            errors("CREATE TABLE t ()"),
+            [error("expected an identifier, found `)`", ")")]
+        );
+        assert_eq!(
+            errors("CREATE TABLE t (a INT,)"),
+            [error("expected an identifier, found `)`", ")")]
+        );
+        assert_eq!(
+            errors("CREATE TABLE t (a INT CONSTRAINT x)"),
+            [error("expected a column constraint, found `)`", ")")]
+        );
+        assert_eq!(
+            errors("CREATE TABLE t (a INT, CONSTRAINT x)"),
+            [error("expected a table constraint, found `)`", ")")]
+        );
+        assert_eq!(
+            errors("CREATE TABLE t (a)"),
+            [error("expected a data type, found `)`", ")")]
+        );
+        assert_eq!(
+            errors("CREATE TABLE t (a DOUBLE, b DECIMAL(10,))"),
+            [error("expected `PRECISION`, found `,`", ",")]
+        );
+    }
+}
@@ -0,0 +1,1012 @@
+//! Printing of the AST back to ADQL.
+//!
+//! Everything is printed on one line with uppercase keywords. Parentheses are only added
+//! where the binding power of an operator requires them, so parsing the output gives back
+//! the same tree apart from spans. Nodes that failed to parse print as `<error>`.
+
+use std::fmt;
+
+use super::expression::{COMPARISON, NOT, UNARY};
+use super::{
+    Assignment, BinaryOperator, ColumnConstraint, ColumnConstraintKind, ColumnDefinition,
+    CommonTableExpression, CreateTable, DataType, Delete, DropBehaviour, DropTable, Expr,
+    ForeignKeyTarget, FunctionArguments, FunctionCall, Ident, Insert, InsertSource, Join,
+    JoinConstraint, JoinKind, Limit, Literal, LiteralValue, ObjectName, OrderByItem, Query, Select,
+    SelectItem, SetExpr, SetOperator, SortDirection, Statement, TableConstraint,
+    TableConstraintKind, TableReference, TransactionKind, UnaryOperator, Update, WhenClause,
+};
+
+const ERROR: &str = "<error>";
+
+/// Writes `items` separated by `, `.
+struct CommaSeparated<'a, T>(&'a [T]);
+
+impl<T: fmt::Display> fmt::Display for CommaSeparated<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, item) in self.0.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{item}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes an expression, parenthesised if it binds looser than `min_power`.
+struct Operand<'a> {
+    expr: &'a Expr,
+    min_power: u8,
+}
+
+fn operand(expr: &Expr, min_power: u8) -> Operand<'_> {
+    Operand { expr, min_power }
+}
+
+impl fmt::Display for Operand<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if binding_power(self.expr) < self.min_power {
+            write!(f, "({})", self.expr)
+        } else {
+            write!(f, "{}", self.expr)
+        }
+    }
+}
+
+/// How tightly the top-level operator of `expr` binds, with atoms binding tightest.
+fn binding_power(expr: &Expr) -> u8 {
+    match expr {
+        Expr::Binary { op, .. } => op.binding_power(),
+        Expr::Unary {
+            op: UnaryOperator::Not,
+            ..
+        } => NOT,
+        Expr::Unary { .. } => UNARY,
+        Expr::Between { .. }
+        | Expr::Like { .. }
+        | Expr::InList { .. }
+        | Expr::InSubquery { .. }
+        | Expr::IsNull { .. } => COMPARISON,
+        Expr::Column(_)
+        | Expr::Literal(_)
+        | Expr::Exists { .. }
+        | Expr::Case { .. }
+        | Expr::Function(_)
+        | Expr::Subquery { .. }
+        | Expr::Error(_) => u8::MAX,
+    }
+}
+
+fn not(negated: bool) -> &'static str {
+    if negated {
+        "NOT "
+    } else {
+        ""
+    }
+}
+
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Statement::Query(query) => write!(f, "{query}"),
+            Statement::CreateTable(create) => write!(f, "{create}"),
+            Statement::DropTable(drop) => write!(f, "{drop}"),
+            Statement::Insert(insert) => write!(f, "{insert}"),
+            Statement::Update(update) => write!(f, "{update}"),
+            Statement::Delete(delete) => write!(f, "{delete}"),
+            Statement::Transaction { kind, .. } => write!(f, "{kind}"),
+            Statement::Error(_) => write!(f, "{ERROR}"),
+        }
+    }
+}
+
+impl fmt::Display for TransactionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransactionKind::Begin => write!(f, "BEGIN"),
+            TransactionKind::Commit => write!(f, "COMMIT"),
+            TransactionKind::Rollback => write!(f, "ROLLBACK"),
+        }
+    }
+}
+
+impl fmt::Display for CreateTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "CREATE TABLE {} ({}",
+            self.name,
+            CommaSeparated(&self.columns)
+        )?;
+        for constraint in &self.constraints {
+            write!(f, ", {constraint}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl fmt::Display for ColumnDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.name, self.data_type)?;
+        for constraint in &self.constraints {
+            write!(f, " {constraint}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DataType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_length(f: &mut fmt::Formatter<'_>, length: Option<u64>) -> fmt::Result {
+            match length {
+                Some(length) => write!(f, "({length})"),
+                None => Ok(()),
+            }
+        }
+        fn write_precision(
+            f: &mut fmt::Formatter<'_>,
+            precision: Option<u64>,
+            scale: Option<u64>,
+        ) -> fmt::Result {
+            match (precision, scale) {
+                (Some(precision), Some(scale)) => write!(f, "({precision}, {scale})"),
+                (precision, _) => write_length(f, precision),
+            }
+        }
+
+        match self {
+            DataType::Smallint => write!(f, "SMALLINT"),
+            DataType::Integer => write!(f, "INTEGER"),
+            DataType::Real => write!(f, "REAL"),
+            DataType::DoublePrecision => write!(f, "DOUBLE PRECISION"),
+            DataType::Float(precision) => {
+                write!(f, "FLOAT")?;
+                write_length(f, *precision)
+            }
+            DataType::Decimal { precision, scale } => {
+                write!(f, "DECIMAL")?;
+                write_precision(f, *precision, *scale)
+            }
+            DataType::Numeric { precision, scale } => {
+                write!(f, "NUMERIC")?;
+                write_precision(f, *precision, *scale)
+            }
+            DataType::Char(length) => {
+                write!(f, "CHAR")?;
+                write_length(f, *length)
+            }
+            DataType::Varchar(length) => {
+                write!(f, "VARCHAR")?;
+                write_length(f, *length)
+            }
+            DataType::Date => write!(f, "DATE"),
+            DataType::Time => write!(f, "TIME"),
+            DataType::Timestamp => write!(f, "TIMESTAMP"),
+            DataType::Custom(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+impl fmt::Display for ColumnConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = &self.name {
+            write!(f, "CONSTRAINT {name} ")?;
+        }
+        match &self.kind {
+            ColumnConstraintKind::NotNull => write!(f, "NOT NULL"),
+            ColumnConstraintKind::Null => write!(f, "NULL"),
+            ColumnConstraintKind::PrimaryKey => write!(f, "PRIMARY KEY"),
+            ColumnConstraintKind::Unique => write!(f, "UNIQUE"),
+            ColumnConstraintKind::Default(expr) => write!(f, "DEFAULT {expr}"),
+            ColumnConstraintKind::Check(expr) => write!(f, "CHECK ({expr})"),
+            ColumnConstraintKind::References(target) => write!(f, "{target}"),
+        }
+    }
+}
+
+impl fmt::Display for TableConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = &self.name {
+            write!(f, "CONSTRAINT {name} ")?;
+        }
+        match &self.kind {
+            TableConstraintKind::PrimaryKey(columns) => {
+                write!(f, "PRIMARY KEY ({})", CommaSeparated(columns))
+            }
+            TableConstraintKind::Unique(columns) => {
+                write!(f, "UNIQUE ({})", CommaSeparated(columns))
+            }
+            TableConstraintKind::ForeignKey { columns, target } => {
+                write!(f, "FOREIGN KEY ({}) {target}", CommaSeparated(columns))
+            }
+            TableConstraintKind::Check(expr) => write!(f, "CHECK ({expr})"),
+        }
+    }
+}
+
+impl fmt::Display for ForeignKeyTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "REFERENCES {}", self.table)?;
+        if !self.columns.is_empty() {
+            write!(f, " ({})", CommaSeparated(&self.columns))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DropTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DROP TABLE {}", CommaSeparated(&self.names))?;
+        match self.behaviour {
+            Some(DropBehaviour::Cascade) => write!(f, " CASCADE"),
+            Some(DropBehaviour::Restrict) => write!(f, " RESTRICT"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Insert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "INSERT INTO {}", self.table)?;
+        if !self.columns.is_empty() {
+            write!(f, " ({})", CommaSeparated(&self.columns))?;
+        }
+        match &self.source {
+            InsertSource::Values(rows) => {
+                write!(f, " VALUES ")?;
+                for (index, row) in rows.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "({})", CommaSeparated(row))?;
+                }
+                Ok(())
+            }
+            InsertSource::Query(query) => write!(f, " {query}"),
+        }
+    }
+}
+
+impl fmt::Display for Update {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "UPDATE {} SET {}",
+            self.table,
+            CommaSeparated(&self.assignments)
+        )?;
+        if let Some(condition) = &self.where_clause {
+            write!(f, " WHERE {condition}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Assignment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {}", self.column, self.value)
+    }
+}
+
+impl fmt::Display for Delete {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DELETE FROM {}", self.table)?;
+        if let Some(condition) = &self.where_clause {
+            write!(f, " WHERE {condition}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.with.is_empty() {
+            write!(f, "WITH {} ", CommaSeparated(&self.with))?;
+        }
+        write!(f, "{}", self.body)?;
+        if !self.order_by.is_empty() {
+            write!(f, " ORDER BY {}", CommaSeparated(&self.order_by))?;
+        }
+        if let Some(offset) = &self.offset {
+            write!(f, " OFFSET {offset}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for CommonTableExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.columns.is_empty() {
+            write!(f, " ({})", CommaSeparated(&self.columns))?;
+        }
+        write!(f, " AS ({})", self.query)
+    }
+}
+
+impl SetOperator {
+    fn binding_power(self) -> u8 {
+        match self {
+            SetOperator::Union | SetOperator::Except => 1,
+            SetOperator::Intersect => 3,
+        }
+    }
+}
+
+impl fmt::Display for SetExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        /// Writes a set operation operand, parenthesised if it binds looser than `min_power`.
+        fn write_operand(f: &mut fmt::Formatter<'_>, expr: &SetExpr, min_power: u8) -> fmt::Result {
+            match expr {
+                SetExpr::SetOperation { op, .. } if op.binding_power() < min_power => {
+                    write!(f, "({expr})")
+                }
+                _ => write!(f, "{expr}"),
+            }
+        }
+
+        match self {
+            SetExpr::Select(select) => write!(f, "{select}"),
+            SetExpr::Query(query) => write!(f, "({query})"),
+            SetExpr::SetOperation {
+                op,
+                all,
+                left,
+                right,
+                ..
+            } => {
+                let power = op.binding_power();
+                write_operand(f, left, power)?;
+                write!(f, " {op}{} ", if *all { " ALL" } else { "" })?;
+                write_operand(f, right, power + 1)
+            }
+        }
+    }
+}
+
+impl fmt::Display for SetOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SetOperator::Union => write!(f, "UNION"),
+            SetOperator::Intersect => write!(f, "INTERSECT"),
+            SetOperator::Except => write!(f, "EXCEPT"),
+        }
+    }
+}
+
+impl fmt::Display for Select {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SELECT ")?;
+        if self.distinct {
+            write!(f, "DISTINCT ")?;
+        }
+        if let Some(top) = &self.top {
+            write!(f, "TOP {top} ")?;
+        }
+        write!(f, "{}", CommaSeparated(&self.items))?;
+        if !self.from.is_empty() {
+            write!(f, " FROM {}", CommaSeparated(&self.from))?;
+        }
+        if let Some(condition) = &self.where_clause {
+            write!(f, " WHERE {condition}")?;
+        }
+        if !self.group_by.is_empty() {
+            write!(f, " GROUP BY {}", CommaSeparated(&self.group_by))?;
+        }
+        if let Some(condition) = &self.having {
+            write!(f, " HAVING {condition}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl fmt::Display for SelectItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectItem::Wildcard(_) => write!(f, "*"),
+            SelectItem::QualifiedWildcard(name, _) => write!(f, "{name}.*"),
+            SelectItem::Expression { expr, alias, .. } => {
+                write!(f, "{expr}")?;
+                if let Some(alias) = alias {
+                    write!(f, " AS {alias}")?;
+                }
+                Ok(())
+            }
+            SelectItem::Error(_) => write!(f, "{ERROR}"),
+        }
+    }
+}
+
+impl fmt::Display for TableReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TableReference::Table { name, alias, .. } => {
+                write!(f, "{name}")?;
+                if let Some(alias) = alias {
+                    write!(f, " AS {alias}")?;
+                }
+                Ok(())
+            }
+            TableReference::Derived { query, alias, .. } => write!(f, "({query}) AS {alias}"),
+            TableReference::Join(join) => write!(f, "{join}"),
+            TableReference::Error(_) => write!(f, "{ERROR}"),
+        }
+    }
+}
+
+impl fmt::Display for Join {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ", self.left)?;
+        if self.constraint == JoinConstraint::Natural {
+            write!(f, "NATURAL ")?;
+        }
+        write!(f, "{} ", self.kind)?;
+        // Joins associate to the left, so a join on the right needs parentheses
+        match &self.right {
+            TableReference::Join(right) => write!(f, "({right})")?,
+            right => write!(f, "{right}")?,
+        }
+        match &self.constraint {
+            JoinConstraint::On(condition) => write!(f, " ON {condition}"),
+            JoinConstraint::Using(columns) => write!(f, " USING ({})", CommaSeparated(columns)),
+            JoinConstraint::Natural | JoinConstraint::None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for JoinKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JoinKind::Inner => write!(f, "JOIN"),
+            JoinKind::Left => write!(f, "LEFT JOIN"),
+            JoinKind::Right => write!(f, "RIGHT JOIN"),
+            JoinKind::Full => write!(f, "FULL JOIN"),
+            JoinKind::Cross => write!(f, "CROSS JOIN"),
+        }
+    }
+}
+
+impl fmt::Display for OrderByItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.expr)?;
+        match self.direction {
+            Some(SortDirection::Ascending) => write!(f, " ASC"),
+            Some(SortDirection::Descending) => write!(f, " DESC"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Comparisons and predicates do not associate, so a comparison as their left
+        // operand needs parentheses too
+        let predicate_operand = |expr| operand(expr, COMPARISON + 1);
+
+        match self {
+            Expr::Column(name) => write!(f, "{name}"),
+            Expr::Literal(literal) => write!(f, "{literal}"),
+            Expr::Unary {
+                op, operand: expr, ..
+            } => {
+                let text = operand(expr, binding_power(self)).to_string();
+                match op {
+                    UnaryOperator::Not => write!(f, "NOT {text}"),
+                    // `- -a` must not become the comment `--a`
+                    UnaryOperator::Plus | UnaryOperator::Minus if text.starts_with(['+', '-']) => {
+                        write!(f, "{op} {text}")
+                    }
+                    _ => write!(f, "{op}{text}"),
+                }
+            }
+            Expr::Binary {
+                left, op, right, ..
+            } => {
+                let power = op.binding_power();
+                let left_power = if power == COMPARISON {
+                    power + 1
+                } else {
+                    power
+                };
+                write!(
+                    f,
+                    "{} {op} {}",
+                    operand(left, left_power),
+                    operand(right, power + 1)
+                )
+            }
+            Expr::Between {
+                expr,
+                negated,
+                low,
+                high,
+                ..
+            } => write!(
+                f,
+                "{} {}BETWEEN {} AND {}",
+                predicate_operand(expr),
+                not(*negated),
+                predicate_operand(low),
+                predicate_operand(high)
+            ),
+            Expr::Like {
+                expr,
+                negated,
+                case_insensitive,
+                pattern,
+                escape,
+                ..
+            } => {
+                write!(
+                    f,
+                    "{} {}{} {}",
+                    predicate_operand(expr),
+                    not(*negated),
+                    if *case_insensitive { "ILIKE" } else { "LIKE" },
+                    predicate_operand(pattern)
+                )?;
+                if let Some(escape) = escape {
+                    write!(f, " ESCAPE {}", predicate_operand(escape))?;
+                }
+                Ok(())
+            }
+            Expr::InList {
+                expr,
+                negated,
+                list,
+                ..
+            } => write!(
+                f,
+                "{} {}IN ({})",
+                predicate_operand(expr),
+                not(*negated),
+                CommaSeparated(list)
+            ),
+            Expr::InSubquery {
+                expr,
+                negated,
+                subquery,
+                ..
+            } => write!(
+                f,
+                "{} {}IN ({subquery})",
+                predicate_operand(expr),
+                not(*negated)
+            ),
+            Expr::IsNull { expr, negated, .. } => {
+                write!(f, "{} IS {}NULL", predicate_operand(expr), not(*negated))
+            }
+            Expr::Exists { subquery, .. } => write!(f, "EXISTS ({subquery})"),
+            Expr::Case {
+                operand,
+                branches,
+                else_result,
+                ..
+            } => {
+                write!(f, "CASE")?;
+                if let Some(operand) = operand {
+                    write!(f, " {operand}")?;
+                }
+                for branch in branches {
+                    write!(f, " {branch}")?;
+                }
+                if let Some(else_result) = else_result {
+                    write!(f, " ELSE {else_result}")?;
+                }
+                write!(f, " END")
+            }
+            Expr::Function(call) => write!(f, "{call}"),
+            Expr::Subquery { query, .. } => write!(f, "({query})"),
+            Expr::Error(_) => write!(f, "{ERROR}"),
+        }
+    }
+}
+
+impl fmt::Display for UnaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnaryOperator::Not => write!(f, "NOT"),
+            UnaryOperator::Plus => write!(f, "+"),
+            UnaryOperator::Minus => write!(f, "-"),
+            UnaryOperator::BitwiseNot => write!(f, "~"),
+        }
+    }
+}
+
+impl fmt::Display for BinaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let symbol = match self {
+            BinaryOperator::Or => "OR",
+            BinaryOperator::And => "AND",
+            BinaryOperator::Equals => "=",
+            BinaryOperator::NotEquals => "<>",
+            BinaryOperator::LessThan => "<",
+            BinaryOperator::LessThanOrEquals => "<=",
+            BinaryOperator::GreaterThan => ">",
+            BinaryOperator::GreaterThanOrEquals => ">=",
+            BinaryOperator::BitwiseOr => "|",
+            BinaryOperator::BitwiseXor => "^",
+            BinaryOperator::BitwiseAnd => "&",
+            BinaryOperator::Concatenate => "||",
+            BinaryOperator::Plus => "+",
+            BinaryOperator::Minus => "-",
+            BinaryOperator::Multiply => "*",
+            BinaryOperator::Divide => "/",
+        };
+        write!(f, "{symbol}")
+    }
+}
+
+impl fmt::Display for WhenClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "WHEN {} THEN {}", self.condition, self.result)
+    }
+}
+
+impl fmt::Display for FunctionCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}(", self.name.value)?;
+        if self.distinct {
+            write!(f, "DISTINCT ")?;
+        }
+        match &self.args {
+            FunctionArguments::Wildcard(_) => write!(f, "*")?,
+            FunctionArguments::List(args) => write!(f, "{}", CommaSeparated(args))?,
+        }
+        write!(f, ")")
+    }
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.value {
+            LiteralValue::Number(text) => write!(f, "{text}"),
+            LiteralValue::String(value) => write!(f, "'{}'", value.replace('\'', "''")),
+            LiteralValue::Boolean(true) => write!(f, "TRUE"),
+            LiteralValue::Boolean(false) => write!(f, "FALSE"),
+            LiteralValue::Null => write!(f, "NULL"),
+        }
+    }
+}
+
+impl fmt::Display for Ident {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.quoted {
+            write!(f, "\"{}\"", self.value.replace('"', "\"\""))
+        } else {
+            write!(f, "{}", self.value)
+        }
+    }
+}
+
+impl fmt::Display for ObjectName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, part) in self.parts.iter().enumerate() {
+            if index > 0 {
+                write!(f, ".")?;
+            }
+            write!(f, "{part}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lexer::Span;
+    use crate::parser::fold::{self, Fold};
+    use crate::parser::{
+        Assignment, ColumnConstraint, ColumnDefinition, CommonTableExpression, CreateTable, Delete,
+        DropTable, Expr, ForeignKeyTarget, FunctionCall, Ident, Insert, Join, Limit, Literal,
+        ObjectName, OrderByItem, Query, Select, SelectItem, SetExpr, Statement, TableConstraint,
+        TableReference, Update, WhenClause,
+    };
+    use crate::Script;
+
+    /// Resets every span to the default, so trees parsed from different text compare equal
+    /// when they only differ in layout.
+    struct StripSpans;
+
+    impl Fold for StripSpans {
+        fn fold_statement(&mut self, statement: Statement) -> Statement {
+            match fold::walk_statement(self, statement) {
+                Statement::Transaction { kind, .. } => Statement::Transaction {
+                    kind,
+                    span: Span::default(),
+                },
+                Statement::Error(_) => Statement::Error(Span::default()),
+                statement => statement,
+            }
+        }
+
+        fn fold_create_table(&mut self, create: CreateTable) -> CreateTable {
+            let span = Span::default();
+            CreateTable {
+                span,
+                ..fold::walk_create_table(self, create)
+            }
+        }
+
+        fn fold_column_definition(&mut self, column: ColumnDefinition) -> ColumnDefinition {
+            let span = Span::default();
+            ColumnDefinition {
+                span,
+                ..fold::walk_column_definition(self, column)
+            }
+        }
+
+        fn fold_column_constraint(&mut self, constraint: ColumnConstraint) -> ColumnConstraint {
+            let span = Span::default();
+            ColumnConstraint {
+                span,
+                ..fold::walk_column_constraint(self, constraint)
+            }
+        }
+
+        fn fold_table_constraint(&mut self, constraint: TableConstraint) -> TableConstraint {
+            let span = Span::default();
+            TableConstraint {
+                span,
+                ..fold::walk_table_constraint(self, constraint)
+            }
+        }
+
+        fn fold_foreign_key_target(&mut self, target: ForeignKeyTarget) -> ForeignKeyTarget {
+            let span = Span::default();
+            ForeignKeyTarget {
+                span,
+                ..fold::walk_foreign_key_target(self, target)
+            }
+        }
+
+        fn fold_drop_table(&mut self, drop: DropTable) -> DropTable {
+            let span = Span::default();
+            DropTable {
+                span,
+                ..fold::walk_drop_table(self, drop)
+            }
+        }
+
+        fn fold_insert(&mut self, insert: Insert) -> Insert {
+            let span = Span::default();
+            Insert {
+                span,
+                ..fold::walk_insert(self, insert)
+            }
+        }
+
+        fn fold_update(&mut self, update: Update) -> Update {
+            let span = Span::default();
+            Update {
+                span,
+                ..fold::walk_update(self, update)
+            }
+        }
+
+        fn fold_assignment(&mut self, assignment: Assignment) -> Assignment {
+            let span = Span::default();
+            Assignment {
+                span,
+                ..fold::walk_assignment(self, assignment)
+            }
+        }
+
+        fn fold_delete(&mut self, delete: Delete) -> Delete {
+            let span = Span::default();
+            Delete {
+                span,
+                ..fold::walk_delete(self, delete)
+            }
+        }
+
+        fn fold_query(&mut self, query: Query) -> Query {
+            let span = Span::default();
+            Query {
+                span,
+                ..fold::walk_query(self, query)
+            }
+        }
+
+        fn fold_common_table_expression(
+            &mut self,
+            cte: CommonTableExpression,
+        ) -> CommonTableExpression {
+            let span = Span::default();
+            CommonTableExpression {
+                span,
+                ..fold::walk_common_table_expression(self, cte)
+            }
+        }
+
+        fn fold_set_expr(&mut self, set_expr: SetExpr) -> SetExpr {
+            let mut set_expr = fold::walk_set_expr(self, set_expr);
+            if let SetExpr::SetOperation { span, .. } = &mut set_expr {
+                *span = Span::default();
+            }
+            set_expr
+        }
+
+        fn fold_select(&mut self, select: Select) -> Select {
+            let span = Span::default();
+            Select {
+                span,
+                ..fold::walk_select(self, select)
+            }
+        }
+
+        fn fold_limit(&mut self, limit: Limit) -> Limit {
+            let span = Span::default();
+            Limit { span, ..limit }
+        }
+
+        fn fold_select_item(&mut self, item: SelectItem) -> SelectItem {
+            let mut item = fold::walk_select_item(self, item);
+            match &mut item {
+                SelectItem::Wildcard(span)
+                | SelectItem::QualifiedWildcard(_, span)
+                | SelectItem::Expression { span, .. }
+                | SelectItem::Error(span) => *span = Span::default(),
+            }
+            item
+        }
+
+        fn fold_table_reference(&mut self, table: TableReference) -> TableReference {
+            let mut table = fold::walk_table_reference(self, table);
+            match &mut table {
+                TableReference::Table { span, .. }
+                | TableReference::Derived { span, .. }
+                | TableReference::Error(span) => *span = Span::default(),
+                TableReference::Join(_) => {}
+            }
+            table
+        }
+
+        fn fold_join(&mut self, join: Join) -> Join {
+            let span = Span::default();
+            Join {
+                span,
+                ..fold::walk_join(self, join)
+            }
+        }
+
+        fn fold_order_by_item(&mut self, item: OrderByItem) -> OrderByItem {
+            let span = Span::default();
+            OrderByItem {
+                span,
+                ..fold::walk_order_by_item(self, item)
+            }
+        }
+
+        fn fold_expr(&mut self, expr: Expr) -> Expr {
+            let mut expr = fold::walk_expr(self, expr);
+            match &mut expr {
+                Expr::Unary { span, .. }
+                | Expr::Binary { span, .. }
+                | Expr::Between { span, .. }
+                | Expr::Like { span, .. }
+                | Expr::InList { span, .. }
+                | Expr::InSubquery { span, .. }
+                | Expr::IsNull { span, .. }
+                | Expr::Exists { span, .. }
+                | Expr::Case { span, .. }
+                | Expr::Subquery { span, .. }
+                | Expr::Error(span) => *span = Span::default(),
+                Expr::Column(_) | Expr::Literal(_) | Expr::Function(_) => {}
+            }
+            expr
+        }
+
+        fn fold_when_clause(&mut self, when: WhenClause) -> WhenClause {
+            let span = Span::default();
+            WhenClause {
+                span,
+                ..fold::walk_when_clause(self, when)
+            }
+        }
+
+        fn fold_function_call(&mut self, call: FunctionCall) -> FunctionCall {
+            let span = Span::default();
+            FunctionCall {
+                span,
+                ..fold::walk_function_call(self, call)
+            }
+        }
+
+        fn fold_literal(&mut self, literal: Literal) -> Literal {
+            let span = Span::default();
+            Literal { span, ..literal }
+        }
+
+        fn fold_object_name(&mut self, name: ObjectName) -> ObjectName {
+            let span = Span::default();
+            ObjectName {
+                span,
+                ..fold::walk_object_name(self, name)
+            }
+        }
+
+        fn fold_ident(&mut self, ident: Ident) -> Ident {
+            let span = Span::default();
+            Ident { span, ..ident }
+        }
+    }
+
+    /// The statements of `source` without spans, asserting that it parses cleanly.
+    fn parse(source: &str) -> Vec<Statement> {
+        let script = Script::parse(source);
+        assert!(script.errors.is_empty(), "{source}: {:?}", script.errors);
+        script
+            .statements
+            .into_iter()
+            .map(|statement| StripSpans.fold_statement(statement.statement))
+            .collect()
+    }
+
+    /// Asserts that printing the statements of `source` and parsing them again gives back
+    /// the same trees.
+    fn assert_round_trips(source: &str) {
+        let statements = parse(source);
+        let printed: Vec<String> = statements.iter().map(Statement::to_string).collect();
+        assert_eq!(parse(&printed.join(";\n")), statements, "{source}");
+    }
+
+    #[test]
+    fn example_scripts_round_trip() {
+        assert_round_trips(include_str!("../../example.sql"));
+        assert_round_trips(include_str!("../../simple.sql"));
+    }
+
+    #[test]
+    fn corpus_round_trips() {
+        let corpus = [
+            "SELECT DISTINCT TOP 10 s.*, ra AS \"Right \"\"Ascension\"\"\" FROM gaia.source AS s",
+            "WITH near (id) AS (SELECT source_id FROM gaia.source WHERE dec > -30) \
+             SELECT id FROM near ORDER BY id DESC OFFSET 5",
+            "(SELECT a FROM t UNION ALL SELECT a FROM u) EXCEPT (SELECT a FROM v ORDER BY a)",
+            "SELECT a FROM t INTERSECT SELECT a FROM u UNION SELECT a FROM v",
+            "SELECT * FROM ((SELECT a FROM t) AS x JOIN u ON x.a = u.a) NATURAL LEFT JOIN w",
+            "SELECT * FROM a CROSS JOIN (b FULL OUTER JOIN c USING (x, y)), ((SELECT 1 AS x FROM d)) e",
+            "SELECT CASE WHEN a < 0 THEN 'negative' WHEN a = 0 THEN 'zero' ELSE 'positive' END, \
+             CASE b WHEN 1 THEN 'one' END FROM t",
+            "SELECT (a + b) * c, a - (b - c), -(a - b), a / (b * c), 'x' || (a || 'y') FROM t",
+            "SELECT a FROM t WHERE (a OR b) AND NOT (c AND d) OR NOT NOT e",
+            "SELECT a FROM t WHERE (a = b) = c AND (a < b) IS NULL",
+            "SELECT a FROM t WHERE a NOT BETWEEN -1 AND 1 + 2 AND b LIKE 'a%' AND c NOT LIKE '_'",
+            "SELECT a FROM t WHERE a IN (1, 2, 3) AND b NOT IN (SELECT b FROM u) AND c IS NOT NULL",
+            "SELECT a FROM t WHERE EXISTS (SELECT 1 FROM u WHERE u.a = t.a) AND a = (SELECT MAX(a) FROM u)",
+            "SELECT COUNT(*), COUNT(DISTINCT a), AVG(b) FROM t GROUP BY c HAVING COUNT(*) > 1",
+            "SELECT DISTANCE(POINT('ICRS', ra, dec), POINT('ICRS', 266.4, -29.0)) FROM gaia.source \
+             WHERE 1 = CONTAINS(POINT('ICRS', ra, dec), CIRCLE('ICRS', 266.4, -29.0, 0.5))",
+            "SELECT 1.5e-3, .5, 5., 0x1F, 'it''s', -(-1), +a FROM t",
+            "SELECT a & b | c ^ d, ~a FROM t",
+            "CREATE TABLE s.t (id INTEGER PRIMARY KEY, name VARCHAR(20) NOT NULL UNIQUE DEFAULT 'x', \
+             parent INTEGER REFERENCES s.t (id), FOREIGN KEY (parent) REFERENCES s.u (id))",
+            "DROP TABLE t CASCADE",
+            "INSERT INTO t (a, b) SELECT a, b FROM u WHERE a > 1",
+            "UPDATE t SET a = a + 1, b = NULL WHERE c IN (SELECT c FROM u)",
+            "DELETE FROM t WHERE NOT EXISTS (SELECT 1 FROM u)",
+            "BEGIN; ROLLBACK; COMMIT",
+        ];
+        for source in corpus {
+            assert_round_trips(source);
+        }
+    }
+
+    #[test]
+    fn parentheses_only_change_spans() {
+        assert_eq!(
+            parse("SELECT ((a)) + (b * c) FROM ((t))"),
+            parse("SELECT a + b * c FROM t")
+        );
+        assert_ne!(
+            parse("SELECT (a + b) * c FROM t"),
+            parse("SELECT a + b * c FROM t")
+        );
+    }
+}
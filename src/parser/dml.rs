@@ -0,0 +1,261 @@
+use crate::lexer::{Keyword, TokenKind};
+
+use super::{Assignment, Delete, Expr, Insert, InsertSource, ParseError, Parser, Update};
+
+impl Parser<'_> {
+    pub(super) fn parse_insert(&mut self) -> Result<Insert, ParseError> {
+        let start = self.expect_keyword(Keyword::Insert, "`INSERT`")?.span;
+        self.expect_keyword(Keyword::Into, "`INTO`")?;
+        let table = self.parse_object_name()?;
+        // A `(` followed by anything but a name starts a parenthesised query
+        let columns =
+            if self.peek_kind() == Some(TokenKind::LeftParenthesis) && self.is_identifier(1) {
+                self.parse_parenthesized_identifiers()?
+            } else {
+                Vec::new()
+            };
+
+        let source = if self.consume_keyword(Keyword::Values).is_some() {
+            InsertSource::Values(self.parse_comma_separated(Self::parse_values_row)?)
+        } else if self.is_query_start() || self.peek_kind() == Some(TokenKind::LeftParenthesis) {
+            InsertSource::Query(Box::new(self.parse_query()?))
+        } else {
+            return Err(self.unexpected("`VALUES` or a query"));
+        };
+
+        Ok(Insert {
+            table,
+            columns,
+            source,
+            span: self.span_from(start),
+        })
+    }
+
+    /// `(expr, ...)`
+    fn parse_values_row(&mut self) -> Result<Vec<Expr>, ParseError> {
+        self.expect(TokenKind::LeftParenthesis, "`(`")?;
+        let row = self.parse_comma_separated(Self::parse_expression)?;
+        self.expect(TokenKind::RightParenthesis, "`)`")?;
+        Ok(row)
+    }
+
+    pub(super) fn parse_update(&mut self) -> Result<Update, ParseError> {
+        let start = self.expect_keyword(Keyword::Update, "`UPDATE`")?.span;
+        let table = self.parse_object_name()?;
+        self.expect_keyword(Keyword::Set, "`SET`")?;
+        let assignments = self.parse_comma_separated(Self::parse_assignment)?;
+        let where_clause = self
+            .consume_keyword(Keyword::Where)
+            .map(|_| self.parse_or_recover(Self::parse_expression, Expr::Error));
+        Ok(Update {
+            table,
+            assignments,
+            where_clause,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_assignment(&mut self) -> Result<Assignment, ParseError> {
+        let column = self.parse_identifier()?;
+        self.expect(TokenKind::Equals, "`=`")?;
+        let value = self.parse_expression()?;
+        Ok(Assignment {
+            span: self.span_from(column.span),
+            column,
+            value,
+        })
+    }
+
+    pub(super) fn parse_delete(&mut self) -> Result<Delete, ParseError> {
+        let start = self.expect_keyword(Keyword::Delete, "`DELETE`")?.span;
+        self.expect_keyword(Keyword::From, "`FROM`")?;
+        let table = self.parse_object_name()?;
+        let where_clause = self
+            .consume_keyword(Keyword::Where)
+            .map(|_| self.parse_or_recover(Self::parse_expression, Expr::Error));
+        Ok(Delete {
+            table,
+            where_clause,
+            span: self.span_from(start),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{Delete, Insert, InsertSource, SetExpr, Statement, Update};
+    use crate::Script;
+
+    /// The only statement of `source`, which must parse cleanly.
+    fn statement(source: &str) -> Statement {
+        let mut script = Script::parse(source);
+        assert!(script.errors.is_empty(), "{source}: {:?}", script.errors);
+        assert_eq!(script.statements.len(), 1, "{source}");
+        script.statements.remove(0).statement
+    }
+
+    fn insert(source: &str) -> Insert {
+        match statement(source) {
+            Statement::Insert(insert) => *insert,
+            statement => panic!("{statement:?}"),
+        }
+    }
+
+    fn update(source: &str) -> Update {
+        match statement(source) {
+            Statement::Update(update) => *update,
+            statement => panic!("{statement:?}"),
+        }
+    }
+
+    fn delete(source: &str) -> Delete {
+        match statement(source) {
+            Statement::Delete(delete) => *delete,
+            statement => panic!("{statement:?}"),
+        }
+    }
+
+    fn columns(insert: &Insert) -> Vec<&str> {
+        insert
+            .columns
+            .iter()
+            .map(|column| column.value.as_str())
+            .collect()
+    }
+
+    /// Each row of a `VALUES` source, printed.
+    fn rows(insert: &Insert) -> Vec<Vec<String>> {
+        let InsertSource::Values(rows) = &insert.source else {
+            panic!("{:?}", insert.source);
+        };
+        rows.iter()
+            .map(|row| row.iter().map(|value| value.to_string()).collect())
+            .collect()
+    }
+
+    /// The printed query of a query source.
+    fn query(insert: &Insert) -> String {
+        let InsertSource::Query(query) = &insert.source else {
+            panic!("{:?}", insert.source);
+        };
+        query.to_string()
+    }
+
+    #[test]
+    fn insert_values_keeps_every_row() {
+        let source = "INSERT INTO s.t (a, \"B\") VALUES (1, 'x'), (-2, NULL)";
+        let insert = insert(source);
+        assert_eq!(insert.table.to_string(), "s.t");
+        assert_eq!(columns(&insert), ["a", "B"]);
+        assert!(insert.columns[1].quoted);
+        assert_eq!(&source[insert.columns[1].span.range()], "\"B\"");
+        assert_eq!(rows(&insert), [["1", "'x'"], ["-2", "NULL"]]);
+        assert_eq!(insert.span.range(), 0..source.len());
+
+        let insert = self::insert("INSERT INTO t VALUES (1)");
+        assert!(insert.columns.is_empty());
+        assert_eq!(rows(&insert), [["1"]]);
+    }
+
+    #[test]
+    fn a_parenthesis_before_a_name_starts_a_column_list() {
+        let insert = self::insert("INSERT INTO t (a, b) SELECT x, y FROM u");
+        assert_eq!(columns(&insert), ["a", "b"]);
+        assert_eq!(query(&insert), "SELECT x, y FROM u");
+
+        let insert = self::insert("INSERT INTO t (SELECT x FROM u)");
+        assert!(insert.columns.is_empty());
+        let InsertSource::Query(query) = &insert.source else {
+            panic!("{:?}", insert.source);
+        };
+        assert!(matches!(query.body, SetExpr::Query(_)), "{query:?}");
+
+        let insert = self::insert("INSERT INTO t (WITH w AS (SELECT x FROM u) SELECT x FROM w)");
+        assert!(insert.columns.is_empty());
+        assert!(matches!(insert.source, InsertSource::Query(_)));
+    }
+
+    #[test]
+    fn insert_select_takes_an_optional_column_list() {
+        let insert = self::insert("INSERT INTO t SELECT * FROM u WHERE x > 1");
+        assert!(insert.columns.is_empty());
+        assert_eq!(query(&insert), "SELECT * FROM u WHERE x > 1");
+
+        let insert = self::insert("INSERT INTO t (a) SELECT x FROM u UNION SELECT y FROM v");
+        assert_eq!(columns(&insert), ["a"]);
+        assert_eq!(query(&insert), "SELECT x FROM u UNION SELECT y FROM v");
+    }
+
+    #[test]
+    fn update_keeps_every_assignment() {
+        let source = "UPDATE s.t SET a = a + 1, b = 'x' WHERE c IS NULL";
+        let update = update(source);
+        assert_eq!(update.table.to_string(), "s.t");
+        let assignments: Vec<(&str, String)> = update
+            .assignments
+            .iter()
+            .map(|assignment| {
+                (
+                    assignment.column.value.as_str(),
+                    assignment.value.to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            assignments,
+            [("a", "a + 1".to_string()), ("b", "'x'".to_string())]
+        );
+        assert_eq!(&source[update.assignments[0].span.range()], "a = a + 1");
+        assert_eq!(update.where_clause.unwrap().to_string(), "c IS NULL");
+        assert_eq!(update.span.range(), 0..source.len());
+
+        assert_eq!(self::update("UPDATE t SET a = 1").where_clause, None);
+    }
+
+    #[test]
+    fn delete_keeps_its_condition() {
+        let source = "DELETE FROM s.t WHERE a = 1 AND b <> 2";
+        let delete = delete(source);
+        assert_eq!(delete.table.to_string(), "s.t");
+        assert_eq!(delete.where_clause.unwrap().to_string(), "a = 1 AND b <> 2");
+        assert_eq!(delete.span.range(), 0..source.len());
+
+        let delete = self::delete("DELETE FROM t");
+        assert_eq!(delete.table.to_string(), "t");
+        assert_eq!(delete.where_clause, None);
+    }
+
+    #[test]
+    fn malformed_statements_are_errors() {
+        let errors = |source: &'static str| -> Vec<(String, &str)> {
+            Script::parse(source)
+                .errors
+                .iter()
+                .map(|error| (error.to_string(), &source[error.span.range()]))
+                .collect()
+        };
+        let error = |message: &str, text: &'static str| (message.to_string(), text);
+        assert_eq!(
+            errors("INSERT INTO t (a, b)"),
+            [error(
+                "expected `VALUES` or a query, found end of input",
+                ""
+            )]
+        );
+        assert_eq!(
+            errors("INSERT INTO t DEFAULT VALUES"),
+            [error(
+                "expected `VALUES` or a query, found keyword `DEFAULT`",
+                "DEFAULT"
+            )]
+        );
+        assert_eq!(
+            errors("UPDATE t SET a"),
+            [error("expected `=`, found end of input", "")]
+        );
+        assert_eq!(
+            errors("DELETE t"),
+            [error("expected `FROM`, found identifier", "t")]
+        );
+    }
+}
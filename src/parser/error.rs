@@ -0,0 +1,112 @@
+use std::fmt;
+
+use super::StatementKind;
+use crate::lexer::{LexError, Span, TokenKind};
+
+/// An error in the source, found while lexing or parsing it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// What is wrong.
+    pub kind: ParseErrorKind,
+    /// Where it is wrong.
+    pub span: Span,
+}
+
+/// The kinds of [`ParseError`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseErrorKind {
+    /// A token that cannot appear where it is.
+    UnexpectedToken {
+        /// A description of what could have appeared instead, such as ``"`FROM`"``.
+        expected: &'static str,
+        /// The token that appeared.
+        found: TokenKind,
+    },
+    /// The source ended in the middle of a statement.
+    UnexpectedEndOfInput {
+        /// A description of what should have come next.
+        expected: &'static str,
+    },
+    /// Source text that is not a valid token.
+    Lex(LexError),
+    /// An integer too large for a row count or data type length.
+    IntegerOutOfRange,
+    /// A comparison used as the operand of another, as in `a < b < c`.
+    ChainedComparison,
+    /// `NATURAL JOIN` followed by `ON` or `USING`.
+    NaturalJoinWithCondition,
+    /// A statement that is recognised but cannot be parsed yet.
+    UnsupportedStatement(StatementKind),
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::UnexpectedToken { expected, found } => {
+                write!(f, "expected {expected}, found {}", describe(found))
+            }
+            ParseErrorKind::UnexpectedEndOfInput { expected } => {
+                write!(f, "expected {expected}, found end of input")
+            }
+            ParseErrorKind::Lex(error) => write!(f, "{error}"),
+            ParseErrorKind::IntegerOutOfRange => write!(f, "integer is out of range"),
+            ParseErrorKind::ChainedComparison => {
+                write!(f, "comparison operators cannot be chained")
+            }
+            ParseErrorKind::NaturalJoinWithCondition => {
+                write!(f, "a natural join cannot have a join condition")
+            }
+            ParseErrorKind::UnsupportedStatement(kind) => {
+                write!(f, "{kind} statements are not supported yet")
+            }
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn describe(kind: &TokenKind) -> String {
+    let symbol = match kind {
+        TokenKind::Asterisk => "*",
+        TokenKind::Comma => ",",
+        TokenKind::Colon => ":",
+        TokenKind::Semicolon => ";",
+        TokenKind::LeftParenthesis => "(",
+        TokenKind::RightParenthesis => ")",
+        TokenKind::Percent => "%",
+        TokenKind::Period => ".",
+        TokenKind::Plus => "+",
+        TokenKind::Minus => "-",
+        TokenKind::Solidus => "/",
+        TokenKind::LessThan => "<",
+        TokenKind::GreaterThan => ">",
+        TokenKind::Equals => "=",
+        TokenKind::Underscore => "_",
+        TokenKind::Ampersand => "&",
+        TokenKind::QuestionMark => "?",
+        TokenKind::Circumflex => "^",
+        TokenKind::Tilde => "~",
+        TokenKind::VerticalBar => "|",
+        TokenKind::LessThanOrEquals => "<=",
+        TokenKind::GreaterThanOrEquals => ">=",
+        TokenKind::NotEquals => "<>",
+        TokenKind::Concatenate => "||",
+        TokenKind::Keyword(keyword) => return format!("keyword `{keyword}`"),
+        TokenKind::Identifier | TokenKind::DelimitedIdentifier => return "identifier".to_string(),
+        TokenKind::SingleQuotedStringLiteral => return "string literal".to_string(),
+        TokenKind::IntegerLiteral
+        | TokenKind::DecimalLiteral
+        | TokenKind::HexadecimalLiteral
+        | TokenKind::ExponentLiteral => return "numeric literal".to_string(),
+        TokenKind::Whitespace => return "whitespace".to_string(),
+        TokenKind::InlineComment | TokenKind::BlockComment => return "comment".to_string(),
+        TokenKind::Error(error) => return error.to_string(),
+    };
+    format!("`{symbol}`")
+}
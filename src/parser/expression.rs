@@ -0,0 +1,478 @@
+use crate::lexer::{Keyword, Span, TokenKind};
+
+use super::{
+    BinaryOperator, Expr, FunctionArguments, FunctionCall, Ident, Literal, LiteralValue,
+    ParseError, ParseErrorKind, Parser, UnaryOperator, WhenClause,
+};
+
+// Binding powers, from loosest to tightest. Infix operators bind with `(power, power + 1)`
+// so that they associate to the left.
+pub(super) const OR: u8 = 1;
+pub(super) const AND: u8 = 3;
+pub(super) const NOT: u8 = 5;
+pub(super) const COMPARISON: u8 = 7;
+pub(super) const BITWISE_OR: u8 = 9;
+pub(super) const BITWISE_XOR: u8 = 11;
+pub(super) const BITWISE_AND: u8 = 13;
+pub(super) const CONCATENATE: u8 = 15;
+pub(super) const ADDITIVE: u8 = 17;
+pub(super) const MULTIPLICATIVE: u8 = 19;
+pub(super) const UNARY: u8 = 21;
+
+#[derive(Debug, Clone, Copy)]
+enum InfixOperator {
+    Binary(BinaryOperator),
+    Between,
+    Like { case_insensitive: bool },
+    In,
+    Is,
+}
+
+impl BinaryOperator {
+    pub(super) fn binding_power(self) -> u8 {
+        match self {
+            BinaryOperator::Or => OR,
+            BinaryOperator::And => AND,
+            BinaryOperator::Equals
+            | BinaryOperator::NotEquals
+            | BinaryOperator::LessThan
+            | BinaryOperator::LessThanOrEquals
+            | BinaryOperator::GreaterThan
+            | BinaryOperator::GreaterThanOrEquals => COMPARISON,
+            BinaryOperator::BitwiseOr => BITWISE_OR,
+            BinaryOperator::BitwiseXor => BITWISE_XOR,
+            BinaryOperator::BitwiseAnd => BITWISE_AND,
+            BinaryOperator::Concatenate => CONCATENATE,
+            BinaryOperator::Plus | BinaryOperator::Minus => ADDITIVE,
+            BinaryOperator::Multiply | BinaryOperator::Divide => MULTIPLICATIVE,
+        }
+    }
+}
+
+impl InfixOperator {
+    fn binding_power(self) -> u8 {
+        match self {
+            InfixOperator::Binary(op) => op.binding_power(),
+            InfixOperator::Between
+            | InfixOperator::Like { .. }
+            | InfixOperator::In
+            | InfixOperator::Is => COMPARISON,
+        }
+    }
+}
+
+impl Parser<'_> {
+    pub(super) fn parse_expression(&mut self) -> Result<Expr, ParseError> {
+        self.parse_expression_with_power(0)
+    }
+
+    fn parse_expression_with_power(&mut self, min_power: u8) -> Result<Expr, ParseError> {
+        let start = self.current_span();
+        let mut left = self.parse_prefix()?;
+        // Comparisons and predicates are non-associative: `a = b = c` is rejected.
+        let mut compared = false;
+
+        while let Some((operator, negated, length)) = self.peek_infix() {
+            let power = operator.binding_power();
+            if power < min_power {
+                break;
+            }
+            if power == COMPARISON {
+                if compared {
+                    return Err(ParseError {
+                        kind: ParseErrorKind::ChainedComparison,
+                        span: self.current_span(),
+                    });
+                }
+                compared = true;
+            } else {
+                compared = false;
+            }
+            self.position += length;
+
+            left = match operator {
+                InfixOperator::Binary(op) => {
+                    let right = self.parse_expression_with_power(power + 1)?;
+                    Expr::Binary {
+                        left: Box::new(left),
+                        op,
+                        right: Box::new(right),
+                        span: self.span_from(start),
+                    }
+                }
+                InfixOperator::Between => {
+                    let low = self.parse_expression_with_power(COMPARISON + 1)?;
+                    self.expect_keyword(Keyword::And, "`AND`")?;
+                    let high = self.parse_expression_with_power(COMPARISON + 1)?;
+                    Expr::Between {
+                        expr: Box::new(left),
+                        negated,
+                        low: Box::new(low),
+                        high: Box::new(high),
+                        span: self.span_from(start),
+                    }
+                }
+                InfixOperator::Like { case_insensitive } => {
+                    let pattern = self.parse_expression_with_power(COMPARISON + 1)?;
+                    let escape = match self.consume_keyword(Keyword::Escape) {
+                        Some(_) => {
+                            Some(Box::new(self.parse_expression_with_power(COMPARISON + 1)?))
+                        }
+                        None => None,
+                    };
+                    Expr::Like {
+                        expr: Box::new(left),
+                        negated,
+                        case_insensitive,
+                        pattern: Box::new(pattern),
+                        escape,
+                        span: self.span_from(start),
+                    }
+                }
+                InfixOperator::In => self.parse_in(left, negated, start)?,
+                InfixOperator::Is => {
+                    let negated = self.consume_keyword(Keyword::Not).is_some();
+                    self.expect_keyword(Keyword::Null, "`NULL`")?;
+                    Expr::IsNull {
+                        expr: Box::new(left),
+                        negated,
+                        span: self.span_from(start),
+                    }
+                }
+            };
+        }
+
+        Ok(left)
+    }
+
+    fn parse_in(&mut self, left: Expr, negated: bool, start: Span) -> Result<Expr, ParseError> {
+        self.expect(TokenKind::LeftParenthesis, "`(`")?;
+        if self.is_query_start() {
+            let subquery = self.parse_query()?;
+            self.expect(TokenKind::RightParenthesis, "`)`")?;
+            return Ok(Expr::InSubquery {
+                expr: Box::new(left),
+                negated,
+                subquery: Box::new(subquery),
+                span: self.span_from(start),
+            });
+        }
+
+        let list = self.parse_comma_separated(Self::parse_expression)?;
+        self.expect(TokenKind::RightParenthesis, "`)`")?;
+        Ok(Expr::InList {
+            expr: Box::new(left),
+            negated,
+            list,
+            span: self.span_from(start),
+        })
+    }
+
+    /// Returns the infix operator at the cursor, whether it was negated by a leading `NOT`,
+    /// and how many tokens it spans.
+    fn peek_infix(&self) -> Option<(InfixOperator, bool, usize)> {
+        let binary = |op| Some((InfixOperator::Binary(op), false, 1));
+
+        match self.peek_kind()? {
+            TokenKind::Keyword(Keyword::Or) => binary(BinaryOperator::Or),
+            TokenKind::Keyword(Keyword::And) => binary(BinaryOperator::And),
+            TokenKind::Equals => binary(BinaryOperator::Equals),
+            TokenKind::NotEquals => binary(BinaryOperator::NotEquals),
+            TokenKind::LessThan => binary(BinaryOperator::LessThan),
+            TokenKind::LessThanOrEquals => binary(BinaryOperator::LessThanOrEquals),
+            TokenKind::GreaterThan => binary(BinaryOperator::GreaterThan),
+            TokenKind::GreaterThanOrEquals => binary(BinaryOperator::GreaterThanOrEquals),
+            TokenKind::Concatenate => binary(BinaryOperator::Concatenate),
+            TokenKind::VerticalBar => binary(BinaryOperator::BitwiseOr),
+            TokenKind::Circumflex => binary(BinaryOperator::BitwiseXor),
+            TokenKind::Ampersand => binary(BinaryOperator::BitwiseAnd),
+            TokenKind::Plus => binary(BinaryOperator::Plus),
+            TokenKind::Minus => binary(BinaryOperator::Minus),
+            TokenKind::Asterisk => binary(BinaryOperator::Multiply),
+            TokenKind::Solidus => binary(BinaryOperator::Divide),
+            TokenKind::Keyword(Keyword::Is) => Some((InfixOperator::Is, false, 1)),
+            TokenKind::Keyword(Keyword::Not) => {
+                let (operator, _, length) = self.peek_predicate(1)?;
+                Some((operator, true, length + 1))
+            }
+            _ => self.peek_predicate(0),
+        }
+    }
+
+    fn peek_predicate(&self, lookahead: usize) -> Option<(InfixOperator, bool, usize)> {
+        let operator = match self.peek_kind_at(lookahead)? {
+            TokenKind::Keyword(Keyword::Between) => InfixOperator::Between,
+            TokenKind::Keyword(Keyword::Like) => InfixOperator::Like {
+                case_insensitive: false,
+            },
+            TokenKind::Keyword(Keyword::Ilike) => InfixOperator::Like {
+                case_insensitive: true,
+            },
+            TokenKind::Keyword(Keyword::In) => InfixOperator::In,
+            _ => return None,
+        };
+        Some((operator, false, 1))
+    }
+
+    fn parse_prefix(&mut self) -> Result<Expr, ParseError> {
+        let Some(token) = self.peek().copied() else {
+            return Err(self.unexpected("an expression"));
+        };
+
+        let unary = match token.kind {
+            TokenKind::Keyword(Keyword::Not) => Some((UnaryOperator::Not, NOT)),
+            TokenKind::Plus => Some((UnaryOperator::Plus, UNARY)),
+            TokenKind::Minus => Some((UnaryOperator::Minus, UNARY)),
+            TokenKind::Tilde => Some((UnaryOperator::BitwiseNot, UNARY)),
+            _ => None,
+        };
+        if let Some((op, power)) = unary {
+            self.position += 1;
+            let operand = self.parse_expression_with_power(power)?;
+            return Ok(Expr::Unary {
+                op,
+                operand: Box::new(operand),
+                span: self.span_from(token.span),
+            });
+        }
+
+        let value = match token.kind {
+            TokenKind::IntegerLiteral
+            | TokenKind::DecimalLiteral
+            | TokenKind::HexadecimalLiteral
+            | TokenKind::ExponentLiteral => LiteralValue::Number(self.text(&token).to_string()),
+            TokenKind::SingleQuotedStringLiteral => LiteralValue::String(self.unescaped(&token)),
+            TokenKind::Keyword(Keyword::True) => LiteralValue::Boolean(true),
+            TokenKind::Keyword(Keyword::False) => LiteralValue::Boolean(false),
+            TokenKind::Keyword(Keyword::Null) => LiteralValue::Null,
+            TokenKind::LeftParenthesis => return self.parse_parenthesized(),
+            TokenKind::Keyword(Keyword::Exists) => {
+                self.position += 1;
+                self.expect(TokenKind::LeftParenthesis, "`(`")?;
+                let subquery = self.parse_query()?;
+                self.expect(TokenKind::RightParenthesis, "`)`")?;
+                return Ok(Expr::Exists {
+                    subquery: Box::new(subquery),
+                    span: self.span_from(token.span),
+                });
+            }
+            TokenKind::Keyword(Keyword::Case) => return self.parse_case(),
+            TokenKind::Keyword(keyword)
+                if keyword.is_function()
+                    && self.peek_kind_at(1) == Some(TokenKind::LeftParenthesis) =>
+            {
+                return self.parse_function_call();
+            }
+            TokenKind::Identifier if self.peek_kind_at(1) == Some(TokenKind::LeftParenthesis) => {
+                return self.parse_function_call();
+            }
+            _ if self.is_identifier(0) => return self.parse_object_name().map(Expr::Column),
+            _ => return Err(self.unexpected("an expression")),
+        };
+
+        self.position += 1;
+        Ok(Expr::Literal(Literal {
+            value,
+            span: token.span,
+        }))
+    }
+
+    fn parse_parenthesized(&mut self) -> Result<Expr, ParseError> {
+        let start = self.expect(TokenKind::LeftParenthesis, "`(`")?.span;
+        if self.is_query_start() {
+            let query = self.parse_query()?;
+            self.expect(TokenKind::RightParenthesis, "`)`")?;
+            return Ok(Expr::Subquery {
+                query: Box::new(query),
+                span: self.span_from(start),
+            });
+        }
+
+        let expr = self.parse_expression()?;
+        self.expect(TokenKind::RightParenthesis, "`)`")?;
+        Ok(expr)
+    }
+
+    fn parse_case(&mut self) -> Result<Expr, ParseError> {
+        let start = self.expect_keyword(Keyword::Case, "`CASE`")?.span;
+        let operand = if self.check_keyword(Keyword::When) {
+            None
+        } else {
+            Some(Box::new(self.parse_expression()?))
+        };
+
+        let mut branches = Vec::new();
+        while let Some(when) = self.consume_keyword(Keyword::When) {
+            let condition = self.parse_expression()?;
+            self.expect_keyword(Keyword::Then, "`THEN`")?;
+            let result = self.parse_expression()?;
+            branches.push(WhenClause {
+                condition,
+                result,
+                span: self.span_from(when.span),
+            });
+        }
+        if branches.is_empty() {
+            return Err(self.unexpected("`WHEN`"));
+        }
+
+        let else_result = match self.consume_keyword(Keyword::Else) {
+            Some(_) => Some(Box::new(self.parse_expression()?)),
+            None => None,
+        };
+        self.expect_keyword(Keyword::End, "`END`")?;
+
+        Ok(Expr::Case {
+            operand,
+            branches,
+            else_result,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_function_call(&mut self) -> Result<Expr, ParseError> {
+        let Some(token) = self.next_token() else {
+            return Err(self.unexpected("a function name"));
+        };
+        let name = Ident {
+            value: self.text(&token).to_string(),
+            quoted: false,
+            span: token.span,
+        };
+        self.expect(TokenKind::LeftParenthesis, "`(`")?;
+
+        let mut distinct = false;
+        let args = if let Some(asterisk) = self.consume(TokenKind::Asterisk) {
+            FunctionArguments::Wildcard(asterisk.span)
+        } else if self.peek_kind() == Some(TokenKind::RightParenthesis) {
+            FunctionArguments::List(Vec::new())
+        } else {
+            if self.consume_keyword(Keyword::Distinct).is_some() {
+                distinct = true;
+            } else {
+                self.consume_keyword(Keyword::All);
+            }
+            FunctionArguments::List(self.parse_comma_separated(Self::parse_expression)?)
+        };
+        self.expect(TokenKind::RightParenthesis, "`)`")?;
+
+        Ok(Expr::Function(FunctionCall {
+            name,
+            distinct,
+            args,
+            span: self.span_from(token.span),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Expr, ParseErrorKind, Parser, UnaryOperator};
+
+    /// Parses `source` as one expression and writes it with every operation in parentheses.
+    fn grouped(source: &str) -> String {
+        let mut parser = Parser::new(source);
+        let expr = parser.parse_expression().unwrap();
+        parser.expect_end().unwrap();
+        group(&expr)
+    }
+
+    fn group(expr: &Expr) -> String {
+        match expr {
+            Expr::Binary {
+                left, op, right, ..
+            } => format!("({} {op} {})", group(left), group(right)),
+            Expr::Unary {
+                op: UnaryOperator::Not,
+                operand,
+                ..
+            } => format!("(NOT {})", group(operand)),
+            Expr::Unary { op, operand, .. } => format!("({op}{})", group(operand)),
+            Expr::Between {
+                expr, low, high, ..
+            } => format!(
+                "({} BETWEEN {} AND {})",
+                group(expr),
+                group(low),
+                group(high)
+            ),
+            Expr::IsNull { expr, .. } => format!("({} IS NULL)", group(expr)),
+            Expr::Like { expr, pattern, .. } => {
+                format!("({} LIKE {})", group(expr), group(pattern))
+            }
+            other => other.to_string(),
+        }
+    }
+
+    fn error(source: &str) -> ParseErrorKind {
+        let mut parser = Parser::new(source);
+        match parser.parse_expression() {
+            Ok(expr) => panic!("`{source}` parsed as {expr}"),
+            Err(error) => error.kind,
+        }
+    }
+
+    #[test]
+    fn each_level_binds_tighter_than_the_one_before() {
+        assert_eq!(grouped("a OR b AND c"), "(a OR (b AND c))");
+        assert_eq!(grouped("a AND b OR c"), "((a AND b) OR c)");
+        assert_eq!(grouped("NOT a AND b"), "((NOT a) AND b)");
+        assert_eq!(grouped("NOT a = b"), "(NOT (a = b))");
+        assert_eq!(grouped("a = b || c"), "(a = (b || c))");
+        assert_eq!(grouped("a || b + c"), "(a || (b + c))");
+        assert_eq!(grouped("a + b * c"), "(a + (b * c))");
+        assert_eq!(grouped("a * -b"), "(a * (-b))");
+        assert_eq!(grouped("-a * b"), "((-a) * b)");
+    }
+
+    #[test]
+    fn bitwise_operators_sit_between_comparison_and_concatenation() {
+        assert_eq!(grouped("a = b | c"), "(a = (b | c))");
+        assert_eq!(grouped("a | b ^ c & d"), "(a | (b ^ (c & d)))");
+        assert_eq!(grouped("a & b || c"), "(a & (b || c))");
+        assert_eq!(grouped("~a & b"), "((~a) & b)");
+    }
+
+    #[test]
+    fn predicates_bind_like_comparisons() {
+        assert_eq!(
+            grouped("a + 1 BETWEEN b AND c * 2"),
+            "((a + 1) BETWEEN b AND (c * 2))"
+        );
+        assert_eq!(
+            grouped("a BETWEEN 1 AND 2 AND b"),
+            "((a BETWEEN 1 AND 2) AND b)"
+        );
+        assert_eq!(grouped("a || b LIKE c"), "((a || b) LIKE c)");
+        assert_eq!(grouped("NOT a IS NULL"), "(NOT (a IS NULL))");
+    }
+
+    #[test]
+    fn binary_operators_associate_to_the_left() {
+        assert_eq!(grouped("a - b - c"), "((a - b) - c)");
+        assert_eq!(grouped("a / b * c"), "((a / b) * c)");
+        assert_eq!(grouped("a || b || c"), "((a || b) || c)");
+        assert_eq!(grouped("a OR b OR c"), "((a OR b) OR c)");
+        assert_eq!(grouped("a AND b AND c"), "((a AND b) AND c)");
+    }
+
+    #[test]
+    fn parentheses_override_precedence() {
+        assert_eq!(grouped("(a + b) * c"), "((a + b) * c)");
+        assert_eq!(grouped("a - (b - c)"), "(a - (b - c))");
+        assert_eq!(grouped("NOT (a OR b)"), "(NOT (a OR b))");
+    }
+
+    #[test]
+    fn chained_comparisons_are_rejected() {
+        assert_eq!(error("a < b < c"), ParseErrorKind::ChainedComparison);
+        assert_eq!(error("a = b = c"), ParseErrorKind::ChainedComparison);
+        assert_eq!(
+            error("a = b BETWEEN 1 AND 2"),
+            ParseErrorKind::ChainedComparison
+        );
+        assert_eq!(error("a IS NULL = b"), ParseErrorKind::ChainedComparison);
+        assert_eq!(grouped("a < b AND b < c"), "((a < b) AND (b < c))");
+        assert_eq!(grouped("(a < b) = c"), "((a < b) = c)");
+    }
+}
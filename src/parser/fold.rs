@@ -0,0 +1,691 @@
+//! Rewriting of the AST by value.
+//!
+//! Each `fold_*` method takes a node and returns its replacement. The defaults call the
+//! matching `walk_*` function, which folds the children of the node and rebuilds it, so an
+//! implementation only overrides the nodes it rewrites:
+//!
+//! ```
+//! use daoql::parser::fold::{walk_expr, Fold};
+//! use daoql::parser::{Expr, UnaryOperator};
+//! use daoql::Script;
+//!
+//! /// Drops every unary `+`, rewriting `+x` to `x`.
+//! struct RemoveUnaryPlus;
+//!
+//! impl Fold for RemoveUnaryPlus {
+//!     fn fold_expr(&mut self, expr: Expr) -> Expr {
+//!         match walk_expr(self, expr) {
+//!             Expr::Unary { op: UnaryOperator::Plus, operand, .. } => *operand,
+//!             expr => expr,
+//!         }
+//!     }
+//! }
+//!
+//! let script = Script::parse("SELECT +ra FROM gaia.source WHERE +dec > -1");
+//! let statement = RemoveUnaryPlus.fold_statement(script.statements[0].statement.clone());
+//! assert_eq!(statement.to_string(), "SELECT ra FROM gaia.source WHERE dec > -1");
+//! ```
+
+use super::{
+    Assignment, ColumnConstraint, ColumnConstraintKind, ColumnDefinition, CommonTableExpression,
+    CreateTable, DataType, Delete, DropTable, Expr, ForeignKeyTarget, FunctionArguments,
+    FunctionCall, Ident, Insert, InsertSource, Join, JoinConstraint, Limit, Literal, ObjectName,
+    OrderByItem, Query, Select, SelectItem, SetExpr, Statement, TableConstraint,
+    TableConstraintKind, TableReference, Update, WhenClause,
+};
+
+/// A transformation that consumes the AST and returns a rewritten one.
+pub trait Fold {
+    /// Folds a [`Statement`]. Defaults to [`walk_statement`].
+    fn fold_statement(&mut self, statement: Statement) -> Statement {
+        walk_statement(self, statement)
+    }
+
+    /// Folds a [`CreateTable`]. Defaults to [`walk_create_table`].
+    fn fold_create_table(&mut self, create: CreateTable) -> CreateTable {
+        walk_create_table(self, create)
+    }
+
+    /// Folds a [`ColumnDefinition`]. Defaults to [`walk_column_definition`].
+    fn fold_column_definition(&mut self, column: ColumnDefinition) -> ColumnDefinition {
+        walk_column_definition(self, column)
+    }
+
+    /// Folds a [`DataType`]. Defaults to [`walk_data_type`].
+    fn fold_data_type(&mut self, data_type: DataType) -> DataType {
+        walk_data_type(self, data_type)
+    }
+
+    /// Folds a [`ColumnConstraint`]. Defaults to [`walk_column_constraint`].
+    fn fold_column_constraint(&mut self, constraint: ColumnConstraint) -> ColumnConstraint {
+        walk_column_constraint(self, constraint)
+    }
+
+    /// Folds a [`TableConstraint`]. Defaults to [`walk_table_constraint`].
+    fn fold_table_constraint(&mut self, constraint: TableConstraint) -> TableConstraint {
+        walk_table_constraint(self, constraint)
+    }
+
+    /// Folds a [`ForeignKeyTarget`]. Defaults to [`walk_foreign_key_target`].
+    fn fold_foreign_key_target(&mut self, target: ForeignKeyTarget) -> ForeignKeyTarget {
+        walk_foreign_key_target(self, target)
+    }
+
+    /// Folds a [`DropTable`]. Defaults to [`walk_drop_table`].
+    fn fold_drop_table(&mut self, drop: DropTable) -> DropTable {
+        walk_drop_table(self, drop)
+    }
+
+    /// Folds an [`Insert`]. Defaults to [`walk_insert`].
+    fn fold_insert(&mut self, insert: Insert) -> Insert {
+        walk_insert(self, insert)
+    }
+
+    /// Folds an [`Update`]. Defaults to [`walk_update`].
+    fn fold_update(&mut self, update: Update) -> Update {
+        walk_update(self, update)
+    }
+
+    /// Folds an [`Assignment`]. Defaults to [`walk_assignment`].
+    fn fold_assignment(&mut self, assignment: Assignment) -> Assignment {
+        walk_assignment(self, assignment)
+    }
+
+    /// Folds a [`Delete`]. Defaults to [`walk_delete`].
+    fn fold_delete(&mut self, delete: Delete) -> Delete {
+        walk_delete(self, delete)
+    }
+
+    /// Folds a [`Query`]. Defaults to [`walk_query`].
+    fn fold_query(&mut self, query: Query) -> Query {
+        walk_query(self, query)
+    }
+
+    /// Folds a [`CommonTableExpression`]. Defaults to [`walk_common_table_expression`].
+    fn fold_common_table_expression(
+        &mut self,
+        cte: CommonTableExpression,
+    ) -> CommonTableExpression {
+        walk_common_table_expression(self, cte)
+    }
+
+    /// Folds a [`SetExpr`]. Defaults to [`walk_set_expr`].
+    fn fold_set_expr(&mut self, set_expr: SetExpr) -> SetExpr {
+        walk_set_expr(self, set_expr)
+    }
+
+    /// Folds a [`Select`]. Defaults to [`walk_select`].
+    fn fold_select(&mut self, select: Select) -> Select {
+        walk_select(self, select)
+    }
+
+    /// Folds a [`Limit`], returned unchanged by default.
+    fn fold_limit(&mut self, limit: Limit) -> Limit {
+        limit
+    }
+
+    /// Folds a [`SelectItem`]. Defaults to [`walk_select_item`].
+    fn fold_select_item(&mut self, item: SelectItem) -> SelectItem {
+        walk_select_item(self, item)
+    }
+
+    /// Folds a [`TableReference`]. Defaults to [`walk_table_reference`].
+    fn fold_table_reference(&mut self, table: TableReference) -> TableReference {
+        walk_table_reference(self, table)
+    }
+
+    /// Folds a [`Join`]. Defaults to [`walk_join`].
+    fn fold_join(&mut self, join: Join) -> Join {
+        walk_join(self, join)
+    }
+
+    /// Folds a [`JoinConstraint`]. Defaults to [`walk_join_constraint`].
+    fn fold_join_constraint(&mut self, constraint: JoinConstraint) -> JoinConstraint {
+        walk_join_constraint(self, constraint)
+    }
+
+    /// Folds an [`OrderByItem`]. Defaults to [`walk_order_by_item`].
+    fn fold_order_by_item(&mut self, item: OrderByItem) -> OrderByItem {
+        walk_order_by_item(self, item)
+    }
+
+    /// Folds an [`Expr`]. Defaults to [`walk_expr`].
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        walk_expr(self, expr)
+    }
+
+    /// Folds a [`WhenClause`]. Defaults to [`walk_when_clause`].
+    fn fold_when_clause(&mut self, when: WhenClause) -> WhenClause {
+        walk_when_clause(self, when)
+    }
+
+    /// Folds a [`FunctionCall`]. Defaults to [`walk_function_call`].
+    fn fold_function_call(&mut self, call: FunctionCall) -> FunctionCall {
+        walk_function_call(self, call)
+    }
+
+    /// Folds a [`Literal`], returned unchanged by default.
+    fn fold_literal(&mut self, literal: Literal) -> Literal {
+        literal
+    }
+
+    /// Folds an [`ObjectName`]. Defaults to [`walk_object_name`].
+    fn fold_object_name(&mut self, name: ObjectName) -> ObjectName {
+        walk_object_name(self, name)
+    }
+
+    /// Folds an [`Ident`], returned unchanged by default.
+    fn fold_ident(&mut self, ident: Ident) -> Ident {
+        ident
+    }
+}
+
+fn fold_idents<F: Fold + ?Sized>(folder: &mut F, idents: Vec<Ident>) -> Vec<Ident> {
+    idents
+        .into_iter()
+        .map(|ident| folder.fold_ident(ident))
+        .collect()
+}
+
+fn fold_exprs<F: Fold + ?Sized>(folder: &mut F, exprs: Vec<Expr>) -> Vec<Expr> {
+    exprs
+        .into_iter()
+        .map(|expr| folder.fold_expr(expr))
+        .collect()
+}
+
+/// Folds the children of a [`Statement`] and rebuilds it from the results.
+pub fn walk_statement<F: Fold + ?Sized>(folder: &mut F, statement: Statement) -> Statement {
+    match statement {
+        Statement::Query(query) => Statement::Query(Box::new(folder.fold_query(*query))),
+        Statement::CreateTable(create) => {
+            Statement::CreateTable(Box::new(folder.fold_create_table(*create)))
+        }
+        Statement::DropTable(drop) => Statement::DropTable(folder.fold_drop_table(drop)),
+        Statement::Insert(insert) => Statement::Insert(Box::new(folder.fold_insert(*insert))),
+        Statement::Update(update) => Statement::Update(Box::new(folder.fold_update(*update))),
+        Statement::Delete(delete) => Statement::Delete(Box::new(folder.fold_delete(*delete))),
+        statement @ (Statement::Transaction { .. } | Statement::Error(_)) => statement,
+    }
+}
+
+/// Folds the children of a [`CreateTable`] and rebuilds it from the results.
+pub fn walk_create_table<F: Fold + ?Sized>(folder: &mut F, create: CreateTable) -> CreateTable {
+    CreateTable {
+        name: folder.fold_object_name(create.name),
+        columns: create
+            .columns
+            .into_iter()
+            .map(|column| folder.fold_column_definition(column))
+            .collect(),
+        constraints: create
+            .constraints
+            .into_iter()
+            .map(|constraint| folder.fold_table_constraint(constraint))
+            .collect(),
+        span: create.span,
+    }
+}
+
+/// Folds the children of a [`ColumnDefinition`] and rebuilds it from the results.
+pub fn walk_column_definition<F: Fold + ?Sized>(
+    folder: &mut F,
+    column: ColumnDefinition,
+) -> ColumnDefinition {
+    ColumnDefinition {
+        name: folder.fold_ident(column.name),
+        data_type: folder.fold_data_type(column.data_type),
+        constraints: column
+            .constraints
+            .into_iter()
+            .map(|constraint| folder.fold_column_constraint(constraint))
+            .collect(),
+        span: column.span,
+    }
+}
+
+/// Folds the children of a [`DataType`] and rebuilds it from the results.
+pub fn walk_data_type<F: Fold + ?Sized>(folder: &mut F, data_type: DataType) -> DataType {
+    match data_type {
+        DataType::Custom(name) => DataType::Custom(folder.fold_ident(name)),
+        data_type => data_type,
+    }
+}
+
+/// Folds the children of a [`ColumnConstraint`] and rebuilds it from the results.
+pub fn walk_column_constraint<F: Fold + ?Sized>(
+    folder: &mut F,
+    constraint: ColumnConstraint,
+) -> ColumnConstraint {
+    let kind = match constraint.kind {
+        ColumnConstraintKind::Default(expr) => {
+            ColumnConstraintKind::Default(folder.fold_expr(expr))
+        }
+        ColumnConstraintKind::Check(expr) => ColumnConstraintKind::Check(folder.fold_expr(expr)),
+        ColumnConstraintKind::References(target) => {
+            ColumnConstraintKind::References(folder.fold_foreign_key_target(target))
+        }
+        kind @ (ColumnConstraintKind::NotNull
+        | ColumnConstraintKind::Null
+        | ColumnConstraintKind::PrimaryKey
+        | ColumnConstraintKind::Unique) => kind,
+    };
+    ColumnConstraint {
+        name: constraint.name.map(|name| folder.fold_ident(name)),
+        kind,
+        span: constraint.span,
+    }
+}
+
+/// Folds the children of a [`TableConstraint`] and rebuilds it from the results.
+pub fn walk_table_constraint<F: Fold + ?Sized>(
+    folder: &mut F,
+    constraint: TableConstraint,
+) -> TableConstraint {
+    let name = constraint.name.map(|name| folder.fold_ident(name));
+    let kind = match constraint.kind {
+        TableConstraintKind::PrimaryKey(columns) => {
+            TableConstraintKind::PrimaryKey(fold_idents(folder, columns))
+        }
+        TableConstraintKind::Unique(columns) => {
+            TableConstraintKind::Unique(fold_idents(folder, columns))
+        }
+        TableConstraintKind::ForeignKey { columns, target } => TableConstraintKind::ForeignKey {
+            columns: fold_idents(folder, columns),
+            target: folder.fold_foreign_key_target(target),
+        },
+        TableConstraintKind::Check(expr) => TableConstraintKind::Check(folder.fold_expr(expr)),
+    };
+    TableConstraint {
+        name,
+        kind,
+        span: constraint.span,
+    }
+}
+
+/// Folds the children of a [`ForeignKeyTarget`] and rebuilds it from the results.
+pub fn walk_foreign_key_target<F: Fold + ?Sized>(
+    folder: &mut F,
+    target: ForeignKeyTarget,
+) -> ForeignKeyTarget {
+    ForeignKeyTarget {
+        table: folder.fold_object_name(target.table),
+        columns: fold_idents(folder, target.columns),
+        span: target.span,
+    }
+}
+
+/// Folds the children of a [`DropTable`] and rebuilds it from the results.
+pub fn walk_drop_table<F: Fold + ?Sized>(folder: &mut F, drop: DropTable) -> DropTable {
+    DropTable {
+        names: drop
+            .names
+            .into_iter()
+            .map(|name| folder.fold_object_name(name))
+            .collect(),
+        behaviour: drop.behaviour,
+        span: drop.span,
+    }
+}
+
+/// Folds the children of an [`Insert`] and rebuilds it from the results.
+pub fn walk_insert<F: Fold + ?Sized>(folder: &mut F, insert: Insert) -> Insert {
+    let table = folder.fold_object_name(insert.table);
+    let columns = fold_idents(folder, insert.columns);
+    let source = match insert.source {
+        InsertSource::Values(rows) => InsertSource::Values(
+            rows.into_iter()
+                .map(|row| fold_exprs(folder, row))
+                .collect(),
+        ),
+        InsertSource::Query(query) => InsertSource::Query(Box::new(folder.fold_query(*query))),
+    };
+    Insert {
+        table,
+        columns,
+        source,
+        span: insert.span,
+    }
+}
+
+/// Folds the children of an [`Update`] and rebuilds it from the results.
+pub fn walk_update<F: Fold + ?Sized>(folder: &mut F, update: Update) -> Update {
+    Update {
+        table: folder.fold_object_name(update.table),
+        assignments: update
+            .assignments
+            .into_iter()
+            .map(|assignment| folder.fold_assignment(assignment))
+            .collect(),
+        where_clause: update.where_clause.map(|expr| folder.fold_expr(expr)),
+        span: update.span,
+    }
+}
+
+/// Folds the children of an [`Assignment`] and rebuilds it from the results.
+pub fn walk_assignment<F: Fold + ?Sized>(folder: &mut F, assignment: Assignment) -> Assignment {
+    Assignment {
+        column: folder.fold_ident(assignment.column),
+        value: folder.fold_expr(assignment.value),
+        span: assignment.span,
+    }
+}
+
+/// Folds the children of a [`Delete`] and rebuilds it from the results.
+pub fn walk_delete<F: Fold + ?Sized>(folder: &mut F, delete: Delete) -> Delete {
+    Delete {
+        table: folder.fold_object_name(delete.table),
+        where_clause: delete.where_clause.map(|expr| folder.fold_expr(expr)),
+        span: delete.span,
+    }
+}
+
+/// Folds the children of a [`Query`] and rebuilds it from the results.
+pub fn walk_query<F: Fold + ?Sized>(folder: &mut F, query: Query) -> Query {
+    Query {
+        with: query
+            .with
+            .into_iter()
+            .map(|cte| folder.fold_common_table_expression(cte))
+            .collect(),
+        body: folder.fold_set_expr(query.body),
+        order_by: query
+            .order_by
+            .into_iter()
+            .map(|item| folder.fold_order_by_item(item))
+            .collect(),
+        offset: query.offset.map(|offset| folder.fold_limit(offset)),
+        span: query.span,
+    }
+}
+
+/// Folds the children of a [`CommonTableExpression`] and rebuilds it from the results.
+pub fn walk_common_table_expression<F: Fold + ?Sized>(
+    folder: &mut F,
+    cte: CommonTableExpression,
+) -> CommonTableExpression {
+    CommonTableExpression {
+        name: folder.fold_ident(cte.name),
+        columns: fold_idents(folder, cte.columns),
+        query: Box::new(folder.fold_query(*cte.query)),
+        span: cte.span,
+    }
+}
+
+/// Folds the children of a [`SetExpr`] and rebuilds it from the results.
+pub fn walk_set_expr<F: Fold + ?Sized>(folder: &mut F, set_expr: SetExpr) -> SetExpr {
+    match set_expr {
+        SetExpr::Select(select) => SetExpr::Select(Box::new(folder.fold_select(*select))),
+        SetExpr::Query(query) => SetExpr::Query(Box::new(folder.fold_query(*query))),
+        SetExpr::SetOperation {
+            op,
+            all,
+            left,
+            right,
+            span,
+        } => SetExpr::SetOperation {
+            op,
+            all,
+            left: Box::new(folder.fold_set_expr(*left)),
+            right: Box::new(folder.fold_set_expr(*right)),
+            span,
+        },
+    }
+}
+
+/// Folds the children of a [`Select`] and rebuilds it from the results.
+pub fn walk_select<F: Fold + ?Sized>(folder: &mut F, select: Select) -> Select {
+    Select {
+        distinct: select.distinct,
+        top: select.top.map(|top| folder.fold_limit(top)),
+        items: select
+            .items
+            .into_iter()
+            .map(|item| folder.fold_select_item(item))
+            .collect(),
+        from: select
+            .from
+            .into_iter()
+            .map(|table| folder.fold_table_reference(table))
+            .collect(),
+        where_clause: select.where_clause.map(|expr| folder.fold_expr(expr)),
+        group_by: fold_exprs(folder, select.group_by),
+        having: select.having.map(|expr| folder.fold_expr(expr)),
+        span: select.span,
+    }
+}
+
+/// Folds the children of a [`SelectItem`] and rebuilds it from the results.
+pub fn walk_select_item<F: Fold + ?Sized>(folder: &mut F, item: SelectItem) -> SelectItem {
+    match item {
+        SelectItem::QualifiedWildcard(name, span) => {
+            SelectItem::QualifiedWildcard(folder.fold_object_name(name), span)
+        }
+        SelectItem::Expression { expr, alias, span } => SelectItem::Expression {
+            expr: folder.fold_expr(expr),
+            alias: alias.map(|alias| folder.fold_ident(alias)),
+            span,
+        },
+        item @ (SelectItem::Wildcard(_) | SelectItem::Error(_)) => item,
+    }
+}
+
+/// Folds the children of a [`TableReference`] and rebuilds it from the results.
+pub fn walk_table_reference<F: Fold + ?Sized>(
+    folder: &mut F,
+    table: TableReference,
+) -> TableReference {
+    match table {
+        TableReference::Table { name, alias, span } => TableReference::Table {
+            name: folder.fold_object_name(name),
+            alias: alias.map(|alias| folder.fold_ident(alias)),
+            span,
+        },
+        TableReference::Derived { query, alias, span } => TableReference::Derived {
+            query: Box::new(folder.fold_query(*query)),
+            alias: folder.fold_ident(alias),
+            span,
+        },
+        TableReference::Join(join) => TableReference::Join(Box::new(folder.fold_join(*join))),
+        TableReference::Error(span) => TableReference::Error(span),
+    }
+}
+
+/// Folds the children of a [`Join`] and rebuilds it from the results.
+pub fn walk_join<F: Fold + ?Sized>(folder: &mut F, join: Join) -> Join {
+    Join {
+        left: folder.fold_table_reference(join.left),
+        right: folder.fold_table_reference(join.right),
+        kind: join.kind,
+        constraint: folder.fold_join_constraint(join.constraint),
+        span: join.span,
+    }
+}
+
+/// Folds the children of a [`JoinConstraint`] and rebuilds it from the results.
+pub fn walk_join_constraint<F: Fold + ?Sized>(
+    folder: &mut F,
+    constraint: JoinConstraint,
+) -> JoinConstraint {
+    match constraint {
+        JoinConstraint::On(condition) => JoinConstraint::On(folder.fold_expr(condition)),
+        JoinConstraint::Using(columns) => JoinConstraint::Using(fold_idents(folder, columns)),
+        constraint @ (JoinConstraint::Natural | JoinConstraint::None) => constraint,
+    }
+}
+
+/// Folds the children of an [`OrderByItem`] and rebuilds it from the results.
+pub fn walk_order_by_item<F: Fold + ?Sized>(folder: &mut F, item: OrderByItem) -> OrderByItem {
+    OrderByItem {
+        expr: folder.fold_expr(item.expr),
+        direction: item.direction,
+        span: item.span,
+    }
+}
+
+/// Folds the children of an [`Expr`] and rebuilds it from the results.
+pub fn walk_expr<F: Fold + ?Sized>(folder: &mut F, expr: Expr) -> Expr {
+    match expr {
+        Expr::Column(name) => Expr::Column(folder.fold_object_name(name)),
+        Expr::Literal(literal) => Expr::Literal(folder.fold_literal(literal)),
+        Expr::Unary { op, operand, span } => Expr::Unary {
+            op,
+            operand: Box::new(folder.fold_expr(*operand)),
+            span,
+        },
+        Expr::Binary {
+            left,
+            op,
+            right,
+            span,
+        } => Expr::Binary {
+            left: Box::new(folder.fold_expr(*left)),
+            op,
+            right: Box::new(folder.fold_expr(*right)),
+            span,
+        },
+        Expr::Between {
+            expr,
+            negated,
+            low,
+            high,
+            span,
+        } => Expr::Between {
+            expr: Box::new(folder.fold_expr(*expr)),
+            negated,
+            low: Box::new(folder.fold_expr(*low)),
+            high: Box::new(folder.fold_expr(*high)),
+            span,
+        },
+        Expr::Like {
+            expr,
+            negated,
+            case_insensitive,
+            pattern,
+            escape,
+            span,
+        } => Expr::Like {
+            expr: Box::new(folder.fold_expr(*expr)),
+            negated,
+            case_insensitive,
+            pattern: Box::new(folder.fold_expr(*pattern)),
+            escape: escape.map(|escape| Box::new(folder.fold_expr(*escape))),
+            span,
+        },
+        Expr::InList {
+            expr,
+            negated,
+            list,
+            span,
+        } => Expr::InList {
+            expr: Box::new(folder.fold_expr(*expr)),
+            negated,
+            list: fold_exprs(folder, list),
+            span,
+        },
+        Expr::InSubquery {
+            expr,
+            negated,
+            subquery,
+            span,
+        } => Expr::InSubquery {
+            expr: Box::new(folder.fold_expr(*expr)),
+            negated,
+            subquery: Box::new(folder.fold_query(*subquery)),
+            span,
+        },
+        Expr::IsNull {
+            expr,
+            negated,
+            span,
+        } => Expr::IsNull {
+            expr: Box::new(folder.fold_expr(*expr)),
+            negated,
+            span,
+        },
+        Expr::Exists { subquery, span } => Expr::Exists {
+            subquery: Box::new(folder.fold_query(*subquery)),
+            span,
+        },
+        Expr::Case {
+            operand,
+            branches,
+            else_result,
+            span,
+        } => Expr::Case {
+            operand: operand.map(|operand| Box::new(folder.fold_expr(*operand))),
+            branches: branches
+                .into_iter()
+                .map(|branch| folder.fold_when_clause(branch))
+                .collect(),
+            else_result: else_result.map(|result| Box::new(folder.fold_expr(*result))),
+            span,
+        },
+        Expr::Function(call) => Expr::Function(folder.fold_function_call(call)),
+        Expr::Subquery { query, span } => Expr::Subquery {
+            query: Box::new(folder.fold_query(*query)),
+            span,
+        },
+        Expr::Error(span) => Expr::Error(span),
+    }
+}
+
+/// Folds the children of a [`WhenClause`] and rebuilds it from the results.
+pub fn walk_when_clause<F: Fold + ?Sized>(folder: &mut F, when: WhenClause) -> WhenClause {
+    WhenClause {
+        condition: folder.fold_expr(when.condition),
+        result: folder.fold_expr(when.result),
+        span: when.span,
+    }
+}
+
+/// Folds the children of a [`FunctionCall`] and rebuilds it from the results.
+pub fn walk_function_call<F: Fold + ?Sized>(folder: &mut F, call: FunctionCall) -> FunctionCall {
+    let name = folder.fold_ident(call.name);
+    let args = match call.args {
+        FunctionArguments::List(args) => FunctionArguments::List(fold_exprs(folder, args)),
+        args @ FunctionArguments::Wildcard(_) => args,
+    };
+    FunctionCall {
+        name,
+        distinct: call.distinct,
+        args,
+        span: call.span,
+    }
+}
+
+/// Folds the children of an [`ObjectName`] and rebuilds it from the results.
+pub fn walk_object_name<F: Fold + ?Sized>(folder: &mut F, name: ObjectName) -> ObjectName {
+    ObjectName {
+        parts: fold_idents(folder, name.parts),
+        span: name.span,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Fold;
+    use crate::parser::visit::tests::{identifiers, parse, SOURCES};
+    use crate::parser::Ident;
+
+    struct Rename;
+
+    impl Fold for Rename {
+        fn fold_ident(&mut self, ident: Ident) -> Ident {
+            let value = format!("x_{}", ident.value);
+            Ident { value, ..ident }
+        }
+    }
+
+    #[test]
+    fn every_identifier_is_folded() {
+        for source in SOURCES {
+            let printed = Rename.fold_statement(parse(source)).to_string();
+            let renamed: Vec<String> = identifiers(source)
+                .iter()
+                .map(|identifier| format!("x_{identifier}"))
+                .collect();
+            assert_eq!(identifiers(&printed), renamed, "{printed}");
+        }
+    }
+}
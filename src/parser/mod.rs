@@ -0,0 +1,975 @@
+//! Parsing tokens into an AST, with error recovery so that every error is reported.
+
+mod ast;
+mod ddl;
+mod display;
+mod dml;
+mod error;
+mod expression;
+pub mod fold;
+mod script;
+pub mod visit;
+pub mod visit_mut;
+
+pub use ast::*;
+pub use error::{ParseError, ParseErrorKind};
+pub use script::{Script, ScriptStatement, StatementKind};
+
+use crate::lexer::{Decimal, Keyword, LexError, Lexer, Span, Token, TokenKind};
+
+/// Parses a single query, failing with the first error in the source.
+pub fn parse_query(source: &str) -> Result<Query, ParseError> {
+    let mut parser = Parser::new(source);
+    let query = parser.parse_query().and_then(|query| {
+        parser.consume(TokenKind::Semicolon);
+        parser.expect_end()?;
+        Ok(query)
+    });
+    if let Err(error) = &query {
+        parser.errors.push(error.clone());
+    }
+    match parser.into_errors().into_iter().next() {
+        Some(error) => Err(error),
+        None => query,
+    }
+}
+
+/// Clause keywords that error recovery resumes parsing at.
+const CLAUSE_KEYWORDS: [Keyword; 9] = [
+    Keyword::From,
+    Keyword::Where,
+    Keyword::Group,
+    Keyword::Having,
+    Keyword::Union,
+    Keyword::Intersect,
+    Keyword::Except,
+    Keyword::Order,
+    Keyword::Offset,
+];
+
+pub(crate) struct Parser<'a> {
+    source: &'a str,
+    tokens: Vec<Token>,
+    position: usize,
+    /// Index of the token that ends the input as far as the parser can see, so that a
+    /// statement of a script is parsed on its own.
+    end: usize,
+    /// Errors that have been recovered from, including lex errors.
+    errors: Vec<ParseError>,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let mut tokens = Vec::new();
+        let mut errors = Vec::new();
+        for token in Lexer::new(source).filter(|token| !token.kind.is_trivia()) {
+            match token.kind {
+                TokenKind::Error(error) => {
+                    errors.push(ParseError {
+                        kind: ParseErrorKind::Lex(error),
+                        span: token.span,
+                    });
+                    // `""` is still parsed as a name, so the statement around it is checked
+                    if error == LexError::EmptyIdentifier {
+                        tokens.push(Token {
+                            kind: TokenKind::DelimitedIdentifier,
+                            ..token
+                        });
+                    }
+                }
+                _ => tokens.push(token),
+            }
+        }
+        Self {
+            source,
+            end: tokens.len(),
+            tokens,
+            position: 0,
+            errors,
+        }
+    }
+
+    /// The recovered errors in source order.
+    pub fn into_errors(mut self) -> Vec<ParseError> {
+        // An unterminated string or comment swallows the rest of the input, so anything
+        // reported as missing at the end is a consequence of it
+        let end = self.source.len();
+        if self
+            .errors
+            .iter()
+            .any(|error| matches!(error.kind, ParseErrorKind::Lex(_)) && error.span.end() == end)
+        {
+            self.errors
+                .retain(|error| !matches!(error.kind, ParseErrorKind::UnexpectedEndOfInput { .. }));
+        }
+        self.errors.sort_by_key(|error| error.span.start);
+        self.errors
+    }
+
+    fn parse_statement(&mut self) -> Result<Statement, ParseError> {
+        match self.peek_kind() {
+            Some(
+                TokenKind::Keyword(Keyword::Select | Keyword::With) | TokenKind::LeftParenthesis,
+            ) => Ok(Statement::Query(Box::new(self.parse_query()?))),
+            Some(TokenKind::Keyword(Keyword::Create)) => {
+                Ok(Statement::CreateTable(Box::new(self.parse_create_table()?)))
+            }
+            Some(TokenKind::Keyword(Keyword::Drop)) => {
+                self.parse_drop_table().map(Statement::DropTable)
+            }
+            Some(TokenKind::Keyword(Keyword::Insert)) => {
+                Ok(Statement::Insert(Box::new(self.parse_insert()?)))
+            }
+            Some(TokenKind::Keyword(Keyword::Update)) => {
+                Ok(Statement::Update(Box::new(self.parse_update()?)))
+            }
+            Some(TokenKind::Keyword(Keyword::Delete)) => {
+                Ok(Statement::Delete(Box::new(self.parse_delete()?)))
+            }
+            Some(TokenKind::Keyword(Keyword::Begin | Keyword::Commit | Keyword::Rollback)) => {
+                self.parse_transaction()
+            }
+            kind => match kind.and_then(StatementKind::classify) {
+                Some(kind) => Err(ParseError {
+                    kind: ParseErrorKind::UnsupportedStatement(kind),
+                    span: self.current_span(),
+                }),
+                None => Err(self.unexpected("a statement")),
+            },
+        }
+    }
+
+    /// `BEGIN`, `COMMIT` or `ROLLBACK`, optionally followed by `TRANSACTION` or `WORK`.
+    fn parse_transaction(&mut self) -> Result<Statement, ParseError> {
+        let token = self
+            .next_token()
+            .ok_or_else(|| self.unexpected("a statement"))?;
+        let kind = match token.kind {
+            TokenKind::Keyword(Keyword::Begin) => TransactionKind::Begin,
+            TokenKind::Keyword(Keyword::Commit) => TransactionKind::Commit,
+            TokenKind::Keyword(Keyword::Rollback) => TransactionKind::Rollback,
+            _ => unreachable!("checked by `parse_statement`"),
+        };
+        if self.consume_keyword(Keyword::Transaction).is_none() {
+            self.consume_keyword(Keyword::Work);
+        }
+        Ok(Statement::Transaction {
+            kind,
+            span: self.span_from(token.span),
+        })
+    }
+
+    pub fn parse_query(&mut self) -> Result<Query, ParseError> {
+        let start = self.current_span();
+        let with = match self.consume_keyword(Keyword::With) {
+            Some(_) => self.parse_comma_separated(Self::parse_common_table_expression)?,
+            None => Vec::new(),
+        };
+
+        let body = self.parse_set_expr(0)?;
+
+        let mut order_by = Vec::new();
+        if self.consume_keyword(Keyword::Order).is_some() {
+            order_by = self.parse_or_recover(
+                |parser| {
+                    parser.expect_keyword(Keyword::By, "`BY`")?;
+                    parser.parse_comma_separated(Self::parse_order_by_item)
+                },
+                |_| Vec::new(),
+            );
+        }
+
+        let offset = match self.consume_keyword(Keyword::Offset) {
+            Some(_) => self.parse_or_recover(|parser| parser.parse_limit().map(Some), |_| None),
+            None => None,
+        };
+
+        Ok(Query {
+            span: self.span_from(start),
+            with,
+            body,
+            order_by,
+            offset,
+        })
+    }
+
+    fn parse_common_table_expression(&mut self) -> Result<CommonTableExpression, ParseError> {
+        let name = self.parse_identifier()?;
+        let columns = if self.peek_kind() == Some(TokenKind::LeftParenthesis) {
+            self.parse_parenthesized_identifiers()?
+        } else {
+            Vec::new()
+        };
+        self.expect_keyword(Keyword::As, "`AS`")?;
+        self.expect(TokenKind::LeftParenthesis, "`(`")?;
+        let query = self.parse_query()?;
+        self.expect(TokenKind::RightParenthesis, "`)`")?;
+        Ok(CommonTableExpression {
+            span: self.span_from(name.span),
+            name,
+            columns,
+            query: Box::new(query),
+        })
+    }
+
+    /// Parses set operations whose binding power is at least `min_power`. `INTERSECT` binds
+    /// tighter than `UNION` and `EXCEPT`, which associate to the left.
+    fn parse_set_expr(&mut self, min_power: u8) -> Result<SetExpr, ParseError> {
+        let mut left = self.parse_set_operand()?;
+
+        loop {
+            let (op, power) = match self.peek_kind() {
+                Some(TokenKind::Keyword(Keyword::Union)) => (SetOperator::Union, 1),
+                Some(TokenKind::Keyword(Keyword::Except)) => (SetOperator::Except, 1),
+                Some(TokenKind::Keyword(Keyword::Intersect)) => (SetOperator::Intersect, 3),
+                _ => break,
+            };
+            if power < min_power {
+                break;
+            }
+            self.position += 1;
+
+            let all = if self.consume_keyword(Keyword::All).is_some() {
+                true
+            } else {
+                self.consume_keyword(Keyword::Distinct);
+                false
+            };
+            let right = self.parse_set_expr(power + 1)?;
+            left = SetExpr::SetOperation {
+                span: self.span_from(left.span()),
+                op,
+                all,
+                left: Box::new(left),
+                right: Box::new(right),
+            };
+        }
+
+        Ok(left)
+    }
+
+    /// A `SELECT` or a parenthesised query.
+    fn parse_set_operand(&mut self) -> Result<SetExpr, ParseError> {
+        if self.consume(TokenKind::LeftParenthesis).is_none() {
+            if !self.check_keyword(Keyword::Select) {
+                return Err(self.unexpected("`SELECT` or `(`"));
+            }
+            return Ok(SetExpr::Select(Box::new(self.parse_select()?)));
+        }
+
+        let start = self.tokens[self.position - 1].span;
+        let mut query = self.parse_query()?;
+        self.expect(TokenKind::RightParenthesis, "`)`")?;
+        // The query's span includes the parentheses around it
+        query.span = self.span_from(start);
+        Ok(SetExpr::Query(Box::new(query)))
+    }
+
+    pub fn expect_end(&self) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(_) => Err(self.unexpected("end of input")),
+            None => Ok(()),
+        }
+    }
+
+    /// Parses a `SELECT`, recovering from errors clause by clause. Only a missing `SELECT`
+    /// keyword is returned as an error.
+    fn parse_select(&mut self) -> Result<Select, ParseError> {
+        let start = self.expect_keyword(Keyword::Select, "`SELECT`")?.span;
+
+        let distinct = if self.consume_keyword(Keyword::Distinct).is_some() {
+            true
+        } else {
+            self.consume_keyword(Keyword::All);
+            false
+        };
+
+        // A bad count is reported without skipping ahead, as the select list after it is
+        // still worth parsing
+        let top = match self.consume_keyword(Keyword::Top) {
+            Some(_) => match self.parse_limit() {
+                Ok(limit) => Some(limit),
+                Err(error) => {
+                    self.errors.push(error);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let items = self.parse_or_recover(
+            |parser| parser.parse_comma_separated(Self::parse_select_item),
+            |span| vec![SelectItem::Error(span)],
+        );
+
+        // Without `FROM` the table list is skipped rather than reported a second time
+        let from_keyword = match self.expect_keyword(Keyword::From, "`FROM`") {
+            Ok(token) => Some(token),
+            Err(error) => {
+                self.recover(error);
+                self.consume_keyword(Keyword::From)
+            }
+        };
+        let from = match from_keyword {
+            Some(_) => self.parse_or_recover(
+                |parser| parser.parse_comma_separated(Self::parse_table_reference),
+                |span| vec![TableReference::Error(span)],
+            ),
+            None => Vec::new(),
+        };
+
+        let where_clause = self
+            .consume_keyword(Keyword::Where)
+            .map(|_| self.parse_or_recover(Self::parse_expression, Expr::Error));
+
+        let mut group_by = Vec::new();
+        if self.consume_keyword(Keyword::Group).is_some() {
+            group_by = self.parse_or_recover(
+                |parser| {
+                    parser.expect_keyword(Keyword::By, "`BY`")?;
+                    parser.parse_comma_separated(Self::parse_expression)
+                },
+                |span| vec![Expr::Error(span)],
+            );
+        }
+
+        let having = self
+            .consume_keyword(Keyword::Having)
+            .map(|_| self.parse_or_recover(Self::parse_expression, Expr::Error));
+
+        Ok(Select {
+            distinct,
+            top,
+            items,
+            from,
+            where_clause,
+            group_by,
+            having,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_limit(&mut self) -> Result<Limit, ParseError> {
+        let token = self.expect(TokenKind::IntegerLiteral, "an unsigned integer")?;
+        let value = token
+            .to_decimal(self.source)
+            .as_ref()
+            .and_then(Decimal::to_i64)
+            .and_then(|value| u64::try_from(value).ok())
+            .ok_or(ParseError {
+                kind: ParseErrorKind::IntegerOutOfRange,
+                span: token.span,
+            })?;
+        Ok(Limit {
+            value,
+            span: token.span,
+        })
+    }
+
+    fn parse_select_item(&mut self) -> Result<SelectItem, ParseError> {
+        if let Some(token) = self.consume(TokenKind::Asterisk) {
+            return Ok(SelectItem::Wildcard(token.span));
+        }
+
+        // `t.*` needs lookahead past the identifier chain
+        if self.is_identifier(0) {
+            let mut lookahead = 1;
+            while self.peek_kind_at(lookahead) == Some(TokenKind::Period)
+                && self.is_identifier(lookahead + 1)
+            {
+                lookahead += 2;
+            }
+            if self.peek_kind_at(lookahead) == Some(TokenKind::Period)
+                && self.peek_kind_at(lookahead + 1) == Some(TokenKind::Asterisk)
+            {
+                let name = self.parse_object_name()?;
+                self.expect(TokenKind::Period, "`.`")?;
+                self.expect(TokenKind::Asterisk, "`*`")?;
+                let span = self.span_from(name.span);
+                return Ok(SelectItem::QualifiedWildcard(name, span));
+            }
+        }
+
+        let start = self.current_span();
+        let expr = self.parse_expression()?;
+        let alias = self.parse_optional_alias()?;
+        Ok(SelectItem::Expression {
+            span: self.span_from(start),
+            expr,
+            alias,
+        })
+    }
+
+    fn parse_table_reference(&mut self) -> Result<TableReference, ParseError> {
+        let mut table = self.parse_table_primary()?;
+        loop {
+            let natural = self.consume_keyword(Keyword::Natural).is_some();
+            let kind = if !natural && self.consume_keyword(Keyword::Cross).is_some() {
+                JoinKind::Cross
+            } else if self.consume_keyword(Keyword::Inner).is_some() {
+                JoinKind::Inner
+            } else if self.consume_keyword(Keyword::Left).is_some() {
+                self.consume_keyword(Keyword::Outer);
+                JoinKind::Left
+            } else if self.consume_keyword(Keyword::Right).is_some() {
+                self.consume_keyword(Keyword::Outer);
+                JoinKind::Right
+            } else if self.consume_keyword(Keyword::Full).is_some() {
+                self.consume_keyword(Keyword::Outer);
+                JoinKind::Full
+            } else if natural || self.check_keyword(Keyword::Join) {
+                JoinKind::Inner
+            } else {
+                break;
+            };
+            self.expect_keyword(Keyword::Join, "`JOIN`")?;
+            let right = self.parse_table_primary()?;
+
+            let constraint = if natural {
+                if self.check_keyword(Keyword::On) || self.check_keyword(Keyword::Using) {
+                    return Err(ParseError {
+                        kind: ParseErrorKind::NaturalJoinWithCondition,
+                        span: self.current_span(),
+                    });
+                }
+                JoinConstraint::Natural
+            } else if kind == JoinKind::Cross {
+                JoinConstraint::None
+            } else if self.consume_keyword(Keyword::On).is_some() {
+                JoinConstraint::On(self.parse_expression()?)
+            } else if self.consume_keyword(Keyword::Using).is_some() {
+                JoinConstraint::Using(self.parse_parenthesized_identifiers()?)
+            } else {
+                return Err(self.unexpected("`ON` or `USING`"));
+            };
+
+            let span = self.span_from(table.span());
+            table = TableReference::Join(Box::new(Join {
+                left: table,
+                right,
+                kind,
+                constraint,
+                span,
+            }));
+        }
+        Ok(table)
+    }
+
+    /// A table name, a derived table or a parenthesised joined table.
+    fn parse_table_primary(&mut self) -> Result<TableReference, ParseError> {
+        if self.peek_kind() != Some(TokenKind::LeftParenthesis) {
+            let name = self.parse_object_name()?;
+            let alias = self.parse_optional_alias()?;
+            return Ok(TableReference::Table {
+                span: self.span_from(name.span),
+                name,
+                alias,
+            });
+        }
+
+        // Skip any further parentheses to tell `((SELECT ...))` from `((a JOIN b ...))`
+        let mut lookahead = 1;
+        while self.peek_kind_at(lookahead) == Some(TokenKind::LeftParenthesis) {
+            lookahead += 1;
+        }
+        let is_query = matches!(
+            self.peek_kind_at(lookahead),
+            Some(TokenKind::Keyword(Keyword::Select | Keyword::With))
+        );
+
+        let start = self.expect(TokenKind::LeftParenthesis, "`(`")?.span;
+        if !is_query {
+            return self.parse_parenthesised_join();
+        }
+        if self.peek_kind() != Some(TokenKind::LeftParenthesis) {
+            return self.parse_derived_table(start);
+        }
+
+        // `((SELECT ...) AS x JOIN ...)` and `((SELECT ...)) AS x` can't be told apart until
+        // after the inner `)`, so try a join first and fall back to a derived table. If both
+        // fail, the error that got further is reported.
+        let (position, error_count) = (self.position, self.errors.len());
+        let join_error = match self.parse_parenthesised_join() {
+            Ok(table) => return Ok(table),
+            Err(error) => error,
+        };
+        let join_position = self.position;
+        let join_errors = self.errors.split_off(error_count);
+        self.position = position;
+        match self.parse_derived_table(start) {
+            Err(error) if error.span.start < join_error.span.start => {
+                self.position = join_position;
+                self.errors.truncate(error_count);
+                self.errors.extend(join_errors);
+                Err(join_error)
+            }
+            result => result,
+        }
+    }
+
+    /// The rest of `( table_reference )` after the `(`.
+    fn parse_parenthesised_join(&mut self) -> Result<TableReference, ParseError> {
+        let table = self.parse_table_reference()?;
+        self.expect(TokenKind::RightParenthesis, "`)`")?;
+        Ok(table)
+    }
+
+    /// The rest of `( query ) alias` after the `(` at `start`.
+    fn parse_derived_table(&mut self, start: Span) -> Result<TableReference, ParseError> {
+        let query = self.parse_query()?;
+        self.expect(TokenKind::RightParenthesis, "`)`")?;
+        let Some(alias) = self.parse_optional_alias()? else {
+            return Err(self.unexpected("an alias for the derived table"));
+        };
+        Ok(TableReference::Derived {
+            query: Box::new(query),
+            alias,
+            span: self.span_from(start),
+        })
+    }
+
+    fn parse_optional_alias(&mut self) -> Result<Option<Ident>, ParseError> {
+        if self.consume_keyword(Keyword::As).is_some() {
+            return self.parse_identifier().map(Some);
+        }
+        if self.is_identifier(0) {
+            return self.parse_identifier().map(Some);
+        }
+        Ok(None)
+    }
+
+    fn parse_order_by_item(&mut self) -> Result<OrderByItem, ParseError> {
+        let start = self.current_span();
+        let expr = self.parse_expression()?;
+        let direction = if self.consume_keyword(Keyword::Asc).is_some() {
+            Some(SortDirection::Ascending)
+        } else if self.consume_keyword(Keyword::Desc).is_some() {
+            Some(SortDirection::Descending)
+        } else {
+            None
+        };
+        Ok(OrderByItem {
+            span: self.span_from(start),
+            expr,
+            direction,
+        })
+    }
+
+    fn parse_object_name(&mut self) -> Result<ObjectName, ParseError> {
+        let mut parts = vec![self.parse_identifier()?];
+        while self.peek_kind() == Some(TokenKind::Period) && self.is_identifier(1) {
+            self.position += 1;
+            parts.push(self.parse_identifier()?);
+        }
+        let span = parts[0].span.merge(parts[parts.len() - 1].span);
+        Ok(ObjectName { parts, span })
+    }
+
+    fn parse_identifier(&mut self) -> Result<Ident, ParseError> {
+        let Some(token) = self.peek().copied() else {
+            return Err(self.unexpected("an identifier"));
+        };
+        let ident = match token.kind {
+            TokenKind::Identifier => Ident {
+                value: self.text(&token).to_string(),
+                quoted: false,
+                span: token.span,
+            },
+            TokenKind::DelimitedIdentifier => Ident {
+                value: self.unescaped(&token),
+                quoted: true,
+                span: token.span,
+            },
+            _ => return Err(self.unexpected("an identifier")),
+        };
+        self.position += 1;
+        Ok(ident)
+    }
+
+    /// `(name, ...)`
+    fn parse_parenthesized_identifiers(&mut self) -> Result<Vec<Ident>, ParseError> {
+        self.expect(TokenKind::LeftParenthesis, "`(`")?;
+        let identifiers = self.parse_comma_separated(Self::parse_identifier)?;
+        self.expect(TokenKind::RightParenthesis, "`)`")?;
+        Ok(identifiers)
+    }
+
+    fn parse_comma_separated<T>(
+        &mut self,
+        mut parse: impl FnMut(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<Vec<T>, ParseError> {
+        let mut items = vec![parse(self)?];
+        while self.consume(TokenKind::Comma).is_some() {
+            items.push(parse(self)?);
+        }
+        Ok(items)
+    }
+
+    /// Runs `parse`, and on failure records the error, skips to the next clause and builds a
+    /// placeholder from the span of the skipped input with `recovered`.
+    fn parse_or_recover<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+        recovered: impl FnOnce(Span) -> T,
+    ) -> T {
+        match parse(self) {
+            Ok(value) => value,
+            Err(error) => {
+                let span = self.recover(error);
+                recovered(span)
+            }
+        }
+    }
+
+    /// Records `error` and skips to a `;`, a clause keyword or an unmatched `)`, returning the
+    /// span from the error to the last skipped token.
+    fn recover(&mut self, error: ParseError) -> Span {
+        let mut depth = 0usize;
+        let mut span = error.span;
+        self.errors.push(error);
+        while let Some(token) = self.peek().copied() {
+            let stop = match token.kind {
+                TokenKind::Semicolon => true,
+                TokenKind::LeftParenthesis => {
+                    depth += 1;
+                    false
+                }
+                TokenKind::RightParenthesis if depth == 0 => true,
+                TokenKind::RightParenthesis => {
+                    depth -= 1;
+                    false
+                }
+                TokenKind::Keyword(keyword) if depth == 0 => CLAUSE_KEYWORDS.contains(&keyword),
+                _ => false,
+            };
+            if stop {
+                break;
+            }
+            span = span.merge(token.span);
+            self.position += 1;
+        }
+        span
+    }
+
+    /// Whether a query starts at the cursor. A parenthesised query is not recognised, since
+    /// after a `(` it could equally be an expression.
+    fn is_query_start(&self) -> bool {
+        self.check_keyword(Keyword::Select) || self.check_keyword(Keyword::With)
+    }
+
+    fn is_identifier(&self, lookahead: usize) -> bool {
+        matches!(
+            self.peek_kind_at(lookahead),
+            Some(TokenKind::Identifier | TokenKind::DelimitedIdentifier)
+        )
+    }
+
+    fn check_keyword(&self, keyword: Keyword) -> bool {
+        self.peek_kind() == Some(TokenKind::Keyword(keyword))
+    }
+
+    fn consume_keyword(&mut self, keyword: Keyword) -> Option<Token> {
+        self.consume(TokenKind::Keyword(keyword))
+    }
+
+    fn expect_keyword(
+        &mut self,
+        keyword: Keyword,
+        expected: &'static str,
+    ) -> Result<Token, ParseError> {
+        self.expect(TokenKind::Keyword(keyword), expected)
+    }
+
+    fn consume(&mut self, kind: TokenKind) -> Option<Token> {
+        let token = *self.peek()?;
+        if token.kind != kind {
+            return None;
+        }
+        self.position += 1;
+        Some(token)
+    }
+
+    fn expect(&mut self, kind: TokenKind, expected: &'static str) -> Result<Token, ParseError> {
+        self.consume(kind).ok_or_else(|| self.unexpected(expected))
+    }
+
+    fn next_token(&mut self) -> Option<Token> {
+        let token = *self.peek()?;
+        self.position += 1;
+        Some(token)
+    }
+
+    fn unexpected(&self, expected: &'static str) -> ParseError {
+        // Past the end of a statement the `;` that ended it is what was found
+        match self.tokens.get(self.position) {
+            Some(token) => ParseError {
+                kind: ParseErrorKind::UnexpectedToken {
+                    expected,
+                    found: token.kind,
+                },
+                span: token.span,
+            },
+            None => ParseError {
+                kind: ParseErrorKind::UnexpectedEndOfInput { expected },
+                span: self.current_span(),
+            },
+        }
+    }
+
+    /// The span of the token at the cursor, or an empty span at the end of input.
+    fn current_span(&self) -> Span {
+        match self.tokens.get(self.position) {
+            Some(token) if self.position < self.end => token.span,
+            Some(token) => Span::new(token.span.start, 0),
+            None => Span::new(self.source.len(), 0),
+        }
+    }
+
+    /// The span from `start` up to the end of the most recently consumed token.
+    fn span_from(&self, start: Span) -> Span {
+        match self.position.checked_sub(1) {
+            Some(previous) => start.merge(self.tokens[previous].span),
+            None => start,
+        }
+    }
+
+    fn text(&self, token: &Token) -> &'a str {
+        token.text(self.source)
+    }
+
+    fn unescaped(&self, token: &Token) -> String {
+        token
+            .unescaped(self.source)
+            .unwrap_or_default()
+            .into_owned()
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens[..self.end].get(self.position)
+    }
+
+    fn peek_kind(&self) -> Option<TokenKind> {
+        self.peek_kind_at(0)
+    }
+
+    fn peek_kind_at(&self, lookahead: usize) -> Option<TokenKind> {
+        self.tokens[..self.end]
+            .get(self.position + lookahead)
+            .map(|token| token.kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        parse_query, JoinConstraint, Query, Select, SelectItem, SetExpr, SortDirection,
+        TableReference,
+    };
+
+    /// The `FROM` list of `SELECT * FROM {from}` with joins bracketed, derived tables as
+    /// `(query) alias` and each join's constraint after the right side.
+    fn from(from: &str) -> String {
+        let query = parse_query(&format!("SELECT * FROM {from}")).unwrap();
+        let SetExpr::Select(select) = &query.body else {
+            panic!("expected a SELECT");
+        };
+        let tables: Vec<String> = select.from.iter().map(shape).collect();
+        tables.join(", ")
+    }
+
+    fn shape(table: &TableReference) -> String {
+        match table {
+            TableReference::Table { name, .. } => name.to_string(),
+            TableReference::Derived { query, alias, .. } => format!("({query}) {alias}"),
+            TableReference::Join(join) => {
+                let constraint = match &join.constraint {
+                    JoinConstraint::On(condition) => format!(" ON {condition}"),
+                    JoinConstraint::Using(columns) => format!(" USING {}", columns.len()),
+                    JoinConstraint::Natural => " NATURAL".to_string(),
+                    JoinConstraint::None => String::new(),
+                };
+                let (left, right) = (shape(&join.left), shape(&join.right));
+                format!("[{left} {:?} {right}{constraint}]", join.kind)
+            }
+            TableReference::Error(_) => "<error>".to_string(),
+        }
+    }
+
+    #[test]
+    fn joins_are_left_associative() {
+        assert_eq!(
+            from("a JOIN b ON a.x = b.x LEFT JOIN c USING (x) CROSS JOIN d"),
+            "[[[a Inner b ON a.x = b.x] Left c USING 1] Cross d]"
+        );
+        assert_eq!(
+            from("a NATURAL JOIN b NATURAL FULL OUTER JOIN c"),
+            "[[a Inner b NATURAL] Full c NATURAL]"
+        );
+    }
+
+    #[test]
+    fn parentheses_group_joins() {
+        assert_eq!(
+            from("a JOIN (b JOIN c USING (x, y)) ON a.x = b.x"),
+            "[a Inner [b Inner c USING 2] ON a.x = b.x]"
+        );
+        assert_eq!(
+            from("(a JOIN b ON a.x = b.x) NATURAL JOIN c"),
+            "[[a Inner b ON a.x = b.x] Inner c NATURAL]"
+        );
+        assert_eq!(
+            from("((a NATURAL JOIN b)), ((c))"),
+            "[a Inner b NATURAL], c"
+        );
+    }
+
+    #[test]
+    fn derived_tables_join_on_either_side() {
+        assert_eq!(
+            from("(SELECT a FROM t) AS x JOIN u ON x.a = u.a"),
+            "[(SELECT a FROM t) x Inner u ON x.a = u.a]"
+        );
+        assert_eq!(
+            from("u RIGHT JOIN (SELECT a FROM t) x USING (a)"),
+            "[u Right (SELECT a FROM t) x USING 1]"
+        );
+        assert_eq!(
+            from("(SELECT a FROM t) x NATURAL JOIN (SELECT a FROM u) y"),
+            "[(SELECT a FROM t) x Inner (SELECT a FROM u) y NATURAL]"
+        );
+    }
+
+    #[test]
+    fn a_parenthesised_join_can_start_with_a_derived_table() {
+        assert_eq!(
+            from("((SELECT a FROM t) AS x JOIN u ON x.a = u.a)"),
+            "[(SELECT a FROM t) x Inner u ON x.a = u.a]"
+        );
+        assert_eq!(
+            from("(((SELECT a FROM t) x JOIN (SELECT a FROM u) y USING (a))) NATURAL JOIN v"),
+            "[[(SELECT a FROM t) x Inner (SELECT a FROM u) y USING 1] Inner v NATURAL]"
+        );
+    }
+
+    #[test]
+    fn a_derived_table_can_be_a_parenthesised_query() {
+        assert_eq!(from("((SELECT a FROM t)) AS x"), "((SELECT a FROM t)) x");
+        assert_eq!(
+            from("((SELECT a FROM t) UNION (SELECT a FROM u)) x JOIN v USING (a)"),
+            "[((SELECT a FROM t) UNION (SELECT a FROM u)) x Inner v USING 1]"
+        );
+    }
+
+    #[test]
+    fn a_failed_parenthesised_join_reports_where_it_failed() {
+        let error = parse_query("SELECT * FROM ((SELECT a FROM t) AS x JOIN u ON)").unwrap_err();
+        assert_eq!(error.to_string(), "expected an expression, found `)`");
+        assert_eq!(error.span.start, 47);
+
+        let error = parse_query("SELECT * FROM ((SELECT a FROM t)) JOIN u").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "expected an alias for the derived table, found keyword `JOIN`"
+        );
+    }
+
+    fn body(query: &Query) -> &Select {
+        let SetExpr::Select(select) = &query.body else {
+            panic!("expected a SELECT");
+        };
+        select
+    }
+
+    #[test]
+    fn select_keeps_every_clause() {
+        let source = "SELECT DISTINCT TOP 10 ra AS r, t.*, dec, COUNT(*) \"N\" \
+                      FROM gaia.source AS t WHERE dec > 0 GROUP BY ra, dec HAVING COUNT(*) > 1 \
+                      ORDER BY r DESC, dec ASC, ra OFFSET 5";
+        let query = parse_query(source).unwrap();
+        let select = body(&query);
+        assert!(select.distinct);
+        let top = select.top.as_ref().unwrap();
+        assert_eq!((top.value, &source[top.span.range()]), (10, "10"));
+
+        let items: Vec<(String, Option<&str>, &str)> = select
+            .items
+            .iter()
+            .map(|item| match item {
+                SelectItem::Expression { expr, alias, span } => {
+                    let alias = alias.as_ref().map(|alias| &source[alias.span.range()]);
+                    (expr.to_string(), alias, &source[span.range()])
+                }
+                SelectItem::QualifiedWildcard(name, span) => {
+                    (format!("{name}.*"), None, &source[span.range()])
+                }
+                item => panic!("{item:?}"),
+            })
+            .collect();
+        assert_eq!(
+            items,
+            [
+                ("ra".to_string(), Some("r"), "ra AS r"),
+                ("t.*".to_string(), None, "t.*"),
+                ("dec".to_string(), None, "dec"),
+                ("COUNT(*)".to_string(), Some("\"N\""), "COUNT(*) \"N\""),
+            ]
+        );
+
+        let [TableReference::Table { name, alias, span }] = &select.from[..] else {
+            panic!("{:?}", select.from);
+        };
+        assert_eq!(name.to_string(), "gaia.source");
+        assert_eq!(alias.as_ref().unwrap().value, "t");
+        assert_eq!(&source[span.range()], "gaia.source AS t");
+
+        assert_eq!(select.where_clause.as_ref().unwrap().to_string(), "dec > 0");
+        let group_by: Vec<String> = select.group_by.iter().map(|e| e.to_string()).collect();
+        assert_eq!(group_by, ["ra", "dec"]);
+        assert_eq!(select.having.as_ref().unwrap().to_string(), "COUNT(*) > 1");
+        // The select ends at its last clause, before the query's ORDER BY
+        assert_eq!(
+            &source[select.span.range()],
+            &source[..source.find(" ORDER BY").unwrap()]
+        );
+
+        let order_by: Vec<(String, Option<SortDirection>)> = query
+            .order_by
+            .iter()
+            .map(|item| (item.expr.to_string(), item.direction))
+            .collect();
+        assert_eq!(
+            order_by,
+            [
+                ("r".to_string(), Some(SortDirection::Descending)),
+                ("dec".to_string(), Some(SortDirection::Ascending)),
+                ("ra".to_string(), None),
+            ]
+        );
+        assert_eq!(&source[query.order_by[0].span.range()], "r DESC");
+        let offset = query.offset.as_ref().unwrap();
+        assert_eq!((offset.value, &source[offset.span.range()]), (5, "5"));
+        assert_eq!(query.span.range(), 0..source.len());
+    }
+
+    #[test]
+    fn omitted_clauses_are_empty() {
+        let source = "SELECT ALL * FROM t -- trailing comment";
+        let query = parse_query(source).unwrap();
+        let select = body(&query);
+        assert!(!select.distinct);
+        assert_eq!(select.top, None);
+        assert!(matches!(select.items[..], [SelectItem::Wildcard(_)]));
+        assert_eq!(select.where_clause, None);
+        assert!(select.group_by.is_empty());
+        assert_eq!(select.having, None);
+        assert!(query.order_by.is_empty());
+        assert_eq!(query.offset, None);
+        // Trailing comments are not part of any node
+        assert_eq!(&source[select.span.range()], "SELECT ALL * FROM t");
+        assert_eq!(&source[query.span.range()], "SELECT ALL * FROM t");
+
+        let source = "SELECT a FROM t WHERE a = 1";
+        let query = parse_query(source).unwrap();
+        assert_eq!(body(&query).span.range(), 0..source.len());
+    }
+}
@@ -0,0 +1,289 @@
+use std::fmt;
+
+use crate::lexer::{Keyword, Span, TokenKind};
+
+use super::{ParseError, Parser, Statement};
+
+/// The broad category of a statement, decided by its first keyword.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StatementKind {
+    /// `SELECT`, `WITH` or a parenthesised query
+    Query,
+    /// `CREATE`, `DROP`, `ALTER` and `TRUNCATE`
+    Ddl,
+    /// `INSERT`, `UPDATE` and `DELETE`
+    Dml,
+    /// `BEGIN`, `COMMIT` and `ROLLBACK`
+    Transaction,
+}
+
+impl StatementKind {
+    /// The kind of statement starting with a token of the given kind, if it starts one.
+    pub fn classify(kind: TokenKind) -> Option<StatementKind> {
+        let keyword = match kind {
+            TokenKind::Keyword(keyword) => keyword,
+            TokenKind::LeftParenthesis => return Some(StatementKind::Query),
+            _ => return None,
+        };
+        match keyword {
+            Keyword::Select | Keyword::With => Some(StatementKind::Query),
+            Keyword::Create | Keyword::Drop | Keyword::Alter | Keyword::Truncate => {
+                Some(StatementKind::Ddl)
+            }
+            Keyword::Insert | Keyword::Update | Keyword::Delete => Some(StatementKind::Dml),
+            Keyword::Begin | Keyword::Commit | Keyword::Rollback => {
+                Some(StatementKind::Transaction)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for StatementKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StatementKind::Query => write!(f, "query"),
+            StatementKind::Ddl => write!(f, "DDL"),
+            StatementKind::Dml => write!(f, "DML"),
+            StatementKind::Transaction => write!(f, "transaction control"),
+        }
+    }
+}
+
+/// One statement of a script.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScriptStatement {
+    /// `None` if the statement does not start with a statement keyword.
+    pub kind: Option<StatementKind>,
+    /// The statement text, excluding the terminating `;`.
+    pub span: Span,
+    /// The parsed statement, which is [`Statement::Error`] if it failed to parse.
+    pub statement: Statement,
+}
+
+/// A source split into statements on `;`.
+///
+/// Splitting happens on tokens, so semicolons inside strings, delimited identifiers and
+/// comments do not end a statement. Each statement is parsed on its own, so an error in one
+/// does not affect the next.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Script {
+    /// The statements, in source order. Empty statements between `;`s are left out.
+    pub statements: Vec<ScriptStatement>,
+    /// Every lex and parse error in the script, in source order.
+    pub errors: Vec<ParseError>,
+}
+
+impl Script {
+    /// Splits `source` into statements and parses each of them.
+    pub fn parse(source: &str) -> Script {
+        let mut parser = Parser::new(source);
+        let statements = parser.parse_script();
+        Script {
+            statements,
+            errors: parser.into_errors(),
+        }
+    }
+}
+
+impl Parser<'_> {
+    fn parse_script(&mut self) -> Vec<ScriptStatement> {
+        let mut statements = Vec::new();
+        while self.position < self.tokens.len() {
+            let end = self.tokens[self.position..]
+                .iter()
+                .position(|token| token.kind == TokenKind::Semicolon)
+                .map_or(self.tokens.len(), |length| self.position + length);
+
+            // Empty statements such as `;;` are skipped
+            if end > self.position {
+                self.end = end;
+                statements.push(self.parse_script_statement());
+            }
+
+            self.end = self.tokens.len();
+            self.position = end + 1;
+        }
+        statements
+    }
+
+    fn parse_script_statement(&mut self) -> ScriptStatement {
+        let span = self.tokens[self.position]
+            .span
+            .merge(self.tokens[self.end - 1].span);
+        let kind = self.peek_kind().and_then(StatementKind::classify);
+
+        let statement = match self.parse_statement() {
+            Ok(statement) => {
+                if self.peek().is_some() {
+                    let error = self.unexpected("`;`");
+                    self.errors.push(error);
+                }
+                statement
+            }
+            Err(error) => {
+                self.errors.push(error);
+                Statement::Error(span)
+            }
+        };
+
+        ScriptStatement {
+            kind,
+            span,
+            statement,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Script, StatementKind};
+
+    /// Each error of `source` with the text it points at.
+    fn errors(source: &str) -> Vec<(String, &str)> {
+        Script::parse(source)
+            .errors
+            .iter()
+            .map(|error| (error.to_string(), &source[error.span.range()]))
+            .collect()
+    }
+
+    /// Each statement of `source` printed back, with `<error>` for the parts that failed.
+    fn statements(source: &str) -> Vec<String> {
+        Script::parse(source)
+            .statements
+            .iter()
+            .map(|statement| statement.statement.to_string())
+            .collect()
+    }
+
+    fn error(message: &str, text: &'static str) -> (String, &'static str) {
+        (message.to_string(), text)
+    }
+
+    #[test]
+    fn every_statement_reports_its_own_errors() {
+        let source = "SELECT a FROM t WHERE; SELECT b c d FROM u; \
+                      SELECT 1 + FROM v WHERE x = ; SELECT ok FROM fine";
+        assert_eq!(
+            errors(source),
+            [
+                error("expected an expression, found `;`", ";"),
+                error("expected `FROM`, found identifier", "d"),
+                error("expected an expression, found keyword `FROM`", "FROM"),
+                error("expected an expression, found `;`", ";"),
+            ]
+        );
+        assert_eq!(
+            statements(source),
+            [
+                "SELECT a FROM t WHERE <error>",
+                "SELECT b AS c FROM u",
+                "SELECT <error> FROM v WHERE <error>",
+                "SELECT ok FROM fine",
+            ]
+        );
+    }
+
+    #[test]
+    fn recovery_resumes_at_the_next_clause() {
+        let source = "SELECT a, FROM t WHERE b = 1 GROUP BY ORDER BY c";
+        assert_eq!(
+            errors(source),
+            [
+                error("expected an expression, found keyword `FROM`", "FROM"),
+                error("expected an expression, found keyword `ORDER`", "ORDER"),
+            ]
+        );
+        assert_eq!(
+            statements(source),
+            ["SELECT <error> FROM t WHERE b = 1 GROUP BY <error> ORDER BY c"]
+        );
+    }
+
+    #[test]
+    fn a_bad_top_count_keeps_the_select_list() {
+        let source = "SELECT TOP 99999999999999999999 a, b FROM t WHERE";
+        assert_eq!(
+            errors(source),
+            [
+                error("integer is out of range", "99999999999999999999"),
+                error("expected an expression, found end of input", ""),
+            ]
+        );
+        assert_eq!(statements(source), ["SELECT a, b FROM t WHERE <error>"]);
+
+        let source = "SELECT TOP x a FROM t";
+        assert_eq!(
+            errors(source),
+            [error("expected an unsigned integer, found identifier", "x")]
+        );
+        assert_eq!(statements(source), ["SELECT x AS a FROM t"]);
+    }
+
+    #[test]
+    fn lex_errors_are_reported_with_parse_errors() {
+        let source = "SELECT a ! b FROM u; SELECT c d e FROM v";
+        assert_eq!(
+            errors(source),
+            [
+                error("invalid character `!`", "!"),
+                error("expected `FROM`, found identifier", "e"),
+            ]
+        );
+        assert_eq!(
+            statements(source),
+            ["SELECT a AS b FROM u", "SELECT c AS d FROM v"]
+        );
+
+        // An unterminated string runs to the end, taking the `;`s with it
+        let source = "SELECT 'open FROM t; SELECT c FROM v";
+        assert_eq!(
+            errors(source),
+            [error(
+                "unterminated string literal",
+                "'open FROM t; SELECT c FROM v"
+            )]
+        );
+        assert_eq!(Script::parse(source).statements.len(), 1);
+    }
+
+    #[test]
+    fn statements_are_classified_by_their_first_keyword() {
+        let script = Script::parse(
+            "SELECT 1 FROM t; (SELECT 2 FROM t); CREATE TABLE t (a INTEGER); DELETE FROM t; \
+             COMMIT; nonsense",
+        );
+        let kinds: Vec<_> = script
+            .statements
+            .iter()
+            .map(|statement| statement.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                Some(StatementKind::Query),
+                Some(StatementKind::Query),
+                Some(StatementKind::Ddl),
+                Some(StatementKind::Dml),
+                Some(StatementKind::Transaction),
+                None,
+            ]
+        );
+        assert_eq!(script.errors.len(), 1);
+    }
+
+    #[test]
+    fn empty_delimited_identifiers_are_rejected() {
+        assert_eq!(
+            errors("SELECT a AS \"\" FROM t"),
+            [error("empty delimited identifier", "\"\"")]
+        );
+        assert_eq!(
+            statements("SELECT a AS \"\" FROM t"),
+            ["SELECT a AS \"\" FROM t"]
+        );
+    }
+}
@@ -0,0 +1,634 @@
+//! Read-only traversal of the AST.
+//!
+//! Each `visit_*` method defaults to the matching `walk_*` function, which visits the
+//! children of the node. An implementation overrides the methods for the nodes it cares
+//! about and calls `walk_*` from them to keep descending:
+//!
+//! ```
+//! use daoql::parser::visit::{walk_expr, Visitor};
+//! use daoql::parser::{Expr, ObjectName};
+//! use daoql::Script;
+//!
+//! struct Columns<'ast>(Vec<&'ast ObjectName>);
+//!
+//! impl<'ast> Visitor<'ast> for Columns<'ast> {
+//!     fn visit_expr(&mut self, expr: &'ast Expr) {
+//!         if let Expr::Column(name) = expr {
+//!             self.0.push(name);
+//!         }
+//!         walk_expr(self, expr);
+//!     }
+//! }
+//!
+//! let script = Script::parse("SELECT ra, dec FROM gaia.source WHERE parallax > 1");
+//! let mut columns = Columns(Vec::new());
+//! columns.visit_statement(&script.statements[0].statement);
+//! let names: Vec<_> = columns.0.iter().map(|name| name.to_string()).collect();
+//! assert_eq!(names, ["ra", "dec", "parallax"]);
+//! ```
+
+use super::{
+    Assignment, ColumnConstraint, ColumnConstraintKind, ColumnDefinition, CommonTableExpression,
+    CreateTable, DataType, Delete, DropTable, Expr, ForeignKeyTarget, FunctionArguments,
+    FunctionCall, Ident, Insert, InsertSource, Join, JoinConstraint, Limit, Literal, ObjectName,
+    OrderByItem, Query, Select, SelectItem, SetExpr, Statement, TableConstraint,
+    TableConstraintKind, TableReference, Update, WhenClause,
+};
+
+/// A read-only visitor over an AST borrowed for `'ast`.
+pub trait Visitor<'ast> {
+    /// Called for every [`Statement`]. Defaults to [`walk_statement`].
+    fn visit_statement(&mut self, statement: &'ast Statement) {
+        walk_statement(self, statement);
+    }
+
+    /// Called for every [`CreateTable`]. Defaults to [`walk_create_table`].
+    fn visit_create_table(&mut self, create: &'ast CreateTable) {
+        walk_create_table(self, create);
+    }
+
+    /// Called for every [`ColumnDefinition`]. Defaults to [`walk_column_definition`].
+    fn visit_column_definition(&mut self, column: &'ast ColumnDefinition) {
+        walk_column_definition(self, column);
+    }
+
+    /// Called for every [`DataType`]. Defaults to [`walk_data_type`].
+    fn visit_data_type(&mut self, data_type: &'ast DataType) {
+        walk_data_type(self, data_type);
+    }
+
+    /// Called for every [`ColumnConstraint`]. Defaults to [`walk_column_constraint`].
+    fn visit_column_constraint(&mut self, constraint: &'ast ColumnConstraint) {
+        walk_column_constraint(self, constraint);
+    }
+
+    /// Called for every [`TableConstraint`]. Defaults to [`walk_table_constraint`].
+    fn visit_table_constraint(&mut self, constraint: &'ast TableConstraint) {
+        walk_table_constraint(self, constraint);
+    }
+
+    /// Called for every [`ForeignKeyTarget`]. Defaults to [`walk_foreign_key_target`].
+    fn visit_foreign_key_target(&mut self, target: &'ast ForeignKeyTarget) {
+        walk_foreign_key_target(self, target);
+    }
+
+    /// Called for every [`DropTable`]. Defaults to [`walk_drop_table`].
+    fn visit_drop_table(&mut self, drop: &'ast DropTable) {
+        walk_drop_table(self, drop);
+    }
+
+    /// Called for every [`Insert`]. Defaults to [`walk_insert`].
+    fn visit_insert(&mut self, insert: &'ast Insert) {
+        walk_insert(self, insert);
+    }
+
+    /// Called for every [`Update`]. Defaults to [`walk_update`].
+    fn visit_update(&mut self, update: &'ast Update) {
+        walk_update(self, update);
+    }
+
+    /// Called for every [`Assignment`]. Defaults to [`walk_assignment`].
+    fn visit_assignment(&mut self, assignment: &'ast Assignment) {
+        walk_assignment(self, assignment);
+    }
+
+    /// Called for every [`Delete`]. Defaults to [`walk_delete`].
+    fn visit_delete(&mut self, delete: &'ast Delete) {
+        walk_delete(self, delete);
+    }
+
+    /// Called for every [`Query`]. Defaults to [`walk_query`].
+    fn visit_query(&mut self, query: &'ast Query) {
+        walk_query(self, query);
+    }
+
+    /// Called for every [`CommonTableExpression`]. Defaults to [`walk_common_table_expression`].
+    fn visit_common_table_expression(&mut self, cte: &'ast CommonTableExpression) {
+        walk_common_table_expression(self, cte);
+    }
+
+    /// Called for every [`SetExpr`]. Defaults to [`walk_set_expr`].
+    fn visit_set_expr(&mut self, set_expr: &'ast SetExpr) {
+        walk_set_expr(self, set_expr);
+    }
+
+    /// Called for every [`Select`]. Defaults to [`walk_select`].
+    fn visit_select(&mut self, select: &'ast Select) {
+        walk_select(self, select);
+    }
+
+    /// Called for every [`Limit`], which has no children to walk.
+    fn visit_limit(&mut self, _limit: &'ast Limit) {}
+
+    /// Called for every [`SelectItem`]. Defaults to [`walk_select_item`].
+    fn visit_select_item(&mut self, item: &'ast SelectItem) {
+        walk_select_item(self, item);
+    }
+
+    /// Called for every [`TableReference`]. Defaults to [`walk_table_reference`].
+    fn visit_table_reference(&mut self, table: &'ast TableReference) {
+        walk_table_reference(self, table);
+    }
+
+    /// Called for every [`Join`]. Defaults to [`walk_join`].
+    fn visit_join(&mut self, join: &'ast Join) {
+        walk_join(self, join);
+    }
+
+    /// Called for every [`JoinConstraint`]. Defaults to [`walk_join_constraint`].
+    fn visit_join_constraint(&mut self, constraint: &'ast JoinConstraint) {
+        walk_join_constraint(self, constraint);
+    }
+
+    /// Called for every [`OrderByItem`]. Defaults to [`walk_order_by_item`].
+    fn visit_order_by_item(&mut self, item: &'ast OrderByItem) {
+        walk_order_by_item(self, item);
+    }
+
+    /// Called for every [`Expr`]. Defaults to [`walk_expr`].
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        walk_expr(self, expr);
+    }
+
+    /// Called for every [`WhenClause`]. Defaults to [`walk_when_clause`].
+    fn visit_when_clause(&mut self, when: &'ast WhenClause) {
+        walk_when_clause(self, when);
+    }
+
+    /// Called for every [`FunctionCall`]. Defaults to [`walk_function_call`].
+    fn visit_function_call(&mut self, call: &'ast FunctionCall) {
+        walk_function_call(self, call);
+    }
+
+    /// Called for every [`Literal`], which has no children to walk.
+    fn visit_literal(&mut self, _literal: &'ast Literal) {}
+
+    /// Called for every [`ObjectName`]. Defaults to [`walk_object_name`].
+    fn visit_object_name(&mut self, name: &'ast ObjectName) {
+        walk_object_name(self, name);
+    }
+
+    /// Called for every [`Ident`], which has no children to walk.
+    fn visit_ident(&mut self, _ident: &'ast Ident) {}
+}
+
+/// Visits the children of a [`Statement`].
+pub fn walk_statement<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    statement: &'ast Statement,
+) {
+    match statement {
+        Statement::Query(query) => visitor.visit_query(query),
+        Statement::CreateTable(create) => visitor.visit_create_table(create),
+        Statement::DropTable(drop) => visitor.visit_drop_table(drop),
+        Statement::Insert(insert) => visitor.visit_insert(insert),
+        Statement::Update(update) => visitor.visit_update(update),
+        Statement::Delete(delete) => visitor.visit_delete(delete),
+        Statement::Transaction { .. } | Statement::Error(_) => {}
+    }
+}
+
+/// Visits the children of a [`CreateTable`].
+pub fn walk_create_table<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    create: &'ast CreateTable,
+) {
+    visitor.visit_object_name(&create.name);
+    for column in &create.columns {
+        visitor.visit_column_definition(column);
+    }
+    for constraint in &create.constraints {
+        visitor.visit_table_constraint(constraint);
+    }
+}
+
+/// Visits the children of a [`ColumnDefinition`].
+pub fn walk_column_definition<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    column: &'ast ColumnDefinition,
+) {
+    visitor.visit_ident(&column.name);
+    visitor.visit_data_type(&column.data_type);
+    for constraint in &column.constraints {
+        visitor.visit_column_constraint(constraint);
+    }
+}
+
+/// Visits the children of a [`DataType`].
+pub fn walk_data_type<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, data_type: &'ast DataType) {
+    if let DataType::Custom(name) = data_type {
+        visitor.visit_ident(name);
+    }
+}
+
+/// Visits the children of a [`ColumnConstraint`].
+pub fn walk_column_constraint<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    constraint: &'ast ColumnConstraint,
+) {
+    if let Some(name) = &constraint.name {
+        visitor.visit_ident(name);
+    }
+    match &constraint.kind {
+        ColumnConstraintKind::NotNull
+        | ColumnConstraintKind::Null
+        | ColumnConstraintKind::PrimaryKey
+        | ColumnConstraintKind::Unique => {}
+        ColumnConstraintKind::Default(expr) | ColumnConstraintKind::Check(expr) => {
+            visitor.visit_expr(expr);
+        }
+        ColumnConstraintKind::References(target) => visitor.visit_foreign_key_target(target),
+    }
+}
+
+/// Visits the children of a [`TableConstraint`].
+pub fn walk_table_constraint<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    constraint: &'ast TableConstraint,
+) {
+    if let Some(name) = &constraint.name {
+        visitor.visit_ident(name);
+    }
+    match &constraint.kind {
+        TableConstraintKind::PrimaryKey(columns) | TableConstraintKind::Unique(columns) => {
+            for column in columns {
+                visitor.visit_ident(column);
+            }
+        }
+        TableConstraintKind::ForeignKey { columns, target } => {
+            for column in columns {
+                visitor.visit_ident(column);
+            }
+            visitor.visit_foreign_key_target(target);
+        }
+        TableConstraintKind::Check(expr) => visitor.visit_expr(expr),
+    }
+}
+
+/// Visits the children of a [`ForeignKeyTarget`].
+pub fn walk_foreign_key_target<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    target: &'ast ForeignKeyTarget,
+) {
+    visitor.visit_object_name(&target.table);
+    for column in &target.columns {
+        visitor.visit_ident(column);
+    }
+}
+
+/// Visits the children of a [`DropTable`].
+pub fn walk_drop_table<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, drop: &'ast DropTable) {
+    for name in &drop.names {
+        visitor.visit_object_name(name);
+    }
+}
+
+/// Visits the children of an [`Insert`].
+pub fn walk_insert<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, insert: &'ast Insert) {
+    visitor.visit_object_name(&insert.table);
+    for column in &insert.columns {
+        visitor.visit_ident(column);
+    }
+    match &insert.source {
+        InsertSource::Values(rows) => {
+            for value in rows.iter().flatten() {
+                visitor.visit_expr(value);
+            }
+        }
+        InsertSource::Query(query) => visitor.visit_query(query),
+    }
+}
+
+/// Visits the children of an [`Update`].
+pub fn walk_update<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, update: &'ast Update) {
+    visitor.visit_object_name(&update.table);
+    for assignment in &update.assignments {
+        visitor.visit_assignment(assignment);
+    }
+    if let Some(condition) = &update.where_clause {
+        visitor.visit_expr(condition);
+    }
+}
+
+/// Visits the children of an [`Assignment`].
+pub fn walk_assignment<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    assignment: &'ast Assignment,
+) {
+    visitor.visit_ident(&assignment.column);
+    visitor.visit_expr(&assignment.value);
+}
+
+/// Visits the children of a [`Delete`].
+pub fn walk_delete<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, delete: &'ast Delete) {
+    visitor.visit_object_name(&delete.table);
+    if let Some(condition) = &delete.where_clause {
+        visitor.visit_expr(condition);
+    }
+}
+
+/// Visits the children of a [`Query`].
+pub fn walk_query<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, query: &'ast Query) {
+    for cte in &query.with {
+        visitor.visit_common_table_expression(cte);
+    }
+    visitor.visit_set_expr(&query.body);
+    for item in &query.order_by {
+        visitor.visit_order_by_item(item);
+    }
+    if let Some(offset) = &query.offset {
+        visitor.visit_limit(offset);
+    }
+}
+
+/// Visits the children of a [`CommonTableExpression`].
+pub fn walk_common_table_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    cte: &'ast CommonTableExpression,
+) {
+    visitor.visit_ident(&cte.name);
+    for column in &cte.columns {
+        visitor.visit_ident(column);
+    }
+    visitor.visit_query(&cte.query);
+}
+
+/// Visits the children of a [`SetExpr`].
+pub fn walk_set_expr<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, set_expr: &'ast SetExpr) {
+    match set_expr {
+        SetExpr::Select(select) => visitor.visit_select(select),
+        SetExpr::Query(query) => visitor.visit_query(query),
+        SetExpr::SetOperation { left, right, .. } => {
+            visitor.visit_set_expr(left);
+            visitor.visit_set_expr(right);
+        }
+    }
+}
+
+/// Visits the children of a [`Select`].
+pub fn walk_select<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, select: &'ast Select) {
+    if let Some(top) = &select.top {
+        visitor.visit_limit(top);
+    }
+    for item in &select.items {
+        visitor.visit_select_item(item);
+    }
+    for table in &select.from {
+        visitor.visit_table_reference(table);
+    }
+    if let Some(condition) = &select.where_clause {
+        visitor.visit_expr(condition);
+    }
+    for expr in &select.group_by {
+        visitor.visit_expr(expr);
+    }
+    if let Some(condition) = &select.having {
+        visitor.visit_expr(condition);
+    }
+}
+
+/// Visits the children of a [`SelectItem`].
+pub fn walk_select_item<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, item: &'ast SelectItem) {
+    match item {
+        SelectItem::Wildcard(_) | SelectItem::Error(_) => {}
+        SelectItem::QualifiedWildcard(name, _) => visitor.visit_object_name(name),
+        SelectItem::Expression { expr, alias, .. } => {
+            visitor.visit_expr(expr);
+            if let Some(alias) = alias {
+                visitor.visit_ident(alias);
+            }
+        }
+    }
+}
+
+/// Visits the children of a [`TableReference`].
+pub fn walk_table_reference<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    table: &'ast TableReference,
+) {
+    match table {
+        TableReference::Table { name, alias, .. } => {
+            visitor.visit_object_name(name);
+            if let Some(alias) = alias {
+                visitor.visit_ident(alias);
+            }
+        }
+        TableReference::Derived { query, alias, .. } => {
+            visitor.visit_query(query);
+            visitor.visit_ident(alias);
+        }
+        TableReference::Join(join) => visitor.visit_join(join),
+        TableReference::Error(_) => {}
+    }
+}
+
+/// Visits the children of a [`Join`].
+pub fn walk_join<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, join: &'ast Join) {
+    visitor.visit_table_reference(&join.left);
+    visitor.visit_table_reference(&join.right);
+    visitor.visit_join_constraint(&join.constraint);
+}
+
+/// Visits the children of a [`JoinConstraint`].
+pub fn walk_join_constraint<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    constraint: &'ast JoinConstraint,
+) {
+    match constraint {
+        JoinConstraint::On(condition) => visitor.visit_expr(condition),
+        JoinConstraint::Using(columns) => {
+            for column in columns {
+                visitor.visit_ident(column);
+            }
+        }
+        JoinConstraint::Natural | JoinConstraint::None => {}
+    }
+}
+
+/// Visits the children of an [`OrderByItem`].
+pub fn walk_order_by_item<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    item: &'ast OrderByItem,
+) {
+    visitor.visit_expr(&item.expr);
+}
+
+/// Visits the children of an [`Expr`].
+pub fn walk_expr<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, expr: &'ast Expr) {
+    match expr {
+        Expr::Column(name) => visitor.visit_object_name(name),
+        Expr::Literal(literal) => visitor.visit_literal(literal),
+        Expr::Unary { operand, .. } => visitor.visit_expr(operand),
+        Expr::Binary { left, right, .. } => {
+            visitor.visit_expr(left);
+            visitor.visit_expr(right);
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            visitor.visit_expr(expr);
+            visitor.visit_expr(low);
+            visitor.visit_expr(high);
+        }
+        Expr::Like {
+            expr,
+            pattern,
+            escape,
+            ..
+        } => {
+            visitor.visit_expr(expr);
+            visitor.visit_expr(pattern);
+            if let Some(escape) = escape {
+                visitor.visit_expr(escape);
+            }
+        }
+        Expr::InList { expr, list, .. } => {
+            visitor.visit_expr(expr);
+            for value in list {
+                visitor.visit_expr(value);
+            }
+        }
+        Expr::InSubquery { expr, subquery, .. } => {
+            visitor.visit_expr(expr);
+            visitor.visit_query(subquery);
+        }
+        Expr::IsNull { expr, .. } => visitor.visit_expr(expr),
+        Expr::Exists { subquery, .. } => visitor.visit_query(subquery),
+        Expr::Case {
+            operand,
+            branches,
+            else_result,
+            ..
+        } => {
+            if let Some(operand) = operand {
+                visitor.visit_expr(operand);
+            }
+            for branch in branches {
+                visitor.visit_when_clause(branch);
+            }
+            if let Some(else_result) = else_result {
+                visitor.visit_expr(else_result);
+            }
+        }
+        Expr::Function(call) => visitor.visit_function_call(call),
+        Expr::Subquery { query, .. } => visitor.visit_query(query),
+        Expr::Error(_) => {}
+    }
+}
+
+/// Visits the children of a [`WhenClause`].
+pub fn walk_when_clause<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, when: &'ast WhenClause) {
+    visitor.visit_expr(&when.condition);
+    visitor.visit_expr(&when.result);
+}
+
+/// Visits the children of a [`FunctionCall`].
+pub fn walk_function_call<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    call: &'ast FunctionCall,
+) {
+    visitor.visit_ident(&call.name);
+    if let FunctionArguments::List(args) = &call.args {
+        for arg in args {
+            visitor.visit_expr(arg);
+        }
+    }
+}
+
+/// Visits the children of an [`ObjectName`].
+pub fn walk_object_name<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, name: &'ast ObjectName) {
+    for part in &name.parts {
+        visitor.visit_ident(part);
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::Visitor;
+    use crate::lexer::{Keyword, Lexer, TokenKind};
+    use crate::parser::{Ident, Literal, Statement};
+    use crate::Script;
+
+    /// Statements with an identifier in every place one can appear, including subqueries in
+    /// `FROM`, `IN` and `EXISTS`, `CASE` arms and join conditions.
+    pub(in crate::parser) const SOURCES: [&str; 8] = [
+        "WITH c1 (k1) AS (SELECT a1 FROM t1) \
+         SELECT a2, CASE a3 WHEN a4 THEN (SELECT a5 FROM t2) ELSE a6 END, t3.*, -a7 AS x1 \
+         FROM (SELECT a8 FROM t4) AS d1 JOIN t5 ON a9 = (SELECT a10 FROM t6) \
+         LEFT JOIN (t7 NATURAL JOIN t8) USING (a11) \
+         WHERE a12 IN (SELECT a13 FROM t9) AND EXISTS (SELECT a14 FROM t10) \
+         AND a15 NOT IN (a16, 1) AND a17 BETWEEN a18 AND 'b' AND a19 LIKE a20 \
+         AND a21 IS NULL AND f1(a22, 2.5) = 3 AND CASE WHEN a23 THEN a24 END \
+         GROUP BY a25 HAVING f2(DISTINCT a26) > 0 ORDER BY a27",
+        "(SELECT a1 FROM t1) UNION ALL SELECT a2 FROM t2 WHERE a3 = TRUE OR a4 || 'x' = 'y'",
+        "CREATE TABLE s1.t1 (c1 INTEGER DEFAULT 1 NOT NULL, c2 VARCHAR REFERENCES t2 (c3), \
+         PRIMARY KEY (c1), FOREIGN KEY (c2) REFERENCES s2.t3 (c4))",
+        "DROP TABLE s1.t1",
+        "INSERT INTO t1 (c1, c2) VALUES (1, 'x'), (c3, 0x1F)",
+        "INSERT INTO t1 SELECT c1 FROM t2 WHERE c2 IN (SELECT c3 FROM t3)",
+        "UPDATE t1 SET c1 = c2 + 1e3 WHERE EXISTS (SELECT c3 FROM t2 WHERE c4 = FALSE)",
+        "DELETE FROM t1 WHERE c1 IN (SELECT c2 FROM t2 JOIN t3 ON c3 = c4)",
+    ];
+
+    /// The statement of `source`, which must parse cleanly.
+    pub(in crate::parser) fn parse(source: &str) -> Statement {
+        let mut script = Script::parse(source);
+        assert!(script.errors.is_empty(), "{source}: {:?}", script.errors);
+        script.statements.remove(0).statement
+    }
+
+    /// The identifiers of `source` in sorted order.
+    pub(in crate::parser) fn identifiers(source: &str) -> Vec<&str> {
+        let mut identifiers: Vec<&str> = Lexer::new(source)
+            .filter(|token| token.kind == TokenKind::Identifier)
+            .map(|token| token.text(source))
+            .collect();
+        identifiers.sort();
+        identifiers
+    }
+
+    /// The number of literals in `source`, leaving out `NULL` as it is also part of `IS NULL`.
+    fn literals(source: &str) -> usize {
+        Lexer::new(source)
+            .filter(|token| {
+                matches!(
+                    token.kind,
+                    TokenKind::SingleQuotedStringLiteral
+                        | TokenKind::IntegerLiteral
+                        | TokenKind::DecimalLiteral
+                        | TokenKind::HexadecimalLiteral
+                        | TokenKind::ExponentLiteral
+                        | TokenKind::Keyword(Keyword::True | Keyword::False)
+                )
+            })
+            .count()
+    }
+
+    #[derive(Default)]
+    struct Leaves<'ast> {
+        idents: Vec<&'ast str>,
+        literals: usize,
+    }
+
+    impl<'ast> Visitor<'ast> for Leaves<'ast> {
+        fn visit_ident(&mut self, ident: &'ast Ident) {
+            self.idents.push(&ident.value);
+        }
+
+        fn visit_literal(&mut self, _literal: &'ast Literal) {
+            self.literals += 1;
+        }
+    }
+
+    #[test]
+    fn every_identifier_and_literal_is_visited() {
+        for source in SOURCES {
+            let statement = parse(source);
+            let mut leaves = Leaves::default();
+            leaves.visit_statement(&statement);
+            leaves.idents.sort();
+            assert_eq!(leaves.idents, identifiers(source), "{source}");
+            assert_eq!(leaves.literals, literals(source), "{source}");
+        }
+    }
+}
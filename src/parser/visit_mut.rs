@@ -0,0 +1,533 @@
+//! In-place traversal of the AST, the mutable counterpart of [`super::visit`].
+//!
+//! Each `visit_*_mut` method defaults to the matching `walk_*_mut` function, which visits
+//! the children of the node.
+
+use super::{
+    Assignment, ColumnConstraint, ColumnConstraintKind, ColumnDefinition, CommonTableExpression,
+    CreateTable, DataType, Delete, DropTable, Expr, ForeignKeyTarget, FunctionArguments,
+    FunctionCall, Ident, Insert, InsertSource, Join, JoinConstraint, Limit, Literal, ObjectName,
+    OrderByItem, Query, Select, SelectItem, SetExpr, Statement, TableConstraint,
+    TableConstraintKind, TableReference, Update, WhenClause,
+};
+
+/// A visitor that can modify the AST in place.
+pub trait VisitorMut {
+    /// Called for every [`Statement`]. Defaults to [`walk_statement_mut`].
+    fn visit_statement_mut(&mut self, statement: &mut Statement) {
+        walk_statement_mut(self, statement);
+    }
+
+    /// Called for every [`CreateTable`]. Defaults to [`walk_create_table_mut`].
+    fn visit_create_table_mut(&mut self, create: &mut CreateTable) {
+        walk_create_table_mut(self, create);
+    }
+
+    /// Called for every [`ColumnDefinition`]. Defaults to [`walk_column_definition_mut`].
+    fn visit_column_definition_mut(&mut self, column: &mut ColumnDefinition) {
+        walk_column_definition_mut(self, column);
+    }
+
+    /// Called for every [`DataType`]. Defaults to [`walk_data_type_mut`].
+    fn visit_data_type_mut(&mut self, data_type: &mut DataType) {
+        walk_data_type_mut(self, data_type);
+    }
+
+    /// Called for every [`ColumnConstraint`]. Defaults to [`walk_column_constraint_mut`].
+    fn visit_column_constraint_mut(&mut self, constraint: &mut ColumnConstraint) {
+        walk_column_constraint_mut(self, constraint);
+    }
+
+    /// Called for every [`TableConstraint`]. Defaults to [`walk_table_constraint_mut`].
+    fn visit_table_constraint_mut(&mut self, constraint: &mut TableConstraint) {
+        walk_table_constraint_mut(self, constraint);
+    }
+
+    /// Called for every [`ForeignKeyTarget`]. Defaults to [`walk_foreign_key_target_mut`].
+    fn visit_foreign_key_target_mut(&mut self, target: &mut ForeignKeyTarget) {
+        walk_foreign_key_target_mut(self, target);
+    }
+
+    /// Called for every [`DropTable`]. Defaults to [`walk_drop_table_mut`].
+    fn visit_drop_table_mut(&mut self, drop: &mut DropTable) {
+        walk_drop_table_mut(self, drop);
+    }
+
+    /// Called for every [`Insert`]. Defaults to [`walk_insert_mut`].
+    fn visit_insert_mut(&mut self, insert: &mut Insert) {
+        walk_insert_mut(self, insert);
+    }
+
+    /// Called for every [`Update`]. Defaults to [`walk_update_mut`].
+    fn visit_update_mut(&mut self, update: &mut Update) {
+        walk_update_mut(self, update);
+    }
+
+    /// Called for every [`Assignment`]. Defaults to [`walk_assignment_mut`].
+    fn visit_assignment_mut(&mut self, assignment: &mut Assignment) {
+        walk_assignment_mut(self, assignment);
+    }
+
+    /// Called for every [`Delete`]. Defaults to [`walk_delete_mut`].
+    fn visit_delete_mut(&mut self, delete: &mut Delete) {
+        walk_delete_mut(self, delete);
+    }
+
+    /// Called for every [`Query`]. Defaults to [`walk_query_mut`].
+    fn visit_query_mut(&mut self, query: &mut Query) {
+        walk_query_mut(self, query);
+    }
+
+    /// Called for every [`CommonTableExpression`]. Defaults to [`walk_common_table_expression_mut`].
+    fn visit_common_table_expression_mut(&mut self, cte: &mut CommonTableExpression) {
+        walk_common_table_expression_mut(self, cte);
+    }
+
+    /// Called for every [`SetExpr`]. Defaults to [`walk_set_expr_mut`].
+    fn visit_set_expr_mut(&mut self, set_expr: &mut SetExpr) {
+        walk_set_expr_mut(self, set_expr);
+    }
+
+    /// Called for every [`Select`]. Defaults to [`walk_select_mut`].
+    fn visit_select_mut(&mut self, select: &mut Select) {
+        walk_select_mut(self, select);
+    }
+
+    /// Called for every [`Limit`], which has no children to walk.
+    fn visit_limit_mut(&mut self, _limit: &mut Limit) {}
+
+    /// Called for every [`SelectItem`]. Defaults to [`walk_select_item_mut`].
+    fn visit_select_item_mut(&mut self, item: &mut SelectItem) {
+        walk_select_item_mut(self, item);
+    }
+
+    /// Called for every [`TableReference`]. Defaults to [`walk_table_reference_mut`].
+    fn visit_table_reference_mut(&mut self, table: &mut TableReference) {
+        walk_table_reference_mut(self, table);
+    }
+
+    /// Called for every [`Join`]. Defaults to [`walk_join_mut`].
+    fn visit_join_mut(&mut self, join: &mut Join) {
+        walk_join_mut(self, join);
+    }
+
+    /// Called for every [`JoinConstraint`]. Defaults to [`walk_join_constraint_mut`].
+    fn visit_join_constraint_mut(&mut self, constraint: &mut JoinConstraint) {
+        walk_join_constraint_mut(self, constraint);
+    }
+
+    /// Called for every [`OrderByItem`]. Defaults to [`walk_order_by_item_mut`].
+    fn visit_order_by_item_mut(&mut self, item: &mut OrderByItem) {
+        walk_order_by_item_mut(self, item);
+    }
+
+    /// Called for every [`Expr`]. Defaults to [`walk_expr_mut`].
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        walk_expr_mut(self, expr);
+    }
+
+    /// Called for every [`WhenClause`]. Defaults to [`walk_when_clause_mut`].
+    fn visit_when_clause_mut(&mut self, when: &mut WhenClause) {
+        walk_when_clause_mut(self, when);
+    }
+
+    /// Called for every [`FunctionCall`]. Defaults to [`walk_function_call_mut`].
+    fn visit_function_call_mut(&mut self, call: &mut FunctionCall) {
+        walk_function_call_mut(self, call);
+    }
+
+    /// Called for every [`Literal`], which has no children to walk.
+    fn visit_literal_mut(&mut self, _literal: &mut Literal) {}
+
+    /// Called for every [`ObjectName`]. Defaults to [`walk_object_name_mut`].
+    fn visit_object_name_mut(&mut self, name: &mut ObjectName) {
+        walk_object_name_mut(self, name);
+    }
+
+    /// Called for every [`Ident`], which has no children to walk.
+    fn visit_ident_mut(&mut self, _ident: &mut Ident) {}
+}
+
+/// Visits the children of a [`Statement`].
+pub fn walk_statement_mut<V: VisitorMut + ?Sized>(visitor: &mut V, statement: &mut Statement) {
+    match statement {
+        Statement::Query(query) => visitor.visit_query_mut(query),
+        Statement::CreateTable(create) => visitor.visit_create_table_mut(create),
+        Statement::DropTable(drop) => visitor.visit_drop_table_mut(drop),
+        Statement::Insert(insert) => visitor.visit_insert_mut(insert),
+        Statement::Update(update) => visitor.visit_update_mut(update),
+        Statement::Delete(delete) => visitor.visit_delete_mut(delete),
+        Statement::Transaction { .. } | Statement::Error(_) => {}
+    }
+}
+
+/// Visits the children of a [`CreateTable`].
+pub fn walk_create_table_mut<V: VisitorMut + ?Sized>(visitor: &mut V, create: &mut CreateTable) {
+    visitor.visit_object_name_mut(&mut create.name);
+    for column in &mut create.columns {
+        visitor.visit_column_definition_mut(column);
+    }
+    for constraint in &mut create.constraints {
+        visitor.visit_table_constraint_mut(constraint);
+    }
+}
+
+/// Visits the children of a [`ColumnDefinition`].
+pub fn walk_column_definition_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    column: &mut ColumnDefinition,
+) {
+    visitor.visit_ident_mut(&mut column.name);
+    visitor.visit_data_type_mut(&mut column.data_type);
+    for constraint in &mut column.constraints {
+        visitor.visit_column_constraint_mut(constraint);
+    }
+}
+
+/// Visits the children of a [`DataType`].
+pub fn walk_data_type_mut<V: VisitorMut + ?Sized>(visitor: &mut V, data_type: &mut DataType) {
+    if let DataType::Custom(name) = data_type {
+        visitor.visit_ident_mut(name);
+    }
+}
+
+/// Visits the children of a [`ColumnConstraint`].
+pub fn walk_column_constraint_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    constraint: &mut ColumnConstraint,
+) {
+    if let Some(name) = &mut constraint.name {
+        visitor.visit_ident_mut(name);
+    }
+    match &mut constraint.kind {
+        ColumnConstraintKind::NotNull
+        | ColumnConstraintKind::Null
+        | ColumnConstraintKind::PrimaryKey
+        | ColumnConstraintKind::Unique => {}
+        ColumnConstraintKind::Default(expr) | ColumnConstraintKind::Check(expr) => {
+            visitor.visit_expr_mut(expr);
+        }
+        ColumnConstraintKind::References(target) => visitor.visit_foreign_key_target_mut(target),
+    }
+}
+
+/// Visits the children of a [`TableConstraint`].
+pub fn walk_table_constraint_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    constraint: &mut TableConstraint,
+) {
+    if let Some(name) = &mut constraint.name {
+        visitor.visit_ident_mut(name);
+    }
+    match &mut constraint.kind {
+        TableConstraintKind::PrimaryKey(columns) | TableConstraintKind::Unique(columns) => {
+            for column in columns {
+                visitor.visit_ident_mut(column);
+            }
+        }
+        TableConstraintKind::ForeignKey { columns, target } => {
+            for column in columns {
+                visitor.visit_ident_mut(column);
+            }
+            visitor.visit_foreign_key_target_mut(target);
+        }
+        TableConstraintKind::Check(expr) => visitor.visit_expr_mut(expr),
+    }
+}
+
+/// Visits the children of a [`ForeignKeyTarget`].
+pub fn walk_foreign_key_target_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    target: &mut ForeignKeyTarget,
+) {
+    visitor.visit_object_name_mut(&mut target.table);
+    for column in &mut target.columns {
+        visitor.visit_ident_mut(column);
+    }
+}
+
+/// Visits the children of a [`DropTable`].
+pub fn walk_drop_table_mut<V: VisitorMut + ?Sized>(visitor: &mut V, drop: &mut DropTable) {
+    for name in &mut drop.names {
+        visitor.visit_object_name_mut(name);
+    }
+}
+
+/// Visits the children of an [`Insert`].
+pub fn walk_insert_mut<V: VisitorMut + ?Sized>(visitor: &mut V, insert: &mut Insert) {
+    visitor.visit_object_name_mut(&mut insert.table);
+    for column in &mut insert.columns {
+        visitor.visit_ident_mut(column);
+    }
+    match &mut insert.source {
+        InsertSource::Values(rows) => {
+            for value in rows.iter_mut().flatten() {
+                visitor.visit_expr_mut(value);
+            }
+        }
+        InsertSource::Query(query) => visitor.visit_query_mut(query),
+    }
+}
+
+/// Visits the children of an [`Update`].
+pub fn walk_update_mut<V: VisitorMut + ?Sized>(visitor: &mut V, update: &mut Update) {
+    visitor.visit_object_name_mut(&mut update.table);
+    for assignment in &mut update.assignments {
+        visitor.visit_assignment_mut(assignment);
+    }
+    if let Some(condition) = &mut update.where_clause {
+        visitor.visit_expr_mut(condition);
+    }
+}
+
+/// Visits the children of an [`Assignment`].
+pub fn walk_assignment_mut<V: VisitorMut + ?Sized>(visitor: &mut V, assignment: &mut Assignment) {
+    visitor.visit_ident_mut(&mut assignment.column);
+    visitor.visit_expr_mut(&mut assignment.value);
+}
+
+/// Visits the children of a [`Delete`].
+pub fn walk_delete_mut<V: VisitorMut + ?Sized>(visitor: &mut V, delete: &mut Delete) {
+    visitor.visit_object_name_mut(&mut delete.table);
+    if let Some(condition) = &mut delete.where_clause {
+        visitor.visit_expr_mut(condition);
+    }
+}
+
+/// Visits the children of a [`Query`].
+pub fn walk_query_mut<V: VisitorMut + ?Sized>(visitor: &mut V, query: &mut Query) {
+    for cte in &mut query.with {
+        visitor.visit_common_table_expression_mut(cte);
+    }
+    visitor.visit_set_expr_mut(&mut query.body);
+    for item in &mut query.order_by {
+        visitor.visit_order_by_item_mut(item);
+    }
+    if let Some(offset) = &mut query.offset {
+        visitor.visit_limit_mut(offset);
+    }
+}
+
+/// Visits the children of a [`CommonTableExpression`].
+pub fn walk_common_table_expression_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    cte: &mut CommonTableExpression,
+) {
+    visitor.visit_ident_mut(&mut cte.name);
+    for column in &mut cte.columns {
+        visitor.visit_ident_mut(column);
+    }
+    visitor.visit_query_mut(&mut cte.query);
+}
+
+/// Visits the children of a [`SetExpr`].
+pub fn walk_set_expr_mut<V: VisitorMut + ?Sized>(visitor: &mut V, set_expr: &mut SetExpr) {
+    match set_expr {
+        SetExpr::Select(select) => visitor.visit_select_mut(select),
+        SetExpr::Query(query) => visitor.visit_query_mut(query),
+        SetExpr::SetOperation { left, right, .. } => {
+            visitor.visit_set_expr_mut(left);
+            visitor.visit_set_expr_mut(right);
+        }
+    }
+}
+
+/// Visits the children of a [`Select`].
+pub fn walk_select_mut<V: VisitorMut + ?Sized>(visitor: &mut V, select: &mut Select) {
+    if let Some(top) = &mut select.top {
+        visitor.visit_limit_mut(top);
+    }
+    for item in &mut select.items {
+        visitor.visit_select_item_mut(item);
+    }
+    for table in &mut select.from {
+        visitor.visit_table_reference_mut(table);
+    }
+    if let Some(condition) = &mut select.where_clause {
+        visitor.visit_expr_mut(condition);
+    }
+    for expr in &mut select.group_by {
+        visitor.visit_expr_mut(expr);
+    }
+    if let Some(condition) = &mut select.having {
+        visitor.visit_expr_mut(condition);
+    }
+}
+
+/// Visits the children of a [`SelectItem`].
+pub fn walk_select_item_mut<V: VisitorMut + ?Sized>(visitor: &mut V, item: &mut SelectItem) {
+    match item {
+        SelectItem::Wildcard(_) | SelectItem::Error(_) => {}
+        SelectItem::QualifiedWildcard(name, _) => visitor.visit_object_name_mut(name),
+        SelectItem::Expression { expr, alias, .. } => {
+            visitor.visit_expr_mut(expr);
+            if let Some(alias) = alias {
+                visitor.visit_ident_mut(alias);
+            }
+        }
+    }
+}
+
+/// Visits the children of a [`TableReference`].
+pub fn walk_table_reference_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    table: &mut TableReference,
+) {
+    match table {
+        TableReference::Table { name, alias, .. } => {
+            visitor.visit_object_name_mut(name);
+            if let Some(alias) = alias {
+                visitor.visit_ident_mut(alias);
+            }
+        }
+        TableReference::Derived { query, alias, .. } => {
+            visitor.visit_query_mut(query);
+            visitor.visit_ident_mut(alias);
+        }
+        TableReference::Join(join) => visitor.visit_join_mut(join),
+        TableReference::Error(_) => {}
+    }
+}
+
+/// Visits the children of a [`Join`].
+pub fn walk_join_mut<V: VisitorMut + ?Sized>(visitor: &mut V, join: &mut Join) {
+    visitor.visit_table_reference_mut(&mut join.left);
+    visitor.visit_table_reference_mut(&mut join.right);
+    visitor.visit_join_constraint_mut(&mut join.constraint);
+}
+
+/// Visits the children of a [`JoinConstraint`].
+pub fn walk_join_constraint_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    constraint: &mut JoinConstraint,
+) {
+    match constraint {
+        JoinConstraint::On(condition) => visitor.visit_expr_mut(condition),
+        JoinConstraint::Using(columns) => {
+            for column in columns {
+                visitor.visit_ident_mut(column);
+            }
+        }
+        JoinConstraint::Natural | JoinConstraint::None => {}
+    }
+}
+
+/// Visits the children of an [`OrderByItem`].
+pub fn walk_order_by_item_mut<V: VisitorMut + ?Sized>(visitor: &mut V, item: &mut OrderByItem) {
+    visitor.visit_expr_mut(&mut item.expr);
+}
+
+/// Visits the children of an [`Expr`].
+pub fn walk_expr_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut Expr) {
+    match expr {
+        Expr::Column(name) => visitor.visit_object_name_mut(name),
+        Expr::Literal(literal) => visitor.visit_literal_mut(literal),
+        Expr::Unary { operand, .. } => visitor.visit_expr_mut(operand),
+        Expr::Binary { left, right, .. } => {
+            visitor.visit_expr_mut(left);
+            visitor.visit_expr_mut(right);
+        }
+        Expr::Between {
+            expr, low, high, ..
+        } => {
+            visitor.visit_expr_mut(expr);
+            visitor.visit_expr_mut(low);
+            visitor.visit_expr_mut(high);
+        }
+        Expr::Like {
+            expr,
+            pattern,
+            escape,
+            ..
+        } => {
+            visitor.visit_expr_mut(expr);
+            visitor.visit_expr_mut(pattern);
+            if let Some(escape) = escape {
+                visitor.visit_expr_mut(escape);
+            }
+        }
+        Expr::InList { expr, list, .. } => {
+            visitor.visit_expr_mut(expr);
+            for value in list {
+                visitor.visit_expr_mut(value);
+            }
+        }
+        Expr::InSubquery { expr, subquery, .. } => {
+            visitor.visit_expr_mut(expr);
+            visitor.visit_query_mut(subquery);
+        }
+        Expr::IsNull { expr, .. } => visitor.visit_expr_mut(expr),
+        Expr::Exists { subquery, .. } => visitor.visit_query_mut(subquery),
+        Expr::Case {
+            operand,
+            branches,
+            else_result,
+            ..
+        } => {
+            if let Some(operand) = operand {
+                visitor.visit_expr_mut(operand);
+            }
+            for branch in branches {
+                visitor.visit_when_clause_mut(branch);
+            }
+            if let Some(else_result) = else_result {
+                visitor.visit_expr_mut(else_result);
+            }
+        }
+        Expr::Function(call) => visitor.visit_function_call_mut(call),
+        Expr::Subquery { query, .. } => visitor.visit_query_mut(query),
+        Expr::Error(_) => {}
+    }
+}
+
+/// Visits the children of a [`WhenClause`].
+pub fn walk_when_clause_mut<V: VisitorMut + ?Sized>(visitor: &mut V, when: &mut WhenClause) {
+    visitor.visit_expr_mut(&mut when.condition);
+    visitor.visit_expr_mut(&mut when.result);
+}
+
+/// Visits the children of a [`FunctionCall`].
+pub fn walk_function_call_mut<V: VisitorMut + ?Sized>(visitor: &mut V, call: &mut FunctionCall) {
+    visitor.visit_ident_mut(&mut call.name);
+    if let FunctionArguments::List(args) = &mut call.args {
+        for arg in args {
+            visitor.visit_expr_mut(arg);
+        }
+    }
+}
+
+/// Visits the children of an [`ObjectName`].
+pub fn walk_object_name_mut<V: VisitorMut + ?Sized>(visitor: &mut V, name: &mut ObjectName) {
+    for part in &mut name.parts {
+        visitor.visit_ident_mut(part);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VisitorMut;
+    use crate::parser::visit::tests::{identifiers, parse, SOURCES};
+    use crate::parser::Ident;
+
+    struct Rename;
+
+    impl VisitorMut for Rename {
+        fn visit_ident_mut(&mut self, ident: &mut Ident) {
+            ident.value.insert_str(0, "x_");
+        }
+    }
+
+    #[test]
+    fn every_identifier_is_visited() {
+        for source in SOURCES {
+            let mut statement = parse(source);
+            Rename.visit_statement_mut(&mut statement);
+            let printed = statement.to_string();
+            let renamed: Vec<String> = identifiers(source)
+                .iter()
+                .map(|identifier| format!("x_{identifier}"))
+                .collect();
+            assert_eq!(identifiers(&printed), renamed, "{printed}");
+        }
+    }
+}
@@ -0,0 +1,441 @@
+//! An interactive shell for running statements, used by `daoql repl`. Needs the `repl`
+//! feature.
+//!
+//! Input is read until it ends with `;`, so a statement can span several lines, and is
+//! highlighted as it is typed. Tab completes keywords and the names of tables and columns.
+//! Lines starting with `.` are commands to the shell rather than ADQL:
+//!
+//! ```text
+//! .tables           List the tables in memory and in the data directory
+//! .schema [table]   Print the definition of a table, or of every table
+//! .mode table|csv   Print query results as aligned tables or as CSV
+//! .help             List the commands
+//! .quit             Leave the shell, as does Ctrl-D
+//! ```
+
+use std::borrow::Cow;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::{CmdKind, Highlighter};
+use rustyline::hint::Hinter;
+use rustyline::history::FileHistory;
+use rustyline::validate::{ValidationContext, ValidationResult, Validator};
+use rustyline::{Context, Editor, Helper};
+
+use crate::diagnostics::{Diagnostic, Renderer};
+use crate::engine::{Database, Outcome, Table};
+use crate::lexer::{Keyword, Lexer, Span, TokenKind};
+use crate::parser::{Ident, ObjectName, Script};
+
+const HELP: &str = "\
+.tables           List the tables in memory and in the data directory
+.schema [table]   Print the definition of a table, or of every table
+.mode table|csv   Print query results as aligned tables or as CSV
+.help             List the commands
+.quit             Leave the shell, as does Ctrl-D
+
+Statements end with `;` and can span several lines.";
+
+/// The dot-commands offered as completions.
+const COMMANDS: &[&str] = &[".help", ".mode", ".quit", ".schema", ".tables"];
+
+/// How query results are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Table,
+    Csv,
+}
+
+/// An interactive session against a [`Database`].
+pub struct Repl {
+    database: Database,
+    mode: Mode,
+}
+
+impl Repl {
+    /// A session that runs statements against `database`, printing results as tables.
+    pub fn new(database: Database) -> Self {
+        Self {
+            database,
+            mode: Mode::Table,
+        }
+    }
+
+    /// Reads and runs input until the end of input or `.quit`. The history is read from
+    /// `history` at the start and written back at the end.
+    pub fn run(&mut self, history: Option<&Path>) -> rustyline::Result<()> {
+        let mut editor: Editor<AdqlHelper, FileHistory> = Editor::new()?;
+        editor.set_helper(Some(AdqlHelper::default()));
+        if let Some(history) = history {
+            // The file doesn't exist until the first session ends
+            let _ = editor.load_history(history);
+        }
+
+        loop {
+            if let Some(helper) = editor.helper_mut() {
+                helper.update_names(&self.database);
+            }
+            let prompt = if self.database.in_transaction() {
+                "daoql*> "
+            } else {
+                "daoql> "
+            };
+            let input = match editor.readline(prompt) {
+                Ok(input) => input,
+                Err(ReadlineError::Interrupted) => continue,
+                Err(ReadlineError::Eof) => break,
+                Err(error) => return Err(error),
+            };
+            if input.trim().is_empty() {
+                continue;
+            }
+            editor.add_history_entry(input.as_str())?;
+            match input.trim().strip_prefix('.') {
+                Some(command) => {
+                    if !self.command(command) {
+                        break;
+                    }
+                }
+                None => self.execute(&input),
+            }
+        }
+
+        if let Some(history) = history {
+            editor.save_history(history)?;
+        }
+        Ok(())
+    }
+
+    /// Runs a dot-command, given without the `.`. Returns whether to carry on reading input.
+    fn command(&mut self, command: &str) -> bool {
+        let mut words = command.split_whitespace();
+        match (words.next(), words.next(), words.next()) {
+            (Some("quit" | "exit"), None, _) => return false,
+            (Some("help"), None, _) => println!("{HELP}"),
+            (Some("tables"), None, _) => {
+                for name in self.database.catalogue() {
+                    println!("{name}");
+                }
+            }
+            (Some("schema"), name, None) => self.schema(name),
+            (Some("mode"), None, _) => match self.mode {
+                Mode::Table => println!("table"),
+                Mode::Csv => println!("csv"),
+            },
+            (Some("mode"), Some("table"), None) => self.mode = Mode::Table,
+            (Some("mode"), Some("csv"), None) => self.mode = Mode::Csv,
+            _ => eprintln!("Unknown command `.{command}`. Enter `.help` for the list of commands."),
+        }
+        true
+    }
+
+    /// `.schema [table]` prints the table as `CREATE TABLE`. Columns read from a CSV file
+    /// have no type.
+    fn schema(&mut self, name: Option<&str>) {
+        let names = match name {
+            Some(name) => vec![name.to_string()],
+            None => self.database.catalogue(),
+        };
+        for name in names {
+            let object_name = ObjectName {
+                parts: name
+                    .split('.')
+                    .map(|part| Ident {
+                        value: part.to_string(),
+                        quoted: false,
+                        span: Span::default(),
+                    })
+                    .collect(),
+                span: Span::default(),
+            };
+            match self.database.table(&object_name) {
+                Ok(Some(table)) => println!("{}", create_table(table)),
+                Ok(None) => eprintln!("Table `{name}` does not exist."),
+                Err(error) => eprintln!("{error}"),
+            }
+        }
+    }
+
+    /// Parses and runs `input`, stopping at the first error.
+    fn execute(&mut self, input: &str) {
+        let renderer = Renderer::new("<input>", input, io::stderr().is_terminal());
+        let script = Script::parse(input);
+        if !script.errors.is_empty() {
+            for error in &script.errors {
+                eprintln!("{}", renderer.render(&Diagnostic::from_parse_error(error)));
+            }
+            return;
+        }
+
+        for statement in &script.statements {
+            match self.database.execute(&statement.statement) {
+                Ok(Outcome::Rows(result)) => match self.mode {
+                    Mode::Table => println!("{result}"),
+                    Mode::Csv => print!("{}", result.to_csv()),
+                },
+                Ok(Outcome::RowsAffected(count)) => {
+                    println!(
+                        "({count} {} affected)",
+                        if count == 1 { "row" } else { "rows" }
+                    );
+                }
+                Ok(Outcome::Done) => {}
+                Err(error) => {
+                    let diagnostic = Diagnostic::from_execution_error(&error);
+                    eprintln!("{}", renderer.render(&diagnostic));
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// `table` written as the statement that would create it.
+fn create_table(table: &Table) -> String {
+    let mut statement = format!("CREATE TABLE {} (", table.qualified_name());
+    for (index, column) in table.columns.iter().enumerate() {
+        let separator = if index == 0 { "" } else { "," };
+        statement.push_str(&format!("{separator}\n    {}", column.name));
+        if let Some(data_type) = &column.data_type {
+            statement.push_str(&format!(" {data_type}"));
+        }
+        if let Some(default) = &column.default {
+            statement.push_str(&format!(" DEFAULT {default}"));
+        }
+    }
+    statement.push_str("\n);");
+    statement
+}
+
+/// Highlighting, completion and multi-line input for the line editor.
+#[derive(Default)]
+struct AdqlHelper {
+    /// The names of the tables and of the columns of tables in memory, sorted.
+    names: Vec<String>,
+}
+
+impl AdqlHelper {
+    fn update_names(&mut self, database: &Database) {
+        let mut names = database.catalogue();
+        for table in database.tables() {
+            names.extend(table.columns.iter().map(|column| column.name.clone()));
+        }
+        names.sort();
+        names.dedup();
+        self.names = names;
+    }
+
+    /// The names starting with `prefix`, ignoring case.
+    fn names_starting_with(&self, prefix: &str) -> impl Iterator<Item = &String> {
+        let prefix = prefix.to_lowercase();
+        self.names
+            .iter()
+            .filter(move |name| name.to_lowercase().starts_with(&prefix))
+    }
+}
+
+impl Completer for AdqlHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let candidate = |text: &str| Pair {
+            display: text.to_string(),
+            replacement: text.to_string(),
+        };
+        let before = &line[..pos];
+        if before.starts_with('.') && !before.contains(char::is_whitespace) {
+            let commands = COMMANDS
+                .iter()
+                .filter(|command| command.starts_with(before))
+                .map(|command| candidate(command));
+            return Ok((0, commands.collect()));
+        }
+
+        let start = before
+            .char_indices()
+            .rev()
+            .find(|&(_, c)| !(c.is_alphanumeric() || c == '_' || c == '.'))
+            .map_or(0, |(index, c)| index + c.len_utf8());
+        let word = &before[start..];
+        if word.is_empty() {
+            return Ok((pos, Vec::new()));
+        }
+
+        // Keywords are completed in the case they are being typed in
+        let upper = word.to_uppercase();
+        let lower_case = word == word.to_lowercase();
+        let mut candidates: Vec<Pair> = Keyword::ALL
+            .iter()
+            .map(Keyword::as_str)
+            .filter(|keyword| keyword.starts_with(&upper))
+            .map(|keyword| match lower_case {
+                true => candidate(&keyword.to_lowercase()),
+                false => candidate(keyword),
+            })
+            .collect();
+        candidates.extend(self.names_starting_with(word).map(|name| candidate(name)));
+        if !candidates.is_empty() {
+            return Ok((start, candidates));
+        }
+
+        // A column qualified by a table, such as `s.ra`
+        match word.rfind('.') {
+            Some(dot) => {
+                let names = self.names_starting_with(&word[dot + 1..]);
+                Ok((start + dot + 1, names.map(|name| candidate(name)).collect()))
+            }
+            None => Ok((start, Vec::new())),
+        }
+    }
+}
+
+impl Highlighter for AdqlHelper {
+    fn highlight<'l>(&self, line: &'l str, _pos: usize) -> Cow<'l, str> {
+        if line.trim_start().starts_with('.') {
+            return Cow::Borrowed(line);
+        }
+        let mut highlighted_text = String::with_capacity(line.len());
+        for token in Lexer::lossless(line) {
+            highlighted_text.push_str(token.begin_highlight());
+            highlighted_text.push_str(token.text(line));
+            highlighted_text.push_str(token.end_highlight());
+        }
+        Cow::Owned(highlighted_text)
+    }
+
+    fn highlight_char(&self, _line: &str, _pos: usize, _kind: CmdKind) -> bool {
+        // Any edit can change how the rest of the line lexes, such as an opening quote
+        true
+    }
+}
+
+impl Validator for AdqlHelper {
+    fn validate(&self, context: &mut ValidationContext) -> rustyline::Result<ValidationResult> {
+        if is_complete(context.input()) {
+            Ok(ValidationResult::Valid(None))
+        } else {
+            Ok(ValidationResult::Incomplete)
+        }
+    }
+}
+
+/// Whether `input` is ready to run: a command, a blank line or statements ending with `;`.
+fn is_complete(input: &str) -> bool {
+    let input = input.trim_start();
+    if input.is_empty() || input.starts_with('.') {
+        return true;
+    }
+    // A `;` inside an unterminated string or comment is part of an error token
+    let last = Lexer::new(input)
+        .filter(|token| !token.kind.is_trivia())
+        .last();
+    last.is_some_and(|token| token.kind == TokenKind::Semicolon)
+}
+
+impl Hinter for AdqlHelper {
+    type Hint = String;
+}
+
+impl Helper for AdqlHelper {}
+
+#[cfg(test)]
+mod tests {
+    use rustyline::completion::Completer;
+    use rustyline::history::MemHistory;
+    use rustyline::Context;
+
+    use super::{is_complete, AdqlHelper};
+    use crate::engine::Database;
+    use crate::Script;
+
+    fn helper() -> AdqlHelper {
+        let mut database = Database::new();
+        let script = Script::parse(
+            "CREATE TABLE stars (source_id INTEGER, ra REAL, dec REAL, radius REAL);
+             CREATE TABLE rares (id INTEGER);",
+        );
+        for statement in &script.statements {
+            database.execute(&statement.statement).unwrap();
+        }
+        let mut helper = AdqlHelper::default();
+        helper.update_names(&database);
+        helper
+    }
+
+    /// Where the completed word starts in `line`, which is completed at its end, and the
+    /// replacements offered.
+    fn complete(line: &str) -> (usize, Vec<String>) {
+        let history = MemHistory::new();
+        let (start, candidates) = helper()
+            .complete(line, line.len(), &Context::new(&history))
+            .unwrap();
+        let replacements = candidates
+            .into_iter()
+            .map(|candidate| candidate.replacement)
+            .collect();
+        (start, replacements)
+    }
+
+    #[test]
+    fn keywords_complete_in_the_case_they_are_typed() {
+        assert_eq!(complete("SEL"), (0, vec!["SELECT".to_string()]));
+        assert_eq!(complete("sel"), (0, vec!["select".to_string()]));
+        assert_eq!(complete("SELECT a FR"), (9, vec!["FROM".to_string()]));
+    }
+
+    #[test]
+    fn names_complete_alongside_keywords() {
+        let (start, replacements) = complete("SELECT * FROM ra");
+        assert_eq!(start, 14);
+        assert_eq!(replacements, ["radians", "rand", "ra", "radius", "rares"]);
+        assert_eq!(complete("SELECT sou"), (7, vec!["source_id".to_string()]));
+        assert_eq!(complete("SELECT x FROM "), (14, Vec::new()));
+    }
+
+    #[test]
+    fn qualified_columns_complete_after_the_dot() {
+        assert_eq!(complete("SELECT s.de"), (9, vec!["dec".to_string()]));
+        assert_eq!(
+            complete("SELECT s.ra"),
+            (
+                9,
+                vec!["ra".to_string(), "radius".to_string(), "rares".to_string()]
+            )
+        );
+    }
+
+    #[test]
+    fn commands_complete_at_the_start_of_the_line() {
+        assert_eq!(complete(".s"), (0, vec![".schema".to_string()]));
+        assert_eq!(complete(".").1.len(), 5);
+    }
+
+    #[test]
+    fn input_is_complete_once_it_ends_with_a_semicolon() {
+        assert!(is_complete(""));
+        assert!(is_complete("  .tables"));
+        assert!(is_complete("SELECT a\nFROM t;"));
+        assert!(is_complete("SELECT a FROM t; -- done\n"));
+        assert!(!is_complete("SELECT a\nFROM t"));
+        assert!(!is_complete("SELECT a FROM t; SELECT b"));
+    }
+
+    #[test]
+    fn semicolons_in_strings_and_comments_do_not_end_input() {
+        assert!(!is_complete("SELECT 'a;"));
+        assert!(!is_complete("SELECT 'a;\nb' FROM t"));
+        assert!(is_complete("SELECT 'a;\nb' FROM t;"));
+        assert!(!is_complete("SELECT a FROM t /* ; */"));
+        assert!(!is_complete("SELECT a FROM t /* unterminated;"));
+        assert!(!is_complete("SELECT a FROM t -- ;"));
+        assert!(!is_complete("SELECT \"a;"));
+    }
+}
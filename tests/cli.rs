@@ -0,0 +1,275 @@
+//! Runs the `daoql` binary and checks its output and exit codes.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+const SUCCESS: i32 = 0;
+const FAILURE: i32 = 1;
+const USAGE_ERROR: i32 = 2;
+
+/// A directory of files that is removed when dropped.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    /// An empty directory named after `test`, so tests running at once don't share files.
+    fn new(test: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("daoql-{}-{test}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    /// Writes `contents` to `name` inside the directory, creating any parent directories.
+    fn write(&self, name: &str, contents: &str) -> PathBuf {
+        let path = self.0.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Runs `daoql` with `args` and `stdin` as standard input.
+fn daoql(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_daoql"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn fmt_check_fails_only_for_unformatted_files() {
+    let directory = TempDir::new("fmt-check");
+    let formatted = directory.write("formatted.sql", "SELECT a\nFROM t;\n");
+    let unformatted = directory.write("unformatted.sql", "select a from t;");
+    let formatted = formatted.to_str().unwrap();
+    let unformatted = unformatted.to_str().unwrap();
+
+    let output = daoql(&["fmt", "--check", formatted], "");
+    assert_eq!(output.status.code(), Some(SUCCESS));
+    assert_eq!(stdout(&output), "");
+
+    let output = daoql(&["fmt", "--check", formatted, unformatted], "");
+    assert_eq!(output.status.code(), Some(FAILURE));
+    assert_eq!(stdout(&output), format!("{unformatted} is not formatted\n"));
+    // Checking leaves the file alone
+    assert_eq!(fs::read_to_string(unformatted).unwrap(), "select a from t;");
+}
+
+#[test]
+fn fmt_check_reports_lex_errors_and_unreadable_files() {
+    let directory = TempDir::new("fmt-check-errors");
+    let broken = directory.write("broken.sql", "SELECT 'unterminated FROM t;\n");
+    let missing = directory.path().join("missing.sql");
+
+    let output = daoql(&["fmt", "--check", broken.to_str().unwrap()], "");
+    assert_eq!(output.status.code(), Some(FAILURE));
+    assert!(
+        stderr(&output).contains("unterminated"),
+        "{}",
+        stderr(&output)
+    );
+
+    let output = daoql(&["fmt", "--check", missing.to_str().unwrap()], "");
+    assert_eq!(output.status.code(), Some(USAGE_ERROR));
+
+    let output = daoql(&["fmt", "--check"], "");
+    assert_eq!(output.status.code(), Some(USAGE_ERROR));
+}
+
+#[test]
+fn fmt_check_reads_standard_input() {
+    let output = daoql(&["fmt", "--check", "-"], "SELECT a\nFROM t;\n");
+    assert_eq!(output.status.code(), Some(SUCCESS));
+
+    let output = daoql(&["fmt", "--check", "-"], "select a from t;");
+    assert_eq!(output.status.code(), Some(FAILURE));
+}
+
+#[test]
+fn fmt_rewrites_files_and_prints_standard_input() {
+    let directory = TempDir::new("fmt");
+    let path = directory.write("query.sql", "select a from t;");
+
+    let output = daoql(&["fmt", path.to_str().unwrap()], "");
+    assert_eq!(output.status.code(), Some(SUCCESS));
+    assert_eq!(fs::read_to_string(&path).unwrap(), "SELECT a\nFROM t;\n");
+
+    let output = daoql(&["fmt", "-"], "select a from t;");
+    assert_eq!(output.status.code(), Some(SUCCESS));
+    assert_eq!(stdout(&output), "SELECT a\nFROM t;\n");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn lex_json_fails_when_a_token_is_an_error() {
+    let output = daoql(&["lex", "--json", "-"], "SELECT ra FROM gaia.source");
+    assert_eq!(output.status.code(), Some(SUCCESS));
+
+    let output = daoql(&["lex", "--json", "-"], "SELECT 'unterminated FROM t");
+    assert_eq!(output.status.code(), Some(FAILURE));
+    // The tokens are still printed, error included
+    assert!(stdout(&output).contains("Error"), "{}", stdout(&output));
+}
+
+#[test]
+fn run_prints_each_result() {
+    let directory = TempDir::new("run");
+    directory.write(
+        "gaia/source.csv",
+        "source_id,ra,dec\n1,266.4,-29.0\n2,10.7,41.3\n",
+    );
+    let script = directory.write(
+        "query.sql",
+        "SELECT source_id FROM gaia.source WHERE dec > 0;\n\
+         CREATE TABLE t (a INTEGER);\n\
+         INSERT INTO t VALUES (1), (2);\n\
+         SELECT COUNT(*) AS n FROM t;\n",
+    );
+    let data = directory.path().to_str().unwrap();
+    let script = script.to_str().unwrap();
+
+    let output = daoql(&["run", "--data", data, "--format", "csv", script], "");
+    assert_eq!(output.status.code(), Some(SUCCESS), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "source_id\n2\n\nn\n2\n");
+
+    // Tables also count the rows changed, which CSV leaves out
+    let output = daoql(&["run", "--data", data, script], "");
+    assert_eq!(output.status.code(), Some(SUCCESS), "{}", stderr(&output));
+    assert_eq!(
+        stdout(&output),
+        " source_id\n-----------\n         2\n(1 row)\n\n(2 rows affected)\n\n n\n---\n 2\n(1 row)\n"
+    );
+}
+
+#[test]
+fn run_stops_at_the_first_error() {
+    let output = daoql(
+        &["run", "--format", "csv", "-"],
+        "CREATE TABLE t (a INTEGER); INSERT INTO t VALUES (1);\n\
+         SELECT a FROM t; SELECT b FROM t; SELECT a FROM t;",
+    );
+    assert_eq!(output.status.code(), Some(FAILURE));
+    assert_eq!(stdout(&output), "a\n1\n");
+    assert!(
+        stderr(&output).contains("column `b` does not exist"),
+        "{}",
+        stderr(&output)
+    );
+}
+
+#[test]
+fn run_runs_nothing_if_the_script_has_syntax_errors() {
+    let output = daoql(
+        &["run", "--format", "csv", "-"],
+        "CREATE TABLE t (a INTEGER); SELECT a FROM t; SELECT FROM t;",
+    );
+    assert_eq!(output.status.code(), Some(FAILURE));
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn run_rejects_bad_arguments() {
+    let output = daoql(&["run", "--format", "json", "-"], "");
+    assert_eq!(output.status.code(), Some(USAGE_ERROR));
+
+    let output = daoql(&["run", "no-such-file.sql"], "");
+    assert_eq!(output.status.code(), Some(USAGE_ERROR));
+
+    let output = daoql(&["run"], "");
+    assert_eq!(output.status.code(), Some(USAGE_ERROR));
+}
+
+#[test]
+fn check_fails_if_any_file_has_errors() {
+    let directory = TempDir::new("check");
+    let valid = directory.write("valid.sql", "SELECT a FROM t;\n");
+    let invalid = directory.write("invalid.sql", "SELECT a FROM t WHERE;\n");
+    let valid = valid.to_str().unwrap();
+    let invalid = invalid.to_str().unwrap();
+
+    let output = daoql(&["check", valid], "");
+    assert_eq!(output.status.code(), Some(SUCCESS));
+    assert_eq!(stderr(&output), "");
+
+    let output = daoql(&["check", valid, invalid], "");
+    assert_eq!(output.status.code(), Some(FAILURE));
+    assert!(stderr(&output).contains(invalid), "{}", stderr(&output));
+    assert!(
+        stderr(&output).contains("expected an expression"),
+        "{}",
+        stderr(&output)
+    );
+
+    let output = daoql(&["check", "-"], "SELECT 'unterminated FROM t");
+    assert_eq!(output.status.code(), Some(FAILURE));
+}
+
+#[test]
+fn check_rejects_bad_arguments() {
+    let output = daoql(&["check"], "");
+    assert_eq!(output.status.code(), Some(USAGE_ERROR));
+
+    let output = daoql(&["check", "no-such-file.sql"], "");
+    assert_eq!(output.status.code(), Some(USAGE_ERROR));
+
+    let output = daoql(&["check", "--json", "-"], "");
+    assert_eq!(output.status.code(), Some(USAGE_ERROR));
+}
+
+#[test]
+fn unknown_commands_are_usage_errors() {
+    let output = daoql(&["frobnicate"], "");
+    assert_eq!(output.status.code(), Some(USAGE_ERROR));
+
+    let output = daoql(&[], "");
+    assert_eq!(output.status.code(), Some(USAGE_ERROR));
+
+    let output = daoql(&["help"], "");
+    assert_eq!(output.status.code(), Some(SUCCESS));
+    assert!(stdout(&output).starts_with("Usage: daoql"));
+}
+
+#[test]
+fn lex_prints_character_columns_without_comments() {
+    let output = daoql(&["lex", "-"], "SELECT \"é\", 'ü' /* note */ FROM t -- end");
+    assert_eq!(output.status.code(), Some(SUCCESS));
+    let positions: Vec<String> = stdout(&output)
+        .lines()
+        .map(|line| line.split_whitespace().next().unwrap().to_string())
+        .collect();
+    assert_eq!(positions, ["1:1", "1:8", "1:11", "1:13", "1:28", "1:33"]);
+    assert!(!stdout(&output).contains("Comment"), "{}", stdout(&output));
+
+    let output = daoql(&["lex", "-"], "SELECT 'unterminated");
+    assert_eq!(output.status.code(), Some(FAILURE));
+}